@@ -0,0 +1,128 @@
+//! Alert rules module
+// Threshold alerts such as "cpu > 90 for 10s": a process alerts once its usage has stayed above
+// the threshold for the whole duration, judged from the per-process history the graphs keep.
+// Rules come from the `alerts` config option or are typed in from the Filter/Sort menu.
+
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+
+/// What an alert rule measures.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum AlertMetric {
+    Cpu, // Percent, as in the CPU% column
+    Mem, // Resident memory in MB, like `mem` in script rules
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct AlertRule {
+    pub metric: AlertMetric,
+    pub threshold: f64,
+    pub duration: Duration,
+}
+
+impl AlertRule {
+    /// Parse "<cpu|mem> > <number> [for <N>s|<N>m]", e.g. "cpu > 90 for 10s" or "mem > 2000".
+    /// Without "for", one sample over the threshold is enough.
+    pub fn parse(text: &str) -> Result<AlertRule, String> {
+        let tokens: Vec<&str> = text.split_whitespace().collect();
+        let (metric, threshold, rest) = match tokens.as_slice() {
+            [metric, ">", threshold, rest @ ..] => (*metric, *threshold, rest),
+            _ => return Err(format!("expected '<cpu|mem> > <number> [for <N>s]', got '{}'", text.trim())),
+        };
+        let metric = match metric.to_lowercase().as_str() {
+            "cpu" => AlertMetric::Cpu,
+            "mem" => AlertMetric::Mem,
+            other => return Err(format!("unknown metric '{}' (expected cpu or mem)", other)),
+        };
+        let threshold: f64 = threshold.parse().map_err(|_| format!("'{}' is not a number", threshold))?;
+        let duration = match rest {
+            [] => Duration::ZERO,
+            ["for", duration] => parse_duration(duration).ok_or_else(|| format!("'{}' is not a duration like 10s or 2m", duration))?,
+            _ => return Err(format!("unexpected '{}' after the threshold", rest.join(" "))),
+        };
+        Ok(AlertRule { metric, threshold, duration })
+    }
+
+    /// Whether the newest samples covering `duration` are all above the threshold. `interval` is
+    /// the time between history samples.
+    pub fn sustained(&self, cpu_history: &VecDeque<f32>, mem_history: &VecDeque<u64>, interval: Duration) -> bool {
+        let needed = (self.duration.as_millis() / interval.as_millis().max(1)) as usize + 1;
+        let over: Vec<bool> = match self.metric {
+            AlertMetric::Cpu => cpu_history.iter().rev().take(needed).map(|&cpu| cpu as f64 > self.threshold).collect(),
+            AlertMetric::Mem => mem_history
+                .iter()
+                .rev()
+                .take(needed)
+                .map(|&mem| mem as f64 / (1024.0 * 1024.0) > self.threshold)
+                .collect(),
+        };
+        over.len() == needed && over.iter().all(|&over| over)
+    }
+}
+
+impl std::fmt::Display for AlertRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let metric = match self.metric {
+            AlertMetric::Cpu => "cpu",
+            AlertMetric::Mem => "mem",
+        };
+        write!(f, "{} > {}", metric, self.threshold)?;
+        if !self.duration.is_zero() {
+            write!(f, " for {}s", self.duration.as_secs())?;
+        }
+        Ok(())
+    }
+}
+
+fn parse_duration(text: &str) -> Option<Duration> {
+    if let Some(secs) = text.strip_suffix('s') {
+        return secs.parse().ok().map(Duration::from_secs);
+    }
+    if let Some(minutes) = text.strip_suffix('m') {
+        return minutes.parse::<u64>().ok().map(|m| Duration::from_secs(m * 60));
+    }
+    text.parse().ok().map(Duration::from_secs)
+}
+
+/// Alert rules and which processes are currently over them.
+#[derive(Default)]
+pub struct AlertEngine {
+    pub rules: Vec<AlertRule>,
+    pub bell: bool, // Ring the terminal bell when a process starts alerting
+    alerting: HashMap<u32, usize>, // PID -> index of the first rule it is over
+}
+
+impl AlertEngine {
+    pub fn new(rules: Vec<AlertRule>, bell: bool) -> Self {
+        Self { rules, bell, alerting: HashMap::new() }
+    }
+
+    /// Re-check every process with a history. Returns the PIDs that started alerting with the
+    /// rule they crossed; PIDs that dropped back under every rule stop alerting.
+    pub fn update<'a>(
+        &mut self,
+        histories: impl Iterator<Item = (u32, &'a VecDeque<f32>, &'a VecDeque<u64>)>,
+        interval: Duration,
+    ) -> Vec<(u32, AlertRule)> {
+        let mut alerting = HashMap::new();
+        let mut started = Vec::new();
+        for (pid, cpu, mem) in histories {
+            if let Some(index) = self.rules.iter().position(|rule| rule.sustained(cpu, mem, interval)) {
+                if !self.alerting.contains_key(&pid) {
+                    started.push((pid, self.rules[index].clone()));
+                }
+                alerting.insert(pid, index);
+            }
+        }
+        self.alerting = alerting;
+        started
+    }
+
+    pub fn is_alerting(&self, pid: u32) -> bool {
+        self.alerting.contains_key(&pid)
+    }
+
+    pub fn alerting_count(&self) -> usize {
+        self.alerting.len()
+    }
+}
@@ -0,0 +1,263 @@
+//! Command-line interface module
+// Parses arguments and implements the headless output modes: a one-shot snapshot for scripts and
+// cron jobs, and a JSON-lines stream for log pipelines.
+
+use crate::process::{ProcessInfo, ProcessManager, SORT_MODES};
+use clap::Parser;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+/// Command-line arguments. With no flags the interactive TUI is started.
+#[derive(Parser)]
+#[command(name = "linux_process_manager", about = "Interactive Linux process manager")]
+pub struct Args {
+    /// Print a single snapshot of the process list and exit instead of starting the TUI
+    #[arg(long)]
+    pub once: bool,
+
+    /// Print one JSON object per refresh (system summary and top processes) until interrupted
+    #[arg(long, conflicts_with = "once")]
+    pub stream: bool,
+
+    /// Keep rewriting this file with metrics for the node_exporter textfile collector until interrupted
+    #[arg(long, value_name = "PATH", conflicts_with_all = ["once", "stream"])]
+    pub prometheus: Option<std::path::PathBuf>,
+
+    /// Time between --stream or --prometheus samples, overriding refresh_interval_ms
+    #[arg(long, value_name = "MS")]
+    pub interval_ms: Option<u64>,
+
+    /// Sort column for --once and --stream (pid, mem, ppid, start, nice, threads, mem_percent, uptime, cpu, swap, cpu_time)
+    #[arg(long, value_parser = clap::builder::PossibleValuesParser::new(SORT_MODES))]
+    pub sort: Option<String>,
+
+    /// Sort ascending instead of descending in --once and --stream mode
+    #[arg(long)]
+    pub ascending: bool,
+
+    /// Only print the first N processes (--stream defaults to 10, --prometheus to 20)
+    #[arg(long)]
+    pub top: Option<usize>,
+
+    /// Emit JSON instead of a plain-text table
+    #[arg(long)]
+    pub json: bool,
+
+    /// Filter as FIELD=VALUE where FIELD is user (name or UID), name, pid, ppid or container (e.g. name=nginx)
+    #[arg(long, value_parser = parse_filter)]
+    pub filter: Option<(String, String)>,
+
+    /// Samples kept for the statistics graphs (one per refresh, at most every 500ms), overriding graph_history_points
+    #[arg(long, value_name = "N")]
+    pub history_points: Option<usize>,
+
+    /// Samples kept per process for the per-process graph, overriding process_history_points
+    #[arg(long, value_name = "N")]
+    pub process_history_points: Option<usize>,
+
+    /// Watch this process in the TUI, show a banner when it exits and print a summary on quit
+    #[arg(long, value_name = "PID")]
+    pub wait_for: Option<u32>,
+
+    /// Read processes and system statistics from this procfs mount instead of /proc, e.g. a
+    /// container's /proc bind-mounted on the host, or a captured copy. Signals are refused there.
+    #[arg(long, value_name = "PATH")]
+    pub proc_root: Option<std::path::PathBuf>,
+}
+
+impl Args {
+    /// The procfs mount to read: --proc-root if given, /proc otherwise.
+    pub fn proc_root(&self) -> &std::path::Path {
+        self.proc_root.as_deref().unwrap_or(std::path::Path::new(crate::process::DEFAULT_PROC_ROOT))
+    }
+}
+
+fn parse_filter(arg: &str) -> Result<(String, String), String> {
+    let (field, value) = arg
+        .split_once('=')
+        .ok_or_else(|| format!("expected FIELD=VALUE, got '{}'", arg))?;
+    match field {
+        "user" | "name" | "pid" | "ppid" | "container" => Ok((field.to_string(), value.to_string())),
+        _ => Err(format!("unknown filter field '{}' (expected user, name, pid, ppid or container)", field)),
+    }
+}
+
+/// Take one snapshot and print it to stdout.
+pub fn run_once(args: &Args) -> Result<(), Box<dyn std::error::Error>> {
+    let mut manager = ProcessManager::new(args.proc_root());
+    // CPU usage is a delta between two refreshes, so leave a sampling window first
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    manager.refresh();
+    if let Some((field, value)) = &args.filter {
+        manager.set_filter(Some(field.clone()), Some(value.clone()));
+    }
+    if let Some(mode) = &args.sort {
+        manager.set_sort(mode, args.ascending);
+    }
+
+    let processes = manager.get_processes();
+    let limit = args.top.unwrap_or(processes.len());
+    let selected: Vec<&ProcessInfo> = processes.iter().take(limit).collect();
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&selected)?);
+    } else {
+        println!("{:>8} {:<20} {:>7} {:>9} {:<12} STATUS", "PID", "NAME", "CPU%", "MEM(MB)", "USER");
+        for process in selected {
+            println!(
+                "{:>8} {:<20} {:>6.1}% {:>9} {:<12} {}",
+                process.pid,
+                process.name,
+                process.cpu_usage,
+                process.memory_usage / (1024 * 1024),
+                process.user_label(),
+                process.status.trim(),
+            );
+        }
+    }
+    Ok(())
+}
+
+// Set by the SIGINT/SIGTERM handler so the stream finishes its current line and exits cleanly
+static STOP: AtomicBool = AtomicBool::new(false);
+
+extern "C" fn request_stop(_signal: libc::c_int) {
+    STOP.store(true, Ordering::Relaxed);
+}
+
+/// Print one JSON object per sample to stdout until interrupted or the reader goes away.
+pub fn run_stream(args: &Args) -> Result<(), Box<dyn std::error::Error>> {
+    use std::io::Write;
+    let stdout = std::io::stdout();
+    sample_loop(args, None, |manager, graph_data| {
+        let processes = manager.get_processes();
+        let (memory_used, memory_total) = graph_data.memory_usage();
+        let (load1, load5, load15) = graph_data.load_average();
+        let sample = serde_json::json!({
+            "timestamp": chrono::Local::now().to_rfc3339(),
+            "cpu_percent": graph_data.cpu_usage(),
+            "memory_used_mb": memory_used,
+            "memory_total_mb": memory_total,
+            "load_average": [load1, load5, load15],
+            "process_count": processes.len(),
+            "processes": &processes[..args.top.unwrap_or(10).min(processes.len())],
+        });
+        let mut out = stdout.lock();
+        // A closed pipe (e.g. `| head`) ends the stream like an interrupt does
+        match writeln!(out, "{}", sample).and_then(|_| out.flush()) {
+            Err(e) if e.kind() == std::io::ErrorKind::BrokenPipe => Ok(false),
+            result => result.map(|_| true),
+        }
+    })
+}
+
+/// Rewrite `path` with metrics in the Prometheus text format after every sample, until
+/// interrupted. The file is replaced atomically so the node_exporter textfile collector never
+/// reads half of it.
+pub fn run_prometheus(args: &Args, path: &std::path::Path) -> Result<(), Box<dyn std::error::Error>> {
+    let mut temp = path.as_os_str().to_owned();
+    temp.push(".tmp");
+    // Without --sort, export the busiest processes
+    sample_loop(args, Some("cpu"), |manager, graph_data| {
+        std::fs::write(&temp, prometheus_text(manager.get_processes(), graph_data, args.top.unwrap_or(20)))?;
+        std::fs::rename(&temp, path)?;
+        Ok(true)
+    })
+}
+
+// System metrics plus per-process series for the first `top` processes. The rest are summed into
+// one pid="other" series so the number of series stays bounded however many processes come and go.
+fn prometheus_text(processes: &[ProcessInfo], graph_data: &crate::graph::GraphData, top: usize) -> String {
+    use std::fmt::Write;
+    const MB: u64 = 1024 * 1024;
+    let (memory_used, memory_total) = graph_data.memory_usage();
+    let (load1, load5, load15) = graph_data.load_average();
+    let mut text = String::new();
+    let mut metric = |name: &str, kind: &str, help: &str, samples: &[(String, String)]| {
+        let _ = writeln!(text, "# HELP {} {}\n# TYPE {} {}", name, help, name, kind);
+        for (labels, value) in samples {
+            let _ = writeln!(text, "{}{} {}", name, labels, value);
+        }
+    };
+    let unlabelled = |value: String| vec![(String::new(), value)];
+    metric("system_cpu_percent", "gauge", "CPU usage averaged over all cores.", &unlabelled(graph_data.cpu_usage().to_string()));
+    metric("system_memory_used_bytes", "gauge", "Memory in use.", &unlabelled((memory_used * MB).to_string()));
+    metric("system_memory_total_bytes", "gauge", "Total memory.", &unlabelled((memory_total * MB).to_string()));
+    metric("system_load_average", "gauge", "Load average over the period.", &[
+        ("{period=\"1m\"}".to_string(), load1.to_string()),
+        ("{period=\"5m\"}".to_string(), load5.to_string()),
+        ("{period=\"15m\"}".to_string(), load15.to_string()),
+    ]);
+    metric("system_processes", "gauge", "Number of processes.", &unlabelled(processes.len().to_string()));
+
+    let (listed, rest) = processes.split_at(top.min(processes.len()));
+    let labels = |p: &ProcessInfo| format!("{{pid=\"{}\",name=\"{}\"}}", p.pid, escape_label(&p.name));
+    let other = "{pid=\"other\",name=\"other\"}".to_string();
+    let mut cpu: Vec<(String, String)> = listed.iter().map(|p| (labels(p), p.cpu_usage.to_string())).collect();
+    let mut memory: Vec<(String, String)> = listed.iter().map(|p| (labels(p), p.memory_usage.to_string())).collect();
+    if !rest.is_empty() {
+        cpu.push((other.clone(), rest.iter().map(|p| p.cpu_usage).sum::<f32>().to_string()));
+        memory.push((other, rest.iter().map(|p| p.memory_usage).sum::<u64>().to_string()));
+    }
+    metric("process_cpu_percent", "gauge", "CPU usage of the process (100 = one core).", &cpu);
+    metric("process_memory_bytes", "gauge", "Resident memory of the process.", &memory);
+    text
+}
+
+// Label values are quoted; backslashes, quotes and newlines in them must be escaped
+fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+// Sample like the TUI does, the process manager for the list and GraphData for the summary, and
+// hand each sample to `emit` until interrupted or `emit` returns false
+fn sample_loop(
+    args: &Args,
+    default_sort: Option<&str>,
+    mut emit: impl FnMut(&ProcessManager, &crate::graph::GraphData) -> std::io::Result<bool>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let (config, warnings) = crate::config::load();
+    for warning in warnings {
+        eprintln!("Config: {}", warning);
+    }
+    let interval = Duration::from_millis(args.interval_ms.unwrap_or(config.refresh_interval_ms).max(10));
+    // SAFETY: the handler only stores to an atomic, which is async-signal-safe
+    unsafe {
+        libc::signal(libc::SIGINT, request_stop as *const () as libc::sighandler_t);
+        libc::signal(libc::SIGTERM, request_stop as *const () as libc::sighandler_t);
+    }
+
+    let mut manager = ProcessManager::new(args.proc_root());
+    manager.set_cpu_source(config.cpu_source);
+    if let Some((field, value)) = &args.filter {
+        manager.set_filter(Some(field.clone()), Some(value.clone()));
+    }
+    if let Some(mode) = args.sort.as_deref().or(default_sort) {
+        manager.set_sort(mode, args.ascending);
+    }
+    let mut graph_data = crate::graph::GraphData::new(args.proc_root(), 2, 2, interval.as_millis() as u64);
+    while !STOP.load(Ordering::Relaxed) {
+        sleep_unless_stopped(interval);
+        if STOP.load(Ordering::Relaxed) {
+            break;
+        }
+        manager.refresh();
+        graph_data.update(&manager);
+        if !emit(&manager, &graph_data)? {
+            break;
+        }
+    }
+    Ok(())
+}
+
+// Sleep in short steps so an interrupt doesn't wait out a long interval
+fn sleep_unless_stopped(duration: Duration) {
+    let deadline = std::time::Instant::now() + duration;
+    while !STOP.load(Ordering::Relaxed) {
+        let left = deadline.saturating_duration_since(std::time::Instant::now());
+        if left.is_zero() {
+            break;
+        }
+        std::thread::sleep(left.min(Duration::from_millis(100)));
+    }
+}
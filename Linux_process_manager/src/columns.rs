@@ -0,0 +1,235 @@
+//! Process list column module
+// Describes the columns the process list can show and which of them are visible, in what order.
+// The order and visibility come from the `columns` config option and can be edited in the UI.
+
+use crate::process::ProcessInfo;
+use crate::util::{format_bytes, MemoryUnit};
+
+/// A column of the process list.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Column {
+    Pid,
+    Name,
+    Cpu,
+    CpuTime,
+    Mem,
+    Swap,
+    Virt,
+    MemPercent,
+    Ppid,
+    Start,
+    Uptime,
+    Nice,
+    Threads,
+    User,
+    Status,
+    Cmdline,
+    Container,
+    CpuTrend,
+}
+
+impl Column {
+    /// Every column, in the default display order.
+    pub const ALL: [Column; 18] = [
+        Column::Pid,
+        Column::Name,
+        Column::Cpu,
+        Column::CpuTime,
+        Column::Mem,
+        Column::Swap,
+        Column::Virt,
+        Column::MemPercent,
+        Column::Ppid,
+        Column::Start,
+        Column::Uptime,
+        Column::Nice,
+        Column::Threads,
+        Column::User,
+        Column::Status,
+        Column::Cmdline,
+        Column::Container,
+        Column::CpuTrend,
+    ];
+
+    /// Key used for the column in the config file.
+    pub fn key(self) -> &'static str {
+        match self {
+            Column::Pid => "pid",
+            Column::Name => "name",
+            Column::Cpu => "cpu",
+            Column::CpuTime => "cpu_time",
+            Column::Mem => "mem",
+            Column::Swap => "swap",
+            Column::Virt => "virt",
+            Column::MemPercent => "mem_percent",
+            Column::Ppid => "ppid",
+            Column::Start => "start",
+            Column::Uptime => "uptime",
+            Column::Nice => "nice",
+            Column::Threads => "threads",
+            Column::User => "user",
+            Column::Status => "status",
+            Column::Cmdline => "cmdline",
+            Column::Container => "container",
+            Column::CpuTrend => "cpu_trend",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Column> {
+        Column::ALL.into_iter().find(|column| column.key() == key)
+    }
+
+    /// Header text; `cpu_of_total` is whether CPU% shows a share of the whole machine.
+    pub fn header(self, cpu_of_total: bool) -> &'static str {
+        match self {
+            Column::Pid => "PID",
+            Column::Name => "NAME",
+            Column::Cpu if cpu_of_total => "CPU% (of total)",
+            Column::Cpu => "CPU%",
+            Column::CpuTime => "TIME+",
+            Column::Mem => "MEM",
+            Column::Swap => "SWAP",
+            Column::Virt => "VIRT",
+            Column::MemPercent => "MEM%",
+            Column::Ppid => "PPID",
+            Column::Start => "START",
+            Column::Uptime => "UPTIME",
+            Column::Nice => "NICE",
+            Column::Threads => "THR",
+            Column::User => "USER",
+            Column::Status => "STATUS",
+            Column::Cmdline => "COMMAND",
+            Column::Container => "CONTAINER",
+            Column::CpuTrend => "CPU TREND",
+        }
+    }
+
+    pub fn width(self, cpu_of_total: bool) -> u16 {
+        match self {
+            Column::Pid => 8,
+            Column::Name => 20,
+            Column::Cpu if cpu_of_total => 16,
+            Column::Cpu => 8,
+            Column::CpuTime => 10,
+            Column::Mem => 10,
+            Column::Swap => 10,
+            Column::Virt => 10,
+            Column::MemPercent => 7,
+            Column::Ppid => 8,
+            Column::Start => 12,
+            Column::Uptime => 9,
+            Column::Nice => 8,
+            Column::Threads => 6,
+            Column::User => 12,
+            Column::Status => 10,
+            Column::Cmdline => 40,
+            Column::Container => 24,
+            Column::CpuTrend => 12,
+        }
+    }
+
+    /// Sort mode matching this column, if the list can be sorted by it.
+    pub fn sort_mode(self) -> Option<&'static str> {
+        match self {
+            Column::Pid => Some("pid"),
+            Column::Cpu => Some("cpu"),
+            Column::CpuTime => Some("cpu_time"),
+            Column::Mem => Some("mem"),
+            Column::Swap => Some("swap"),
+            Column::MemPercent => Some("mem_percent"),
+            Column::Ppid => Some("ppid"),
+            Column::Start => Some("start"),
+            Column::Uptime => Some("uptime"),
+            Column::Nice => Some("nice"),
+            Column::Threads => Some("threads"),
+            _ => None,
+        }
+    }
+
+    /// Text shown in this column for a process. The CPU trend needs the process's history, so
+    /// the process list draws it itself and this is empty. CPU% is divided by `cpu_divisor`: 1
+    /// shows percent of one core, like top (a busy multi-threaded process can pass 100%); the
+    /// core count shows percent of the whole machine (0-100).
+    pub fn text(self, process: &ProcessInfo, memory_unit: MemoryUnit, cpu_divisor: usize) -> String {
+        match self {
+            Column::Pid => process.pid.to_string(),
+            Column::Name => process.name.clone(),
+            Column::Cpu => format!("{:.2}%", process.cpu_usage / cpu_divisor.max(1) as f32),
+            Column::CpuTime => crate::util::format_cpu_time(process.cpu_time_secs),
+            Column::Mem => format_bytes(process.memory_usage, memory_unit),
+            Column::Swap => format_bytes(process.swap_usage, memory_unit),
+            Column::Virt => format_bytes(process.virtual_memory, memory_unit),
+            Column::MemPercent => format!("{:.1}%", process.mem_percent),
+            Column::Ppid => process.parent_pid.unwrap_or(0).to_string(),
+            Column::Start => process.start_time_str.clone(),
+            Column::Uptime => crate::util::format_duration(process.uptime_secs),
+            Column::Nice => process.nice.to_string(),
+            Column::Threads => process.thread_count.to_string(),
+            Column::User => process.user_label(),
+            Column::Status => process.status.trim().to_string(),
+            Column::Cmdline => process.cmdline.clone(),
+            Column::Container => process.container.clone().unwrap_or_else(|| "-".to_string()),
+            Column::CpuTrend => String::new(),
+        }
+    }
+}
+
+/// Ordered list of columns with their visibility.
+#[derive(Clone)]
+pub struct ColumnConfig {
+    pub columns: Vec<(Column, bool)>,
+}
+
+impl Default for ColumnConfig {
+    // Everything except the command line, which is too wide to show by default, the swap and
+    // container columns, which are empty on most systems, the virtual size and the CPU trend
+    fn default() -> Self {
+        Self {
+            columns: Column::ALL
+                .into_iter()
+                .map(|column| (column, !matches!(column, Column::Cmdline | Column::Container | Column::Swap | Column::Virt | Column::CpuTrend)))
+                .collect(),
+        }
+    }
+}
+
+impl ColumnConfig {
+    /// Build from the `columns` config option: the listed columns are shown in that order and
+    /// the rest are kept, hidden, after them. Unknown keys are skipped (the config loader warns).
+    pub fn from_keys(keys: &[String]) -> Self {
+        let mut columns: Vec<(Column, bool)> = Vec::new();
+        for column in keys.iter().filter_map(|key| Column::from_key(key)) {
+            if !columns.iter().any(|(c, _)| *c == column) {
+                columns.push((column, true));
+            }
+        }
+        for column in Column::ALL {
+            if !columns.iter().any(|(c, _)| *c == column) {
+                columns.push((column, false));
+            }
+        }
+        Self { columns }
+    }
+
+    /// Keys of the visible columns in order, as stored in the config file.
+    pub fn visible_keys(&self) -> Vec<String> {
+        self.visible().map(|column| column.key().to_string()).collect()
+    }
+
+    pub fn visible(&self) -> impl Iterator<Item = Column> + '_ {
+        self.columns.iter().filter(|(_, shown)| *shown).map(|(column, _)| *column)
+    }
+
+    pub fn toggle(&mut self, index: usize) {
+        if let Some((_, shown)) = self.columns.get_mut(index) {
+            *shown = !*shown;
+        }
+    }
+
+    /// Swap the column at `index` with its neighbour `index + 1`.
+    pub fn swap_with_next(&mut self, index: usize) {
+        if index + 1 < self.columns.len() {
+            self.columns.swap(index, index + 1);
+        }
+    }
+}
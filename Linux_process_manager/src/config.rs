@@ -0,0 +1,237 @@
+//! Configuration file module
+// Loads user defaults from ~/.config/linux_process_manager/config.toml at startup.
+// Every key is optional; a missing file or a bad value falls back to the built-in default.
+
+use crate::alerts::AlertRule;
+use crate::columns::Column;
+use crate::keymap::KeyMap;
+use crate::process::{CpuSource, CPU_SOURCES};
+use crate::theme::{Theme, THEME_NAMES};
+use crate::util::{MemoryUnit, MEMORY_UNITS};
+use serde::de::DeserializeOwned;
+use std::path::PathBuf;
+
+/// User-tunable defaults read from the config file.
+#[derive(Clone)]
+pub struct Config {
+    pub sort_mode: Option<String>,
+    pub sort_ascending: bool,
+    pub columns: Option<Vec<String>>,
+    pub refresh_interval_ms: u64,
+    pub confirm_before_kill: bool,
+    pub exit_log_capacity: usize,
+    pub kill_grace_period_ms: u64, // Wait between SIGTERM and SIGKILL for terminate-then-kill
+    pub cpu_source: CpuSource,
+    pub show_raw_errors: bool, // Append the OS error code to friendly error messages
+    pub memory_unit: MemoryUnit,
+    pub alerts: Vec<AlertRule>,
+    pub alert_bell: bool, // Ring the terminal bell when a process starts alerting
+    pub graph_history_points: usize, // Samples kept for the statistics graphs, one per refresh but at most every 500ms
+    pub process_history_points: usize, // Samples kept per process for the per-process graph and alerts
+    pub usage_warn: f32, // Usage percentages above this are colored as a warning...
+    pub usage_crit: f32, // ...and above this as critical
+    pub keymap: KeyMap,
+    pub theme: Theme,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            sort_mode: None,
+            sort_ascending: true,
+            columns: None,
+            refresh_interval_ms: 100,
+            confirm_before_kill: false,
+            exit_log_capacity: 100,
+            kill_grace_period_ms: 3000,
+            cpu_source: CpuSource::Proc,
+            show_raw_errors: false,
+            memory_unit: MemoryUnit::Auto,
+            alerts: Vec::new(),
+            alert_bell: false,
+            graph_history_points: 120,
+            process_history_points: 600,
+            usage_warn: 50.0,
+            usage_crit: 90.0,
+            keymap: KeyMap::default(),
+            theme: Theme::default(),
+        }
+    }
+}
+
+/// Location of the config file ($XDG_CONFIG_HOME, falling back to ~/.config).
+pub fn config_path() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(base.join("linux_process_manager").join("config.toml"))
+}
+
+/// Load the config file, returning the resulting config and any warnings to surface in the UI.
+pub fn load() -> (Config, Vec<String>) {
+    let Some(path) = config_path() else {
+        return (Config::default(), Vec::new());
+    };
+    match std::fs::read_to_string(&path) {
+        Ok(text) => parse(&text),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => (Config::default(), Vec::new()),
+        Err(e) => (Config::default(), vec![format!("Could not read {}: {}", path.display(), e)]),
+    }
+}
+
+/// Parse config text. Keys that fail to parse are skipped with a warning instead of
+/// discarding the whole file.
+pub fn parse(text: &str) -> (Config, Vec<String>) {
+    let mut config = Config::default();
+    let mut warnings = Vec::new();
+    let table = match text.parse::<toml::Table>() {
+        Ok(table) => table,
+        Err(e) => {
+            warnings.push(format!("Invalid config file, using defaults: {}", e.message()));
+            return (config, warnings);
+        }
+    };
+
+    if let Some(mode) = take::<String>(&table, "sort", &mut warnings) {
+        if crate::process::SORT_MODES.contains(&mode.as_str()) {
+            config.sort_mode = Some(mode);
+        } else {
+            warnings.push(format!("Unknown sort mode '{}' in config", mode));
+        }
+    }
+    if let Some(ascending) = take(&table, "sort_ascending", &mut warnings) {
+        config.sort_ascending = ascending;
+    }
+    if let Some(columns) = take::<Vec<String>>(&table, "columns", &mut warnings) {
+        for column in &columns {
+            if Column::from_key(column).is_none() {
+                warnings.push(format!("Unknown column '{}'", column));
+            }
+        }
+        config.columns = Some(columns);
+    }
+    if let Some(interval) = take::<u64>(&table, "refresh_interval_ms", &mut warnings) {
+        config.refresh_interval_ms = interval.max(10);
+    }
+    if let Some(confirm) = take(&table, "confirm_before_kill", &mut warnings) {
+        config.confirm_before_kill = confirm;
+    }
+    if let Some(capacity) = take::<usize>(&table, "exit_log_capacity", &mut warnings) {
+        config.exit_log_capacity = capacity.max(1);
+    }
+    if let Some(name) = take::<String>(&table, "theme", &mut warnings) {
+        match Theme::named(&name) {
+            Some(theme) => config.theme = theme,
+            None => warnings.push(format!("Unknown theme '{}' (expected one of: {})", name, THEME_NAMES.join(", "))),
+        }
+    }
+    if let Some(grace) = take::<u64>(&table, "kill_grace_period_ms", &mut warnings) {
+        config.kill_grace_period_ms = grace;
+    }
+    if let Some(name) = take::<String>(&table, "cpu_source", &mut warnings) {
+        match CpuSource::named(&name) {
+            Some(source) => config.cpu_source = source,
+            None => warnings.push(format!("Unknown CPU source '{}' (expected one of: {})", name, CPU_SOURCES.join(", "))),
+        }
+    }
+    if let Some(show) = take(&table, "show_raw_errors", &mut warnings) {
+        config.show_raw_errors = show;
+    }
+    if let Some(name) = take::<String>(&table, "memory_unit", &mut warnings) {
+        match MemoryUnit::named(&name) {
+            Some(unit) => config.memory_unit = unit,
+            None => warnings.push(format!("Unknown memory unit '{}' (expected one of: {})", name, MEMORY_UNITS.join(", "))),
+        }
+    }
+    if let Some(rules) = take::<Vec<String>>(&table, "alerts", &mut warnings) {
+        for rule in rules {
+            match AlertRule::parse(&rule) {
+                Ok(rule) => config.alerts.push(rule),
+                Err(e) => warnings.push(format!("Ignoring alert '{}': {}", rule, e)),
+            }
+        }
+    }
+    if let Some(bell) = take(&table, "alert_bell", &mut warnings) {
+        config.alert_bell = bell;
+    }
+    if let Some(points) = take::<usize>(&table, "graph_history_points", &mut warnings) {
+        config.graph_history_points = points.max(2);
+    }
+    if let Some(points) = take::<usize>(&table, "process_history_points", &mut warnings) {
+        config.process_history_points = points.max(2);
+    }
+    if let Some(thresholds) = take::<toml::Table>(&table, "thresholds", &mut warnings) {
+        let warn = take::<f32>(&thresholds, "warn", &mut warnings).unwrap_or(config.usage_warn);
+        let crit = take::<f32>(&thresholds, "crit", &mut warnings).unwrap_or(config.usage_crit);
+        if warn < crit {
+            (config.usage_warn, config.usage_crit) = (warn, crit);
+        } else {
+            warnings.push(format!("Ignoring thresholds: warn ({}) must be below crit ({})", warn, crit));
+        }
+        for key in thresholds.keys().filter(|key| !["warn", "crit"].contains(&key.as_str())) {
+            warnings.push(format!("Unknown threshold '{}' (expected warn or crit)", key));
+        }
+    }
+    if let Some(keys) = take::<toml::Table>(&table, "keys", &mut warnings) {
+        config.keymap = KeyMap::from_table(&keys, &mut warnings);
+    }
+
+    for key in table.keys() {
+        if !KNOWN_KEYS.contains(&key.as_str()) {
+            warnings.push(format!("Unknown config key '{}'", key));
+        }
+    }
+    (config, warnings)
+}
+
+/// Write one key into the config file, keeping the others. Comments in the file are not preserved.
+pub fn save_value(key: &str, value: toml::Value) -> std::io::Result<PathBuf> {
+    let path = config_path()
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "no config directory ($HOME is not set)"))?;
+    let mut table = match std::fs::read_to_string(&path) {
+        Ok(text) => text
+            .parse::<toml::Table>()
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e.message().to_string()))?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => toml::Table::new(),
+        Err(e) => return Err(e),
+    };
+    table.insert(key.to_string(), value);
+    let text = toml::to_string(&table).map_err(|e| std::io::Error::other(e.to_string()))?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(&path, text)?;
+    Ok(path)
+}
+
+const KNOWN_KEYS: &[&str] = &[
+    "sort",
+    "sort_ascending",
+    "columns",
+    "refresh_interval_ms",
+    "confirm_before_kill",
+    "exit_log_capacity",
+    "kill_grace_period_ms",
+    "keys",
+    "theme",
+    "cpu_source",
+    "show_raw_errors",
+    "memory_unit",
+    "alerts",
+    "alert_bell",
+    "graph_history_points",
+    "process_history_points",
+    "thresholds",
+];
+
+// Deserialize a single key, recording a warning if it is present but has the wrong type
+fn take<T: DeserializeOwned>(table: &toml::Table, key: &str, warnings: &mut Vec<String>) -> Option<T> {
+    let value = table.get(key)?;
+    match value.clone().try_into::<T>() {
+        Ok(parsed) => Some(parsed),
+        Err(e) => {
+            warnings.push(format!("Ignoring config key '{}': {}", key, e.message()));
+            None
+        }
+    }
+}
@@ -0,0 +1,49 @@
+//! Diagnostics module
+// Remembers which /proc files could not be read. The readers fall back to zeros so the views
+// keep working, and the UI reports each failed source once so an empty tab (in a container, under
+// WSL) doesn't look like a bug in the tool.
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+struct Failure {
+    source: PathBuf, // The file as read, under whichever proc root it came from
+    consequence: &'static str, // What stops working, e.g. "load average disabled"
+    reported: bool,
+}
+
+static FAILURES: Mutex<Vec<Failure>> = Mutex::new(Vec::new());
+
+/// Read a system-wide file such as "stat" from the procfs mounted at `root`, recording a failure
+/// if it can't be read.
+pub fn read_proc(root: &Path, file: &str, consequence: &'static str) -> Option<String> {
+    let source = root.join(file);
+    match std::fs::read_to_string(&source) {
+        Ok(text) => Some(text),
+        Err(_) => {
+            record_failure(source, consequence);
+            None
+        }
+    }
+}
+
+pub fn record_failure(source: PathBuf, consequence: &'static str) {
+    let Ok(mut failures) = FAILURES.lock() else { return };
+    if !failures.iter().any(|failure| failure.source == source) {
+        failures.push(Failure { source, consequence, reported: false });
+    }
+}
+
+/// Failures not reported yet, as "/proc/loadavg unreadable - load average disabled". Each source
+/// is only returned once.
+pub fn take_unreported() -> Vec<String> {
+    let Ok(mut failures) = FAILURES.lock() else { return Vec::new() };
+    failures
+        .iter_mut()
+        .filter(|failure| !failure.reported)
+        .map(|failure| {
+            failure.reported = true;
+            format!("{} unreadable - {}", failure.source.display(), failure.consequence)
+        })
+        .collect()
+}
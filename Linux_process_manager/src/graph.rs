@@ -1,5 +1,6 @@
 use crate::process::ProcessManager;
 use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
 // use std::collections::HashMap; //delete after debugging
 
@@ -11,7 +12,7 @@ use ratatui::{
 };
 
 // Import Ratatui's color separately to avoid confusion
-use ratatui::style::{Style, Modifier, Color as RatatuiColor};
+use ratatui::style::{Style, Modifier};
 
 // Import Crossterm components with explicit namespace
 // use crossterm::{
@@ -21,7 +22,12 @@ use ratatui::style::{Style, Modifier, Color as RatatuiColor};
 // };
 
 use crate::ui::StatisticsTab;  // Add this at the top with other imports
-use crate::process::ProcessInfo;
+use crate::process::{ProcessInfo, ProcessState};
+use crate::procfs_parse;
+use crate::diagnostics;
+use crate::util::{format_bytes, format_megabytes, MemoryUnit};
+use crate::theme::Theme;
+use crate::keymap::Action;
 
 // Add this struct at the top with other structs
 pub struct CpuInfo {
@@ -40,58 +46,188 @@ impl CpuInfo {
     }
 }
 
+// CPU, resident memory and virtual size samples of one process
+type ProcessHistory = (VecDeque<f32>, VecDeque<u64>, VecDeque<u64>);
+
 // Modify GraphData struct
 pub struct GraphData {
+    proc_root: PathBuf, // procfs mount the system-wide figures are read from, /proc unless --proc-root
     cpu_history: VecDeque<f32>,
     memory_history: VecDeque<u64>,
-    max_points: usize,
+    max_points: usize, // Samples kept for the system-wide graphs
+    process_points: usize, // Samples kept per process; costs about 20 bytes per sample per process
     last_update: Instant,
-    update_interval: Duration,
+    update_interval: Duration, // Minimum time between samples
+    sample_spacing: Duration, // Measured time between samples, which also depends on the caller's refresh rate
     cpu_infos: Vec<CpuInfo>,  // Keep this for per-core display
-    per_process_history: std::collections::HashMap<u32, (VecDeque<f32>, VecDeque<u64>)>,
+    per_process_history: std::collections::HashMap<u32, ProcessHistory>,
+    gpus: std::sync::Arc<std::sync::Mutex<Vec<GpuInfo>>>, // Latest GPU readings, written by the query thread
+    gpu_query_running: std::sync::Arc<std::sync::atomic::AtomicBool>,
+    gpu_last_query: Option<Instant>,
+    gpu_history: VecDeque<f32>, // Utilization of the first GPU
+    battery: Option<BatteryInfo>, // None on machines without a battery
+    temperatures: Vec<(String, f64)>, // Every temperature sensor at the last sample, see get_temperatures
+    battery_history: VecDeque<f32>, // Charge percentage
+    battery_samples: VecDeque<(Instant, f64)>, // Remaining energy (or charge) over time, for the rate
+    rapl_last: Option<(Instant, u64)>, // Previous RAPL package energy reading (µJ)
+    package_power: Option<f64>, // Watts, from the last two RAPL readings
+    disk_io_last: Option<(Instant, u64, u64)>, // Previous /proc/diskstats reading: bytes read, written
+    disk_io_rates: Option<(f64, f64)>, // Read and write MB/s, from the last two diskstats readings
+    memory_total: u64, // MB, alongside the latest memory_history sample
+    load_average: (f64, f64, f64),
+    load_history: VecDeque<f64>, // 1-minute load average
+    fork_samples: VecDeque<(Instant, u64)>, // Processes created since boot (/proc/stat), over the last SPAWN_RATE_WINDOW
+    disk_usage: std::sync::Arc<std::sync::Mutex<(u64, u64)>>, // Root filesystem (total, used) in MB, written by the query thread
+    disk_query_running: std::sync::Arc<std::sync::atomic::AtomicBool>,
+    disk_last_query: Option<Instant>,
+}
+
+// Span the spawn rate is averaged over
+const SPAWN_RATE_WINDOW: Duration = Duration::from_secs(60);
+
+/// Shortest time between two graph samples; slower refresh rates sample once per refresh.
+pub const MIN_SAMPLE_INTERVAL_MS: u64 = 500;
+
+// How often the root filesystem usage is queried. It changes slowly and statvfs can block.
+const DISK_QUERY_INTERVAL: Duration = Duration::from_secs(5);
+
+// How often the GPUs are queried. nvidia-smi takes tens of milliseconds to start, far too slow to
+// run on the UI thread at every sample.
+const GPU_QUERY_INTERVAL: Duration = Duration::from_secs(2);
+
+// Readings for the first battery in /sys/class/power_supply
+pub struct BatteryInfo {
+    pub name: String,
+    pub capacity: Option<f32>, // Percent
+    pub status: String, // Charging, Discharging, Full, ...
+    pub now: Option<f64>, // energy_now (µWh) or charge_now (µAh)
+    pub full: Option<f64>,
+    pub energy_based: bool, // Whether now/full are energy (µWh) rather than charge (µAh)
+    pub ac_online: Option<bool>,
+}
+
+// One GPU's readings. Fields the driver doesn't expose are None.
+pub struct GpuInfo {
+    pub name: String,
+    pub utilization: Option<f32>,
+    pub memory_used_mb: Option<u64>,
+    pub memory_total_mb: Option<u64>,
+    pub temperature: Option<f64>,
 }
 
 impl GraphData {
-    pub fn new(max_points: usize, update_interval_ms: u64) -> Self {
+    pub fn new(proc_root: &Path, max_points: usize, process_points: usize, update_interval_ms: u64) -> Self {
         GraphData {
+            proc_root: proc_root.to_path_buf(),
             cpu_history: VecDeque::with_capacity(max_points),
             memory_history: VecDeque::with_capacity(max_points),
             max_points,
+            process_points,
             last_update: Instant::now(),
             update_interval: Duration::from_millis(update_interval_ms),
-            cpu_infos: (0..get_cpu_count()).map(|_| CpuInfo::new()).collect(),
+            sample_spacing: Duration::from_millis(update_interval_ms),
+            cpu_infos: (0..get_cpu_count(proc_root)).map(|_| CpuInfo::new()).collect(),
             per_process_history: std::collections::HashMap::new(),
+            gpus: Default::default(),
+            gpu_query_running: Default::default(),
+            gpu_last_query: None,
+            gpu_history: VecDeque::with_capacity(max_points),
+            battery: None,
+            temperatures: Vec::new(),
+            battery_history: VecDeque::with_capacity(max_points),
+            battery_samples: VecDeque::with_capacity(max_points),
+            rapl_last: None,
+            package_power: None,
+            disk_io_last: None,
+            disk_io_rates: None,
+            memory_total: 0,
+            load_average: (0.0, 0.0, 0.0),
+            load_history: VecDeque::with_capacity(max_points),
+            fork_samples: VecDeque::new(),
+            disk_usage: Default::default(),
+            disk_query_running: Default::default(),
+            disk_last_query: None,
+        }
+    }
+
+    // Query the root filesystem on a worker thread so a hung mount (stale NFS) only leaves the disk
+    // figures stale instead of freezing the UI. No new query starts while one is still stuck.
+    fn update_disk_usage(&mut self) {
+        use std::sync::atomic::Ordering;
+        if self.disk_last_query.is_some_and(|last| last.elapsed() < DISK_QUERY_INTERVAL)
+            || self.disk_query_running.swap(true, Ordering::AcqRel)
+        {
+            return;
+        }
+        self.disk_last_query = Some(Instant::now());
+        let usage = self.disk_usage.clone();
+        let running = self.disk_query_running.clone();
+        std::thread::spawn(move || {
+            if let Some(result) = statvfs_usage("/")
+                && let Ok(mut usage) = usage.lock()
+            {
+                *usage = result;
+            }
+            running.store(false, Ordering::Release);
+        });
+    }
+
+    // Query the GPUs on a worker thread, like the disk usage: nvidia-smi is a process spawn and
+    // can hang along with the driver. The graph repeats the last reading between queries.
+    fn update_gpus(&mut self) {
+        use std::sync::atomic::Ordering;
+        if self.gpu_last_query.is_some_and(|last| last.elapsed() < GPU_QUERY_INTERVAL)
+            || self.gpu_query_running.swap(true, Ordering::AcqRel)
+        {
+            return;
         }
+        self.gpu_last_query = Some(Instant::now());
+        let gpus = self.gpus.clone();
+        let running = self.gpu_query_running.clone();
+        std::thread::spawn(move || {
+            let readings = read_gpu_info();
+            if let Ok(mut gpus) = gpus.lock() {
+                *gpus = readings;
+            }
+            running.store(false, Ordering::Release);
+        });
+    }
+
+    /// GPU readings as of the last completed query; empty before the first or without a GPU.
+    pub fn gpus(&self) -> std::sync::MutexGuard<'_, Vec<GpuInfo>> {
+        self.gpus.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Root filesystem (total, used) in MB as of the last completed query; (0, 0) before the first.
+    pub fn disk_usage(&self) -> (u64, u64) {
+        self.disk_usage.lock().map(|usage| *usage).unwrap_or((0, 0))
     }
 
     fn update_cpu_info(&mut self) {
-        if let Ok(stat) = std::fs::read_to_string("/proc/stat") {
-            let lines: Vec<&str> = stat.lines().collect();
-            
+        let root = &self.proc_root;
+        if let Some(text) = diagnostics::read_proc(root, "stat", "CPU usage disabled") {
+            let stat = procfs_parse::parse_stat(&text);
+            // The fork counter also counts processes too short-lived to ever show up in the list
+            let now = Instant::now();
+            self.fork_samples.push_back((now, stat.processes));
+            while self.fork_samples.front().is_some_and(|&(at, _)| now.duration_since(at) > SPAWN_RATE_WINDOW) {
+                self.fork_samples.pop_front();
+            }
             // Handle individual cores for the CPU bars display
-            for (i, cpu_info) in self.cpu_infos.iter_mut().enumerate() {
-                if let Some(line) = lines.get(i + 1) {  // Skip first line (aggregate CPU)
-                    if line.starts_with("cpu") {
-                        let values: Vec<u64> = line.split_whitespace()
-                            .skip(1)  // Skip "cpu" prefix
-                            .filter_map(|val| val.parse().ok())
-                            .collect();
-
-                        if values.len() >= 4 {
-                            let idle = values[3];
-                            let total: u64 = values.iter().sum();
-
-                            let idle_delta = idle - cpu_info.last_idle;
-                            let total_delta = total - cpu_info.last_total;
-
-                            if total_delta > 0 {
-                                cpu_info.usage = 100.0 * (1.0 - (idle_delta as f32 / total_delta as f32));
-                            }
-
-                            cpu_info.last_idle = idle;
-                            cpu_info.last_total = total;
-                        }
+            for (cpu_info, times) in self.cpu_infos.iter_mut().zip(&stat.cpus) {
+                if times.0.len() >= 4 {
+                    let idle = times.idle();
+                    let total = times.total();
+
+                    let idle_delta = idle - cpu_info.last_idle;
+                    let total_delta = total - cpu_info.last_total;
+
+                    if total_delta > 0 {
+                        cpu_info.usage = 100.0 * (1.0 - (idle_delta as f32 / total_delta as f32));
                     }
+
+                    cpu_info.last_idle = idle;
+                    cpu_info.last_total = total;
                 }
             }
         }
@@ -99,16 +235,23 @@ impl GraphData {
 
     pub fn update(&mut self, process_manager: &ProcessManager) {
         let now = Instant::now();
-        if now.duration_since(self.last_update) < self.update_interval {
+        let spacing = now.duration_since(self.last_update);
+        if spacing < self.update_interval {
             return;
         }
+        // Samples land on the first refresh after update_interval, so with a slower or uneven
+        // refresh they are further apart. Track the real spacing for the time axes, skipping gaps
+        // such as the TUI being suspended for a pager.
+        if !self.cpu_history.is_empty() && spacing < self.sample_spacing * 3 {
+            self.sample_spacing = (self.sample_spacing * 4 + spacing) / 5;
+        }
 
         // Update CPU info for the per-core display
         self.update_cpu_info();
+        self.update_disk_usage();
         
         // Get total CPU usage from all processes
-        let total_cpu: f32 = process_manager.get_processes()
-            .iter()
+        let total_cpu: f32 = process_manager.all_processes()
             .map(|p| p.cpu_usage)
             .sum();
         
@@ -119,35 +262,157 @@ impl GraphData {
         }
         
         // Use system memory usage from /proc/meminfo
-        let (_mem_total, mem_used, _mem_free, _mem_cached, _mem_available) = get_memory_info();
+        let (mem_total, mem_used, _mem_free, _mem_cached, _mem_available) = get_memory_info(&self.proc_root);
         let total_memory = mem_used / 1024; // Convert to MB
+        self.memory_total = mem_total / 1024;
+        self.load_average = get_load_average(&self.proc_root);
+        self.load_history.push_back(self.load_average.0);
+        while self.load_history.len() > self.max_points {
+            self.load_history.pop_front();
+        }
         self.memory_history.push_back(total_memory);
         while self.memory_history.len() > self.max_points {
             self.memory_history.pop_front();
         }
         
         // Update per-process history (leave as is for per-process graphs)
-        let current_pids: std::collections::HashSet<u32> = process_manager.get_processes()
-            .iter()
+        // Every process, so a filter that hides one doesn't wipe its history
+        let current_pids: std::collections::HashSet<u32> = process_manager.all_processes()
             .map(|p| p.pid)
             .collect();
         self.per_process_history.retain(|&pid, _| current_pids.contains(&pid));
-        for process in process_manager.get_processes() {
-            let entry = self.per_process_history.entry(process.pid).or_insert_with(|| {
-                (VecDeque::with_capacity(self.max_points), VecDeque::with_capacity(self.max_points))
-            });
+        for process in process_manager.all_processes() {
+            // Grow on demand rather than reserving process_points for every short-lived process
+            let entry = self.per_process_history.entry(process.pid).or_default();
             entry.0.push_back(process.cpu_usage);
             entry.1.push_back(process.memory_usage);
-            while entry.0.len() > self.max_points {
+            entry.2.push_back(process.virtual_memory);
+            while entry.0.len() > self.process_points {
                 entry.0.pop_front();
             }
-            while entry.1.len() > self.max_points {
+            while entry.1.len() > self.process_points {
                 entry.1.pop_front();
             }
+            while entry.2.len() > self.process_points {
+                entry.2.pop_front();
+            }
         }
+
+        self.update_gpus();
+        let utilization = self.gpus().first().and_then(|gpu| gpu.utilization);
+        if let Some(utilization) = utilization {
+            self.gpu_history.push_back(utilization);
+            while self.gpu_history.len() > self.max_points {
+                self.gpu_history.pop_front();
+            }
+        }
+
+        self.battery = read_battery_info();
+        self.temperatures = get_temperatures();
+        if let Some(battery) = &self.battery {
+            if let Some(capacity) = battery.capacity {
+                self.battery_history.push_back(capacity);
+                while self.battery_history.len() > self.max_points {
+                    self.battery_history.pop_front();
+                }
+            }
+            if let Some(level) = battery.now {
+                self.battery_samples.push_back((now, level));
+                while self.battery_samples.len() > self.max_points {
+                    self.battery_samples.pop_front();
+                }
+            }
+        }
+        self.update_package_power(now);
+        self.update_disk_io(now);
         self.last_update = now;
     }
 
+    // Like the RAPL counter, diskstats only has totals since boot, so rates come from two samples
+    fn update_disk_io(&mut self, now: Instant) {
+        let Some((read_bytes, write_bytes)) = read_disk_bytes(&self.proc_root) else {
+            self.disk_io_rates = None;
+            return;
+        };
+        if let Some((last_time, last_read, last_write)) = self.disk_io_last {
+            let seconds = now.duration_since(last_time).as_secs_f64();
+            if seconds > 0.0 {
+                let rate = |bytes: u64, last: u64| bytes.saturating_sub(last) as f64 / 1_048_576.0 / seconds;
+                self.disk_io_rates = Some((rate(read_bytes, last_read), rate(write_bytes, last_write)));
+            }
+        }
+        self.disk_io_last = Some((now, read_bytes, write_bytes));
+    }
+
+    /// Main disk read and write speeds in MB/s over the last refresh; None until two samples exist
+    /// or when /proc/diskstats has no recognized disk.
+    pub fn disk_io_rates(&self) -> Option<(f64, f64)> {
+        self.disk_io_rates
+    }
+
+    // RAPL only exposes a cumulative energy counter, so power is the difference between samples
+    fn update_package_power(&mut self, now: Instant) {
+        const RAPL: &str = "/sys/class/powercap/intel-rapl:0";
+        let read = |file: &str| -> Option<u64> {
+            std::fs::read_to_string(format!("{}/{}", RAPL, file)).ok()?.trim().parse().ok()
+        };
+        // Missing on non-Intel machines, and root-only on most recent kernels
+        let Some(energy) = read("energy_uj") else {
+            self.package_power = None;
+            return;
+        };
+        if let Some((last_time, last_energy)) = self.rapl_last {
+            let seconds = now.duration_since(last_time).as_secs_f64();
+            // The counter wraps at max_energy_range_uj
+            let delta = if energy >= last_energy {
+                energy - last_energy
+            } else {
+                read("max_energy_range_uj").unwrap_or(0).saturating_sub(last_energy) + energy
+            };
+            if seconds > 0.0 {
+                self.package_power = Some(delta as f64 / 1_000_000.0 / seconds);
+            }
+        }
+        self.rapl_last = Some((now, energy));
+    }
+
+    // Change in remaining energy (µW) or charge (µA) per hour over the sampled window,
+    // positive while discharging. None until enough time has passed to measure it.
+    fn battery_drain_rate(&self) -> Option<f64> {
+        let (first_time, first_level) = self.battery_samples.front()?;
+        let (last_time, last_level) = self.battery_samples.back()?;
+        let hours = last_time.duration_since(*first_time).as_secs_f64() / 3600.0;
+        if hours * 3600.0 < 10.0 {
+            return None;
+        }
+        Some((first_level - last_level) / hours)
+    }
+
+    /// Whether the last sample found a battery.
+    pub fn has_battery(&self) -> bool {
+        self.battery.is_some()
+    }
+
+    /// Temperature sensors as (label, °C) at the last sample.
+    pub fn temperatures(&self) -> &[(String, f64)] {
+        &self.temperatures
+    }
+
+    /// Hottest CPU sensor at the last sample.
+    pub fn cpu_temperature(&self) -> Option<f64> {
+        cpu_temp(&self.temperatures)
+    }
+
+    /// The procfs mount the figures are read from.
+    pub fn proc_root(&self) -> &Path {
+        &self.proc_root
+    }
+
+    /// Number of CPUs, as counted at startup.
+    pub fn cpu_count(&self) -> usize {
+        self.cpu_infos.len()
+    }
+
     pub fn get_cpu_infos(&self) -> &[CpuInfo] {
         &self.cpu_infos
     }
@@ -160,17 +425,141 @@ impl GraphData {
         &self.memory_history
     }
 
+    /// Average utilization across all cores at the last sample, in percent.
+    pub fn cpu_usage(&self) -> f32 {
+        if self.cpu_infos.is_empty() {
+            return 0.0;
+        }
+        self.cpu_infos.iter().map(|cpu| cpu.usage).sum::<f32>() / self.cpu_infos.len() as f32
+    }
+
+    /// Used and total memory in MB at the last sample.
+    pub fn memory_usage(&self) -> (u64, u64) {
+        (self.memory_history.back().copied().unwrap_or(0), self.memory_total)
+    }
+
+    pub fn load_average(&self) -> (f64, f64, f64) {
+        self.load_average
+    }
+
+    /// Processes (and threads) created per minute, averaged over up to the last minute. None
+    /// until two samples are a second apart.
+    pub fn spawn_rate(&self) -> Option<f64> {
+        let (&(first_at, first), &(last_at, last)) = (self.fork_samples.front()?, self.fork_samples.back()?);
+        let elapsed = last_at.duration_since(first_at).as_secs_f64();
+        (elapsed >= 1.0).then(|| last.saturating_sub(first) as f64 * 60.0 / elapsed)
+    }
+
     pub fn get_process_history(&self, pid: u32) -> Option<(&VecDeque<f32>, &VecDeque<u64>)> {
-        self.per_process_history.get(&pid).map(|(cpu, mem)| (cpu, mem))
+        self.per_process_history.get(&pid).map(|(cpu, mem, _)| (cpu, mem))
+    }
+
+    /// CPU and memory history of every process sampled so far.
+    pub fn process_histories(&self) -> impl Iterator<Item = (u32, &VecDeque<f32>, &VecDeque<u64>)> {
+        self.per_process_history.iter().map(|(&pid, (cpu, mem, _))| (pid, cpu, mem))
+    }
+
+    pub fn get_process_virtual_history(&self, pid: u32) -> Option<&VecDeque<u64>> {
+        self.per_process_history.get(&pid).map(|(_, _, virt)| virt)
+    }
+
+    /// Time between two samples of the histories, as measured over the recent samples.
+    pub fn sample_interval(&self) -> Duration {
+        self.sample_spacing
+    }
+
+    /// Time covered by a full system-wide history, for the statistics chart x-axes.
+    pub fn history_window_secs(&self) -> u64 {
+        (self.max_points as f64 * self.sample_spacing.as_secs_f64()).round() as u64
+    }
+
+    // A system-wide history as chart points, newest at x = 0 (see `windowed_points`)
+    fn history_points<T: Copy>(&self, history: &VecDeque<T>, value: impl Fn(T) -> f64) -> Vec<(f64, f64)> {
+        windowed_points(history, self.sample_spacing, self.history_window_secs(), value)
+    }
+
+    /// Write the system CPU and memory history to `path` as CSV, oldest sample first. Sample times
+    /// are inferred from the measured sample interval, counting back from the latest sample.
+    pub fn export_csv(&self, path: &std::path::Path) -> std::io::Result<()> {
+        use std::io::Write;
+        let latest = chrono::Local::now() - chrono::Duration::from_std(self.last_update.elapsed()).unwrap_or_default();
+        let step = chrono::Duration::from_std(self.sample_spacing).unwrap_or_default();
+        let len = self.cpu_history.len().min(self.memory_history.len());
+        let mut out = std::io::BufWriter::new(std::fs::File::create(path)?);
+        writeln!(out, "timestamp,seconds_ago,cpu_percent,memory_used_mb")?;
+        // Both histories get a sample on every update, so the newest entries line up
+        let cpu = self.cpu_history.iter().skip(self.cpu_history.len() - len);
+        let memory = self.memory_history.iter().skip(self.memory_history.len() - len);
+        for (i, (cpu, memory)) in cpu.zip(memory).enumerate() {
+            let back = (len - 1 - i) as i32;
+            writeln!(
+                out,
+                "{},{:.1},{:.1},{}",
+                (latest - step * back).format("%Y-%m-%dT%H:%M:%S%.3f%:z"),
+                self.sample_spacing.as_secs_f64() * back as f64,
+                cpu,
+                memory,
+            )?;
+        }
+        out.flush()
     }
 }
 
-pub fn render_graph_dashboard(
-    frame: &mut ratatui::Frame,
-    graph_data: &GraphData,
-    current_tab: &StatisticsTab,
-    process_list: &[ProcessInfo],
-) {
+/// Plot a history against seconds before now (the newest sample at 0), keeping only the last
+/// `window_secs`. Histories shorter than the window simply leave the left of the chart empty.
+pub fn windowed_points<T: Copy>(history: &VecDeque<T>, interval: Duration, window_secs: u64, value: impl Fn(T) -> f64) -> Vec<(f64, f64)> {
+    let step = interval.as_secs_f64();
+    let len = history.len();
+    history
+        .iter()
+        .enumerate()
+        .map(|(i, &sample)| (-((len - 1 - i) as f64) * step, value(sample)))
+        .filter(|&(x, _)| x >= -(window_secs as f64))
+        .collect()
+}
+
+/// An x-axis for points from `windowed_points`: the last `window_secs` up to "now" on the right.
+pub fn time_axis(window_secs: u64) -> ratatui::widgets::Axis<'static> {
+    ratatui::widgets::Axis::default()
+        .bounds([-(window_secs as f64), 0.0])
+        .labels(time_axis_labels(window_secs))
+}
+
+/// Relative time labels for an x-axis spanning the last `window_secs`: start, middle and "now".
+fn time_axis_labels(window_secs: u64) -> Vec<Span<'static>> {
+    let label = |secs: u64| {
+        if secs >= 60 && secs.is_multiple_of(60) {
+            format!("-{}m", secs / 60)
+        } else {
+            format!("-{}s", secs)
+        }
+    };
+    vec![Span::raw(label(window_secs)), Span::raw(label(window_secs / 2)), Span::raw("now")]
+}
+
+/// Draw the statistics dashboard. `scroll` is the requested offset of the scrollable tabs; the
+/// returned offset is clamped to the tab's content, so the caller can store it back.
+/// A history chart on the Graphs tab that can be maximized to fill the tab.
+#[derive(Clone, Copy, PartialEq)]
+pub enum GraphKind {
+    Cpu,
+    Memory,
+}
+
+/// Everything the statistics dashboard draws from, borrowed from the UI state.
+pub struct DashboardView<'a> {
+    pub graph_data: &'a GraphData,
+    pub theme: &'a Theme,
+    pub memory_unit: MemoryUnit,
+    pub current_tab: &'a StatisticsTab,
+    pub process_list: &'a [ProcessInfo],
+    pub scroll: usize,
+    pub maximized: Option<GraphKind>,
+}
+
+/// Draw the dashboard and return the scroll offset clamped to the current tab's content.
+pub fn render_graph_dashboard(frame: &mut ratatui::Frame, view: &DashboardView) -> usize {
+    let DashboardView { graph_data, theme, memory_unit, current_tab, process_list, scroll, maximized } = *view;
     let size = frame.size();
     let main_chunks = Layout::default()
         .direction(Direction::Vertical)
@@ -179,29 +568,92 @@ pub fn render_graph_dashboard(
             Constraint::Min(size.height.saturating_sub(3)),
         ])
         .split(size);
-    render_tabs(frame, main_chunks[0], current_tab);
+    render_tabs(frame, main_chunks[0], graph_data, theme, current_tab);
     match current_tab {
-        StatisticsTab::Graphs => render_graphs_tab(frame, main_chunks[1], graph_data),
-        StatisticsTab::Overview => render_overview_tab(frame, main_chunks[1], graph_data, process_list),
-        StatisticsTab::CPU => render_cpu_tab(frame, main_chunks[1], graph_data),
-        StatisticsTab::Memory => render_memory_tab(frame, main_chunks[1]),
-        StatisticsTab::Disk => render_disk_tab(frame, main_chunks[1]),
+        StatisticsTab::Overview => return render_overview_tab(frame, main_chunks[1], graph_data, theme, memory_unit, process_list, scroll),
+        StatisticsTab::Cpu => return render_cpu_tab(frame, main_chunks[1], graph_data, theme, scroll),
+        StatisticsTab::Advanced => return render_advanced_tab(frame, main_chunks[1], graph_data, theme, scroll),
+        StatisticsTab::Graphs => render_graphs_tab(frame, main_chunks[1], graph_data, theme, memory_unit, maximized),
+        StatisticsTab::Memory => render_memory_tab(frame, main_chunks[1], graph_data, theme, memory_unit),
+        StatisticsTab::Disk => render_disk_tab(frame, main_chunks[1], graph_data, theme),
         StatisticsTab::Processes => {
-            render_processes_tab(frame, main_chunks[1], process_list);
+            render_processes_tab(frame, main_chunks[1], graph_data, theme, memory_unit, process_list);
         },
-        StatisticsTab::Advanced => render_advanced_tab(frame, main_chunks[1], graph_data),
+        StatisticsTab::Gpu => render_gpu_tab(frame, main_chunks[1], graph_data, theme, memory_unit),
+        StatisticsTab::Battery => render_battery_tab(frame, main_chunks[1], graph_data, theme),
         StatisticsTab::PerProcessGraph | StatisticsTab::ProcessLog | StatisticsTab::Help => {
-            // Placeholder
+            // Never selected here: these have their own views (ViewMode) drawn from ui.rs
+        }
+    }
+    0
+}
+
+/// Tabs whose content can be taller than the screen and scrolls with ↑/↓.
+pub fn is_scrollable(tab: &StatisticsTab) -> bool {
+    matches!(tab, StatisticsTab::Overview | StatisticsTab::Cpu | StatisticsTab::Advanced)
+}
+
+// Largest useful scroll offset for `rows` rows of content in a bordered box of `area`
+fn max_scroll(rows: usize, area: Rect) -> usize {
+    rows.saturating_sub(area.height.saturating_sub(2) as usize)
+}
+
+// Render bordered boxes of the given heights one below the other, as if on a taller page scrolled
+// down by `scroll` rows. A box cut off at the top keeps its border and shows its remaining lines.
+// Returns `scroll` clamped to the page.
+fn render_stacked_sections(frame: &mut ratatui::Frame, area: Rect, sections: Vec<(Vec<Line>, u16)>, scroll: usize) -> usize {
+    let total: u16 = sections.iter().map(|(_, height)| height).sum();
+    let scroll = scroll.min(total.saturating_sub(area.height) as usize) as u16;
+    let mut top = 0u16; // Row of the current box on the page
+    for (lines, height) in sections {
+        let bottom = top + height;
+        let visible_top = top.max(scroll);
+        let visible_bottom = bottom.min(scroll + area.height);
+        if visible_top < visible_bottom {
+            let rect = Rect::new(area.x, area.y + visible_top - scroll, area.width, visible_bottom - visible_top);
+            let widget = Paragraph::new(lines)
+                .scroll((visible_top - top, 0))
+                .block(Block::default().borders(Borders::ALL))
+                .style(Style::default());
+            frame.render_widget(widget, rect);
         }
+        top = bottom;
     }
+    scroll as usize
+}
+
+// Tab shortcuts shown in the header, in display order, with the action that selects each.
+// Shared with `tab_at` so mouse clicks map onto the same geometry that gets rendered.
+const TAB_LABELS: &[(&str, StatisticsTab, Action)] = &[
+    ("[1] Graphs", StatisticsTab::Graphs, Action::GraphsTab),
+    ("[2] Overview", StatisticsTab::Overview, Action::OverviewTab),
+    ("[3] CPU", StatisticsTab::Cpu, Action::CpuTab),
+    ("[4] Memory", StatisticsTab::Memory, Action::MemoryTab),
+    ("[5] Disk", StatisticsTab::Disk, Action::DiskTab),
+    ("[6] Processes", StatisticsTab::Processes, Action::ProcessesTab),
+    ("[7] Advanced", StatisticsTab::Advanced, Action::AdvancedTab),
+    ("[8] GPU", StatisticsTab::Gpu, Action::GpuTab),
+    ("[9] Battery", StatisticsTab::Battery, Action::BatteryTab),
+];
+
+// Tab labels to show: the battery tab is hidden on machines without one
+fn visible_tab_labels(graph_data: &GraphData) -> impl Iterator<Item = &'static (&'static str, StatisticsTab, Action)> {
+    let battery = graph_data.has_battery();
+    TAB_LABELS.iter().filter(move |(_, tab, _)| battery || *tab != StatisticsTab::Battery)
+}
+
+/// The visible tab selected by a tab action (`Action::GraphsTab` etc.).
+pub fn tab_for(graph_data: &GraphData, action: Action) -> Option<StatisticsTab> {
+    visible_tab_labels(graph_data).find(|(_, _, a)| *a == action).map(|(_, tab, _)| *tab)
 }
+const TAB_SEPARATOR: &str = "  ";
+const CURRENT_VIEW_PREFIX: &str = "Current View: ";
 
-pub fn render_tabs(frame: &mut ratatui::Frame, area: Rect, current_tab: &StatisticsTab) {
-    // Get the current tab name
-    let current_tab_name = match current_tab {
+fn tab_title(tab: &StatisticsTab) -> &'static str {
+    match tab {
         StatisticsTab::Graphs => "Graphs",
         StatisticsTab::Overview => "Overview",
-        StatisticsTab::CPU => "CPU Stats",
+        StatisticsTab::Cpu => "CPU Stats",
         StatisticsTab::Memory => "Memory Stats",
         StatisticsTab::Disk => "Disk Stats",
         StatisticsTab::Processes => "Processes",
@@ -209,17 +661,24 @@ pub fn render_tabs(frame: &mut ratatui::Frame, area: Rect, current_tab: &Statist
         StatisticsTab::PerProcessGraph => "Per-Process Graph",
         StatisticsTab::ProcessLog => "Process Log",
         StatisticsTab::Help => "Help",
-    };
+        StatisticsTab::Gpu => "GPU Stats",
+        StatisticsTab::Battery => "Battery",
+    }
+}
+
+pub fn render_tabs(frame: &mut ratatui::Frame, area: Rect, graph_data: &GraphData, theme: &Theme, current_tab: &StatisticsTab) {
+    let tab_labels = visible_tab_labels(graph_data)
+        .map(|(label, _, _)| *label)
+        .collect::<Vec<_>>()
+        .join(TAB_SEPARATOR);
 
     let title = Line::from(vec![
-        Span::styled("Current View: ", Style::default().fg(RatatuiColor::White)),
-        Span::styled(current_tab_name, 
-            Style::default()
-                .fg(RatatuiColor::Cyan)
-                .add_modifier(Modifier::BOLD | Modifier::UNDERLINED)),
+        Span::styled(CURRENT_VIEW_PREFIX, theme.value),
+        Span::styled(tab_title(current_tab), 
+            theme.accent.add_modifier(Modifier::BOLD | Modifier::UNDERLINED)),
         Span::raw(" "),
-        Span::styled("[1] Graphs  [2] Overview  [3] CPU  [4] Memory  [5] Disk  [6] Processes  [7] Advanced ", Style::default().fg(RatatuiColor::Yellow)),
-        Span::styled("[S/Esc] Return", Style::default().fg(RatatuiColor::Blue))
+        Span::styled(format!("{} ", tab_labels), theme.key_hint),
+        Span::styled("[E] Export CSV  [S/Esc] Return", theme.key_hint)
     ]);
 
     let header = Paragraph::new(title)
@@ -229,11 +688,39 @@ pub fn render_tabs(frame: &mut ratatui::Frame, area: Rect, current_tab: &Statist
     frame.render_widget(header, area);
 }
 
+/// Find the tab label under a mouse click. The tab header is always the top three rows
+/// of the frame (see `render_graph_dashboard`), with the text on the row inside the border.
+pub fn tab_at(graph_data: &GraphData, current_tab: &StatisticsTab, column: u16, row: u16) -> Option<StatisticsTab> {
+    if row != 1 {
+        return None;
+    }
+    let column = column as usize;
+    // Left border, then "Current View: <name> "
+    let mut x = 1 + CURRENT_VIEW_PREFIX.chars().count() + tab_title(current_tab).chars().count() + 1;
+    for (label, tab, _) in visible_tab_labels(graph_data) {
+        let width = label.chars().count();
+        if column >= x && column < x + width {
+            return Some(*tab);
+        }
+        x += width + TAB_SEPARATOR.len();
+    }
+    None
+}
+
 pub fn render_graphs_tab(
     frame: &mut ratatui::Frame,
     area: Rect,
     graph_data: &GraphData,
+    theme: &Theme,
+    memory_unit: MemoryUnit,
+    maximized: Option<GraphKind>,
 ) {
+    // A maximized chart gets the whole tab, without the bars
+    match maximized {
+        Some(GraphKind::Cpu) => return render_cpu_graph(frame, area, graph_data, theme),
+        Some(GraphKind::Memory) => return render_memory_graph(frame, area, graph_data, theme, memory_unit),
+        None => {}
+    }
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -245,7 +732,7 @@ pub fn render_graphs_tab(
         .split(area);
 
     // Render CPU bars (similar to htop)
-    render_cpu_bars(frame, chunks[0], graph_data);
+    render_cpu_bars(frame, chunks[0], graph_data, theme);
     
     // Create a sub-layout for memory bars with spacing
     let mem_chunks = Layout::default()
@@ -258,17 +745,17 @@ pub fn render_graphs_tab(
         .split(chunks[1]);
 
     // Render Memory/Swap bars with spacing
-    render_memory_bars(frame, mem_chunks[0], mem_chunks[2], graph_data);
+    render_memory_bars(frame, mem_chunks[0], mem_chunks[2], graph_data, theme, memory_unit);
 
     // Render the graphs
-    render_cpu_graph(frame, chunks[2], graph_data);
-    render_memory_graph(frame, chunks[3], graph_data);
+    render_cpu_graph(frame, chunks[2], graph_data, theme);
+    render_memory_graph(frame, chunks[3], graph_data, theme, memory_unit);
 }
 
-fn render_cpu_bars(frame: &mut ratatui::Frame, area: Rect, graph_data: &GraphData) {
-    let num_cpus = get_cpu_count();
+fn render_cpu_bars(frame: &mut ratatui::Frame, area: Rect, graph_data: &GraphData, theme: &Theme) {
+    let num_cpus = graph_data.cpu_count();
     let cpus_per_row = 8;
-    let num_rows = (num_cpus + cpus_per_row - 1) / cpus_per_row;
+    let num_rows = num_cpus.div_ceil(cpus_per_row);
     
     let row_chunks = Layout::default()
         .direction(Direction::Vertical)
@@ -298,10 +785,10 @@ fn render_cpu_bars(frame: &mut ratatui::Frame, area: Rect, graph_data: &GraphDat
             let label = format!("{:>2} [{:>3}%]", cpu_index, cpu_usage as u16);
             let text = vec![
                 Line::from(vec![
-                    Span::styled(label, Style::default().fg(RatatuiColor::White)),
+                    Span::styled(label, theme.value),
                 ]),
                 Line::from(vec![
-                    Span::styled(vertical_bar, Style::default().fg(get_usage_color(cpu_usage)))
+                    Span::styled(vertical_bar, theme.cpu(cpu_usage))
                 ])
             ];
 
@@ -317,10 +804,13 @@ fn render_memory_bars(
     frame: &mut ratatui::Frame,
     mem_area: Rect,
     swap_area: Rect,
-    _graph_data: &GraphData
+    graph_data: &GraphData,
+    theme: &Theme,
+    memory_unit: MemoryUnit,
 ) {
+    let root = graph_data.proc_root();
     // Calculate memory usage
-    let (mem_total, mem_used, _mem_free, _mem_cached, _mem_available) = get_memory_info();
+    let (mem_total, mem_used, _mem_free, _mem_cached, _mem_available) = get_memory_info(root);
     let memory_percentage = if mem_total > 0 {
         (((mem_used as f64 / mem_total as f64) * 100.0).min(100.0)) as u16
     } else {
@@ -329,13 +819,13 @@ fn render_memory_bars(
 
     // Memory bar with compact format
     let memory_gauge = ratatui::widgets::Gauge::default()
-        .gauge_style(Style::default().fg(get_usage_color(memory_percentage as f32)))
+        .gauge_style(theme.cpu(memory_percentage as f32))
         .percent(memory_percentage)
-        .label(format!("Mem [{:>4}M/{:>4}M]", mem_used / 1024, mem_total / 1024));
+        .label(format!("Mem [{}/{}]", format_bytes(mem_used * 1024, memory_unit), format_bytes(mem_total * 1024, memory_unit)));
 
-    // Swap bar (reading from /proc/swaps)
+    // Swap bar (reading from /proc/meminfo)
 
-    let (swap_used, swap_total) = get_swap_info();
+    let (swap_used, swap_total) = get_swap_info(root);
     // let swap_percentage = if swap_total > 0 {
     //     ((swap_used as f64 / swap_total as f64) * 100.0) as u16
     // } else {
@@ -354,7 +844,7 @@ fn render_memory_bars(
     
 
     let swap_gauge = ratatui::widgets::Gauge::default()
-        .gauge_style(Style::default().fg(get_usage_color(swap_percentage as f32)))
+        .gauge_style(theme.cpu(swap_percentage as f32))
         .percent(swap_percentage)
         .label(format!("Swp [{:>4}M/{:>4}M]", swap_used, swap_total));
 
@@ -362,315 +852,513 @@ fn render_memory_bars(
     frame.render_widget(swap_gauge, swap_area);
 }
 
-fn get_usage_color(usage: f32) -> RatatuiColor {
-    match usage as u16 {
-        0..=50 => RatatuiColor::Green,
-        51..=75 => RatatuiColor::Yellow,
-        76..=90 => RatatuiColor::Red,
-        _ => RatatuiColor::LightRed,
-    }
-}
 
-fn get_swap_info() -> (u64, u64) {
-    if let Ok(swaps) = std::fs::read_to_string("/proc/swaps") {
-        if let Some(swap_line) = swaps.lines().nth(1) {
-            let parts: Vec<&str> = swap_line.split_whitespace().collect();
-            if parts.len() >= 4 {
-                if let (Ok(total), Ok(used)) = (
-                    parts[2].parse::<u64>(),
-                    parts[3].parse::<u64>(),
-                ) {
-                    return (used / 1024, total / 1024);  // Convert KB to MB
-                }
-            }
-        }
-    }
-    (0, 0)
+// Returns (used, total) swap in MB, summed over all swap devices
+fn get_swap_info(root: &Path) -> (u64, u64) {
+    let info = read_meminfo(root);
+    (info.swap_used() / 1024, info.swap_total / 1024)
 }
 
-pub fn render_overview_tab(frame: &mut ratatui::Frame, area: Rect, graph_data: &GraphData, process_list: &[ProcessInfo]) {
-    let chunks = ratatui::layout::Layout::default()
-        .direction(ratatui::layout::Direction::Vertical)
-        .constraints([
-            ratatui::layout::Constraint::Length(7),   // System Overview
-            ratatui::layout::Constraint::Length(6),   // CPU Summary
-            ratatui::layout::Constraint::Length(5),   // Memory Summary
-            ratatui::layout::Constraint::Length(6),   // Disk Summary (increased from 4 to 6)
-            ratatui::layout::Constraint::Length(4),   // Process States
-            ratatui::layout::Constraint::Min(1),      // Spacer
-        ])
-        .split(area);
-
+pub fn render_overview_tab(frame: &mut ratatui::Frame, area: Rect, graph_data: &GraphData, theme: &Theme, memory_unit: MemoryUnit, process_list: &[ProcessInfo], scroll: usize) -> usize {
+    let root = graph_data.proc_root();
     // System Overview
-    let (boot_time, last_reboot) = get_boot_time();
+    let (boot_time, last_reboot) = get_boot_time(root);
     let hostname = hostname::get().unwrap_or_default().to_string_lossy().to_string();
     let os_info = get_os_info();
-    let kernel_version = std::fs::read_to_string("/proc/version").unwrap_or_default();
-    let uptime = get_system_uptime();
+    let kernel_version = std::fs::read_to_string(root.join("version")).unwrap_or_default();
+    let uptime = get_system_uptime(root);
     let sys_overview = vec![
-        Line::from(vec![Span::styled("System Overview", Style::default().fg(RatatuiColor::White).add_modifier(Modifier::BOLD))]),
-        Line::from(vec![Span::styled("Hostname: ", Style::default().fg(RatatuiColor::Gray)), Span::styled(&hostname, Style::default().fg(RatatuiColor::White))]),
-        Line::from(vec![Span::styled("OS: ", Style::default().fg(RatatuiColor::Gray)), Span::styled(&os_info, Style::default().fg(RatatuiColor::White))]),
-        Line::from(vec![Span::styled("Kernel: ", Style::default().fg(RatatuiColor::Gray)), Span::styled(&kernel_version, Style::default().fg(RatatuiColor::White))]),
-        Line::from(vec![Span::styled("Boot Time: ", Style::default().fg(RatatuiColor::Gray)), Span::styled(&boot_time, Style::default().fg(RatatuiColor::White))]),
-        Line::from(vec![Span::styled("Last Reboot: ", Style::default().fg(RatatuiColor::Gray)), Span::styled(&last_reboot, Style::default().fg(RatatuiColor::White))]),
-        Line::from(vec![Span::styled("Uptime: ", Style::default().fg(RatatuiColor::Gray)), Span::styled(&uptime, Style::default().fg(RatatuiColor::White))]),
+        Line::from(vec![Span::styled("System Overview", theme.heading)]),
+        Line::from(vec![Span::styled("Hostname: ", theme.label), Span::styled(&hostname, theme.value)]),
+        Line::from(vec![Span::styled("OS: ", theme.label), Span::styled(&os_info, theme.value)]),
+        Line::from(vec![Span::styled("Kernel: ", theme.label), Span::styled(&kernel_version, theme.value)]),
+        Line::from(vec![Span::styled("Boot Time: ", theme.label), Span::styled(&boot_time, theme.value)]),
+        Line::from(vec![Span::styled("Last Reboot: ", theme.label), Span::styled(&last_reboot, theme.value)]),
+        Line::from(vec![Span::styled("Uptime: ", theme.label), Span::styled(&uptime, theme.value)]),
     ];
-    let sys_overview_widget = Paragraph::new(sys_overview).block(Block::default().borders(Borders::ALL)).style(Style::default());
-    frame.render_widget(sys_overview_widget, chunks[0]);
 
     // CPU Summary
-    let (cpu_model, _, _) = get_cpu_details();
-    let load_avg = get_load_average();
+    let (cpu_model, _, _) = get_cpu_details(root);
+    let load_avg = get_load_average(root);
     let total_cpu: f32 = graph_data.get_cpu_history().iter().sum();
     let cpu_summary = vec![
-        Line::from(vec![Span::styled("CPU Summary", Style::default().fg(RatatuiColor::White).add_modifier(Modifier::BOLD))]),
-        Line::from(vec![Span::styled("Model: ", Style::default().fg(RatatuiColor::Gray)), Span::styled(&cpu_model, Style::default().fg(RatatuiColor::White))]),
-        Line::from(vec![Span::styled("Cores: ", Style::default().fg(RatatuiColor::Gray)), Span::styled(format!("{} (Physical)", get_cpu_count()), Style::default().fg(RatatuiColor::White))]),
-        Line::from(vec![Span::styled("Load Avg: ", Style::default().fg(RatatuiColor::Gray)), Span::styled(format!("{:.2}, {:.2}, {:.2}", load_avg.0, load_avg.1, load_avg.2), Style::default().fg(RatatuiColor::White))]),
-        Line::from(vec![Span::styled("Total CPU Usage: ", Style::default().fg(RatatuiColor::Gray)), Span::styled(format!("{:.1}%", total_cpu), get_usage_style(total_cpu as f64))]),
+        Line::from(vec![Span::styled("CPU Summary", theme.heading)]),
+        Line::from(vec![Span::styled("Model: ", theme.label), Span::styled(&cpu_model, theme.value)]),
+        Line::from(vec![Span::styled("Cores: ", theme.label), Span::styled(format!("{} (Physical)", graph_data.cpu_count()), theme.value)]),
+        Line::from(vec![Span::styled("Load Avg: ", theme.label), Span::styled(format!("{:.2}, {:.2}, {:.2}", load_avg.0, load_avg.1, load_avg.2), theme.value)]),
+        Line::from(vec![Span::styled("Total CPU Usage: ", theme.label), Span::styled(format!("{:.1}%", total_cpu), theme.usage(total_cpu as f64))]),
     ];
-    let cpu_summary_widget = Paragraph::new(cpu_summary).block(Block::default().borders(Borders::ALL)).style(Style::default());
-    frame.render_widget(cpu_summary_widget, chunks[1]);
 
     // Memory Summary
-    let (mem_total, mem_used, mem_free, mem_cached, _mem_available) = get_memory_info();
+    let (mem_total, mem_used, mem_free, mem_cached, _mem_available) = get_memory_info(root);
     let mem_summary = vec![
-        Line::from(vec![Span::styled("Memory Summary", Style::default().fg(RatatuiColor::White).add_modifier(Modifier::BOLD))]),
-        Line::from(vec![Span::styled("Total: ", Style::default().fg(RatatuiColor::Gray)), Span::styled(format!("{} MB", mem_total / 1024), Style::default().fg(RatatuiColor::White))]),
-        Line::from(vec![Span::styled("Used: ", Style::default().fg(RatatuiColor::Gray)), Span::styled(format!("{} MB", mem_used / 1024), get_usage_style((mem_used as f64 / mem_total as f64) * 100.0))]),
-        Line::from(vec![Span::styled("Free: ", Style::default().fg(RatatuiColor::Gray)), Span::styled(format!("{} MB", mem_free / 1024), Style::default().fg(RatatuiColor::White))]),
-        Line::from(vec![Span::styled("Cached+Buffers: ", Style::default().fg(RatatuiColor::Gray)), Span::styled(format!("{} MB", mem_cached / 1024), Style::default().fg(RatatuiColor::White))]),
+        Line::from(vec![Span::styled("Memory Summary", theme.heading)]),
+        Line::from(vec![Span::styled("Total: ", theme.label), Span::styled(format_bytes(mem_total * 1024, memory_unit), theme.value)]),
+        Line::from(vec![Span::styled("Used: ", theme.label), Span::styled(format_bytes(mem_used * 1024, memory_unit), theme.usage((mem_used as f64 / mem_total as f64) * 100.0))]),
+        Line::from(vec![Span::styled("Free: ", theme.label), Span::styled(format_bytes(mem_free * 1024, memory_unit), theme.value)]),
+        Line::from(vec![Span::styled("Cached+Buffers: ", theme.label), Span::styled(format_bytes(mem_cached * 1024, memory_unit), theme.value)]),
     ];
-    let mem_summary_widget = Paragraph::new(mem_summary).block(Block::default().borders(Borders::ALL)).style(Style::default());
-    frame.render_widget(mem_summary_widget, chunks[2]);
 
     // Disk Summary
-    let (disk_total, disk_used) = get_disk_stats();
+    let (disk_total, disk_used) = graph_data.disk_usage();
     let disk_total_gb = disk_total as f64 / 1024.0 ;
     let disk_used_gb = disk_used as f64 / 1024.0 ;
     let disk_free_gb = (disk_total.saturating_sub(disk_used)) as f64 / 1024.0;
     let disk_summary = vec![
-        Line::from(vec![Span::styled("Disk Summary", Style::default().fg(RatatuiColor::White).add_modifier(Modifier::BOLD))]),
-        Line::from(vec![Span::styled("Total (GB): ", Style::default().fg(RatatuiColor::Gray)), Span::styled(format!("{:.1} GB", disk_total_gb), Style::default().fg(RatatuiColor::White))]),
-        Line::from(vec![Span::styled("Used (GB): ", Style::default().fg(RatatuiColor::Gray)), Span::styled(format!("{:.1} GB", disk_used_gb), get_usage_style((disk_used as f64 / disk_total.max(1) as f64) * 100.0))]),
-        Line::from(vec![Span::styled("Free (GB): ", Style::default().fg(RatatuiColor::Gray)), Span::styled(format!("{:.1} GB", disk_free_gb), Style::default().fg(RatatuiColor::White))]),
+        Line::from(vec![Span::styled("Disk Summary", theme.heading)]),
+        Line::from(vec![Span::styled("Total (GB): ", theme.label), Span::styled(format!("{:.1} GB", disk_total_gb), theme.value)]),
+        Line::from(vec![Span::styled("Used (GB): ", theme.label), Span::styled(format!("{:.1} GB", disk_used_gb), theme.usage((disk_used as f64 / disk_total.max(1) as f64) * 100.0))]),
+        Line::from(vec![Span::styled("Free (GB): ", theme.label), Span::styled(format!("{:.1} GB", disk_free_gb), theme.value)]),
     ];
-    let disk_summary_widget = Paragraph::new(disk_summary).block(Block::default().borders(Borders::ALL)).style(Style::default());
-    frame.render_widget(disk_summary_widget, chunks[3]);
 
     // Process States
     let state_counts = get_process_state_counts_from_status(process_list);
     let process_states = vec![
-        Line::from(vec![Span::styled("Process States", Style::default().fg(RatatuiColor::White).add_modifier(Modifier::BOLD))]),
+        Line::from(vec![Span::styled("Process States", theme.heading)]),
         Line::from(vec![
-            Span::styled("Running: ", Style::default().fg(RatatuiColor::Green)), Span::styled(state_counts.get("Running").unwrap_or(&0).to_string(), Style::default().fg(RatatuiColor::White)),
+            Span::styled("Running: ", theme.status(ProcessState::Running)), Span::styled(state_counts.get("Running").unwrap_or(&0).to_string(), theme.value),
             Span::raw(" | "),
-            Span::styled("Runnable: ", Style::default().fg(RatatuiColor::Cyan)), Span::styled(state_counts.get("Runnable").unwrap_or(&0).to_string(), Style::default().fg(RatatuiColor::White)),
+            Span::styled("Runnable: ", theme.status(ProcessState::Running)), Span::styled(state_counts.get("Runnable").unwrap_or(&0).to_string(), theme.value),
             Span::raw(" | "),
-            Span::styled("Sleeping: ", Style::default().fg(RatatuiColor::Blue)), Span::styled(state_counts.get("Sleeping").unwrap_or(&0).to_string(), Style::default().fg(RatatuiColor::White)),
+            Span::styled("Sleeping: ", theme.status(ProcessState::Sleeping)), Span::styled(state_counts.get("Sleeping").unwrap_or(&0).to_string(), theme.value),
             Span::raw(" | "),
-            Span::styled("Uninterruptible: ", Style::default().fg(RatatuiColor::Magenta)), Span::styled(state_counts.get("Uninterruptible").unwrap_or(&0).to_string(), Style::default().fg(RatatuiColor::White)),
+            Span::styled("Uninterruptible: ", theme.status(ProcessState::DiskSleep)), Span::styled(state_counts.get("Uninterruptible").unwrap_or(&0).to_string(), theme.value),
             Span::raw(" | "),
-            Span::styled("Stopped: ", Style::default().fg(RatatuiColor::Yellow)), Span::styled(state_counts.get("Stopped").unwrap_or(&0).to_string(), Style::default().fg(RatatuiColor::White)),
+            Span::styled("Stopped: ", theme.status(ProcessState::Stopped)), Span::styled(state_counts.get("Stopped").unwrap_or(&0).to_string(), theme.value),
             Span::raw(" | "),
-            Span::styled("Zombie: ", Style::default().fg(RatatuiColor::Red)), Span::styled(state_counts.get("Zombie").unwrap_or(&0).to_string(), Style::default().fg(RatatuiColor::White)),
+            Span::styled("Zombie: ", theme.status(ProcessState::Zombie)), Span::styled(state_counts.get("Zombie").unwrap_or(&0).to_string(), theme.value),
             Span::raw(" | "),
-            Span::styled("Total: ", Style::default().fg(RatatuiColor::Gray)), Span::styled(process_list.len().to_string(), Style::default().fg(RatatuiColor::White)),
+            Span::styled("Total: ", theme.label), Span::styled(process_list.len().to_string(), theme.value),
         ]),
     ];
-    let process_states_widget = Paragraph::new(process_states).block(Block::default().borders(Borders::ALL)).style(Style::default());
-    frame.render_widget(process_states_widget, chunks[4]);
+    // Each box is as tall as its lines plus the borders
+    let sections = vec![
+        (sys_overview, 9),
+        (cpu_summary, 7),
+        (mem_summary, 7),
+        (disk_summary, 6),
+        (process_states, 4),
+    ];
+    render_stacked_sections(frame, area, sections, scroll)
 }
 
-pub fn render_cpu_tab(frame: &mut ratatui::Frame, area: Rect, graph_data: &GraphData) {
+pub fn render_cpu_tab(frame: &mut ratatui::Frame, area: Rect, graph_data: &GraphData, theme: &Theme, scroll: usize) -> usize {
+    let root = graph_data.proc_root();
     // Gather CPU details
-    let (model, freq, cache) = get_cpu_details();
-    let cpu_count = get_cpu_count();
-    let temp = get_cpu_temp();
-    let per_core_freqs = get_per_core_freq();
-    let (ctxt, _processes, procs_running, procs_blocked, interrupts) = get_cpu_stats();
-    let load_avg = get_load_average();
+    let (model, freq, cache) = get_cpu_details(root);
+    let cpu_count = graph_data.cpu_count();
+    let temp = graph_data.cpu_temperature();
+    let per_core_freqs = get_per_core_freq(cpu_count);
+    let (ctxt, _processes, procs_running, procs_blocked, interrupts) = get_cpu_stats(root);
+    let load_avg = get_load_average(root);
 
     // Per-core usage (from GraphData)
     let per_core_usages: Vec<f32> = graph_data.get_cpu_infos().iter().map(|c| c.usage).collect();
 
     // Compose lines for the CPU Info tab
     let mut lines = vec![
-        Line::from(vec![Span::styled("CPU Information", Style::default().fg(RatatuiColor::White).add_modifier(Modifier::BOLD))]),
-        Line::from(vec![Span::styled("Model: ", Style::default().fg(RatatuiColor::Gray)), Span::styled(model, Style::default().fg(RatatuiColor::White))]),
-        Line::from(vec![Span::styled("Frequency: ", Style::default().fg(RatatuiColor::Gray)), Span::styled(freq, Style::default().fg(RatatuiColor::White))]),
-        Line::from(vec![Span::styled("Cache: ", Style::default().fg(RatatuiColor::Gray)), Span::styled(cache, Style::default().fg(RatatuiColor::White))]),
-        Line::from(vec![Span::styled("Cores: ", Style::default().fg(RatatuiColor::Gray)), Span::styled(format!("{}", cpu_count), Style::default().fg(RatatuiColor::White))]),
+        Line::from(vec![Span::styled("CPU Information", theme.heading)]),
+        Line::from(vec![Span::styled("Model: ", theme.label), Span::styled(model, theme.value)]),
+        Line::from(vec![Span::styled("Frequency: ", theme.label), Span::styled(freq, theme.value)]),
+        Line::from(vec![Span::styled("Cache: ", theme.label), Span::styled(cache, theme.value)]),
+        Line::from(vec![Span::styled("Cores: ", theme.label), Span::styled(format!("{}", cpu_count), theme.value)]),
     ];
     if let Some(temp) = temp {
-        lines.push(Line::from(vec![Span::styled("Temperature: ", Style::default().fg(RatatuiColor::Gray)), Span::styled(format!("{:.1} °C", temp), Style::default().fg(RatatuiColor::White))]));
+        lines.push(Line::from(vec![Span::styled("Temperature: ", theme.label), Span::styled(format!("{:.1} °C", temp), theme.value)]));
     }
     // Add total CPU usage line using /proc/stat aggregate
-    let total_cpu = get_total_cpu_usage();
-    lines.push(Line::from(vec![Span::styled("Total CPU Usage: ", Style::default().fg(RatatuiColor::Gray)), Span::styled(format!("{:.1}%", total_cpu), get_usage_style(total_cpu as f64))]));
-    lines.push(Line::from(vec![Span::styled("Context Switches: ", Style::default().fg(RatatuiColor::Gray)), Span::styled(format!("{}", ctxt), Style::default().fg(RatatuiColor::White))]));
-    lines.push(Line::from(vec![Span::styled("Interrupts: ", Style::default().fg(RatatuiColor::Gray)), Span::styled(format!("{}", interrupts), Style::default().fg(RatatuiColor::White))]));
-    lines.push(Line::from(vec![Span::styled("Running Procs: ", Style::default().fg(RatatuiColor::Gray)), Span::styled(format!("{}", procs_running), Style::default().fg(RatatuiColor::White)), Span::raw(" | "), Span::styled("Blocked: ", Style::default().fg(RatatuiColor::Gray)), Span::styled(format!("{}", procs_blocked), Style::default().fg(RatatuiColor::White))]));
-    lines.push(Line::from(vec![Span::styled("Load Avg: ", Style::default().fg(RatatuiColor::Gray)), Span::styled(format!("{:.2}, {:.2}, {:.2}", load_avg.0, load_avg.1, load_avg.2), Style::default().fg(RatatuiColor::White))]));
+    let total_cpu = get_total_cpu_usage(root);
+    lines.push(Line::from(vec![Span::styled("Total CPU Usage: ", theme.label), Span::styled(format!("{:.1}%", total_cpu), theme.usage(total_cpu as f64))]));
+    lines.push(Line::from(vec![Span::styled("Context Switches: ", theme.label), Span::styled(format!("{}", ctxt), theme.value)]));
+    lines.push(Line::from(vec![Span::styled("Interrupts: ", theme.label), Span::styled(format!("{}", interrupts), theme.value)]));
+    lines.push(Line::from(vec![Span::styled("Running Procs: ", theme.label), Span::styled(format!("{}", procs_running), theme.value), Span::raw(" | "), Span::styled("Blocked: ", theme.label), Span::styled(format!("{}", procs_blocked), theme.value)]));
+    lines.push(Line::from(vec![Span::styled("Load Avg: ", theme.label), Span::styled(format!("{:.2}, {:.2}, {:.2}", load_avg.0, load_avg.1, load_avg.2), theme.value)]));
     lines.push(Line::from(vec![Span::styled("", Style::default())]));
-    lines.push(Line::from(vec![Span::styled("Per-Core Usage:", Style::default().fg(RatatuiColor::White).add_modifier(Modifier::BOLD))]));
+    lines.push(Line::from(vec![Span::styled("Per-Core Usage:", theme.heading)]));
     for (i, usage) in per_core_usages.iter().enumerate() {
         let freq_str = per_core_freqs.get(i).map(|f| format!(" @ {:.0} MHz", f)).unwrap_or_default();
         lines.push(Line::from(vec![
-            Span::styled(format!("Core {:2}: ", i), Style::default().fg(RatatuiColor::Gray)),
-            Span::styled(format!("{:5.1}%", usage), get_usage_style(*usage as f64)),
-            Span::styled(freq_str, Style::default().fg(RatatuiColor::Cyan)),
+            Span::styled(format!("Core {:2}: ", i), theme.label),
+            Span::styled(format!("{:5.1}%", usage), theme.usage(*usage as f64)),
+            Span::styled(freq_str, theme.accent),
         ]));
     }
-    let widget = Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title("CPU Info")).wrap(ratatui::widgets::Wrap { trim: false });
-    frame.render_widget(widget, area);
+    // The load chart takes the bottom of the tab when there's room for both
+    let (info_area, load_area) = if area.height >= 24 {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(10)])
+            .split(area);
+        (chunks[0], Some(chunks[1]))
+    } else {
+        (area, None)
+    };
+    let scroll = scroll.min(max_scroll(lines.len(), info_area));
+    let widget = Paragraph::new(lines)
+        .block(Block::default().borders(Borders::ALL).title("CPU Info"))
+        .scroll((scroll as u16, 0));
+    frame.render_widget(widget, info_area);
+    if let Some(load_area) = load_area {
+        render_load_graph(frame, load_area, graph_data, theme, cpu_count);
+    }
+    scroll
 }
 
-pub fn render_memory_tab(frame: &mut ratatui::Frame, area: Rect) {
-    let (mem_total, mem_used, mem_free, mem_cached, _mem_available) = get_memory_info();
-    let (swap_used, swap_total) = get_swap_info();
-    // Read more details from /proc/meminfo
-    let mut available = 0;
-    let mut buffers = 0;
-    if let Ok(meminfo) = std::fs::read_to_string("/proc/meminfo") {
-        for line in meminfo.lines() {
-            if line.starts_with("MemAvailable:") {
-                available = line.split_whitespace().nth(1).unwrap_or("0").parse().unwrap_or(0);
-            } else if line.starts_with("Buffers:") {
-                buffers = line.split_whitespace().nth(1).unwrap_or("0").parse().unwrap_or(0);
-            }
-        }
-    }
-    let mem_total_mb = mem_total / 1024;
-    let mem_used_mb = mem_used / 1024;
-    let mem_free_mb = mem_free / 1024;
-    let mem_cached_mb = mem_cached / 1024;
-    let mem_available_mb = available / 1024;
-    let mem_buffers_mb = buffers / 1024;
+// 1-minute load average over time, with a line at the core count: load above it means runnable
+// tasks are waiting for a CPU
+fn render_load_graph(frame: &mut ratatui::Frame, area: Rect, graph_data: &GraphData, theme: &Theme, cpu_count: usize) {
+    let window = graph_data.history_window_secs();
+    let load_data = graph_data.history_points(&graph_data.load_history, |load| load);
+    let cores = cpu_count as f64;
+    let capacity_data = [(-(window as f64), cores), (0.0, cores)];
+    let peak = load_data.iter().map(|&(_, load)| load).fold(0.0, f64::max);
+    let top = (cores * 1.25).max(peak * 1.1).max(1.0);
+
+    let datasets = vec![
+        Dataset::default()
+            .name(format!("{} cores", cpu_count))
+            .marker(ratatui::symbols::Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(theme.series[3])
+            .data(&capacity_data),
+        Dataset::default()
+            .name("load (1m)")
+            .marker(ratatui::symbols::Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(theme.series[2])
+            .data(&load_data),
+    ];
+    let chart = Chart::new(datasets)
+        .block(Block::default().title("Load Average Over Time (1 min)").borders(Borders::ALL))
+        .x_axis(time_axis(window))
+        .y_axis(ratatui::widgets::Axis::default()
+            .bounds([0.0, top])
+            .labels(vec![Span::from("0"), Span::from(format!("{:.1}", top / 2.0)), Span::from(format!("{:.1}", top))]));
+    frame.render_widget(chart, area);
+}
+
+pub fn render_memory_tab(frame: &mut ratatui::Frame, area: Rect, graph_data: &GraphData, theme: &Theme, memory_unit: MemoryUnit) {
+    let root = graph_data.proc_root();
+    let (mem_total, mem_used, mem_free, mem_cached, _mem_available) = get_memory_info(root);
+    let (swap_used, swap_total) = get_swap_info(root);
+    // More details from /proc/meminfo
+    let meminfo = read_meminfo(root);
+    let available = meminfo.available.unwrap_or(0);
+    let buffers = meminfo.buffers;
     let swap_free = swap_total.saturating_sub(swap_used);
     let mem_usage_percent = if mem_total > 0 { (mem_used as f64 / mem_total as f64) * 100.0 } else { 0.0 };
     let swap_usage_percent = if swap_total > 0 { (swap_used as f64 / swap_total as f64) * 100.0 } else { 0.0 };
     let lines = vec![
-        Line::from(vec![Span::styled("Memory Information", Style::default().fg(RatatuiColor::White).add_modifier(Modifier::BOLD))]),
+        Line::from(vec![Span::styled("Memory Information", theme.heading)]),
         Line::from(vec![Span::styled("", Style::default())]),
-        Line::from(vec![Span::styled("-- RAM --", Style::default().fg(RatatuiColor::Cyan).add_modifier(Modifier::BOLD))]),
-        Line::from(vec![Span::styled("Total: ", Style::default().fg(RatatuiColor::Gray)), Span::styled(format!("{} MB", mem_total_mb), Style::default().fg(RatatuiColor::White))]),
-        Line::from(vec![Span::styled("Used: ", Style::default().fg(RatatuiColor::Gray)), Span::styled(format!("{} MB ({:.1}%)", mem_used_mb, mem_usage_percent), get_usage_style(mem_usage_percent))]),
-        Line::from(vec![Span::styled("Free: ", Style::default().fg(RatatuiColor::Gray)), Span::styled(format!("{} MB", mem_free_mb), Style::default().fg(RatatuiColor::White))]),
-        Line::from(vec![Span::styled("Available: ", Style::default().fg(RatatuiColor::Gray)), Span::styled(format!("{} MB", mem_available_mb), Style::default().fg(RatatuiColor::White))]),
-        Line::from(vec![Span::styled("Cached: ", Style::default().fg(RatatuiColor::Gray)), Span::styled(format!("{} MB", mem_cached_mb), Style::default().fg(RatatuiColor::White))]),
-        Line::from(vec![Span::styled("Buffers: ", Style::default().fg(RatatuiColor::Gray)), Span::styled(format!("{} MB", mem_buffers_mb), Style::default().fg(RatatuiColor::White))]),
+        Line::from(vec![Span::styled("-- RAM --", theme.accent.add_modifier(Modifier::BOLD))]),
+        Line::from(vec![Span::styled("Total: ", theme.label), Span::styled(format_bytes(mem_total * 1024, memory_unit), theme.value)]),
+        Line::from(vec![Span::styled("Used: ", theme.label), Span::styled(format!("{} ({:.1}%)", format_bytes(mem_used * 1024, memory_unit), mem_usage_percent), theme.usage(mem_usage_percent))]),
+        Line::from(vec![Span::styled("Free: ", theme.label), Span::styled(format_bytes(mem_free * 1024, memory_unit), theme.value)]),
+        Line::from(vec![Span::styled("Available: ", theme.label), Span::styled(format_bytes(available * 1024, memory_unit), theme.value)]),
+        Line::from(vec![Span::styled("Cached: ", theme.label), Span::styled(format_bytes(mem_cached * 1024, memory_unit), theme.value)]),
+        Line::from(vec![Span::styled("Buffers: ", theme.label), Span::styled(format_bytes(buffers * 1024, memory_unit), theme.value)]),
         Line::from(vec![Span::styled("", Style::default())]),
-        Line::from(vec![Span::styled("-- SWAP --", Style::default().fg(RatatuiColor::Magenta).add_modifier(Modifier::BOLD))]),
-        Line::from(vec![Span::styled("Total: ", Style::default().fg(RatatuiColor::Gray)), Span::styled(format!("{} MB", swap_total), Style::default().fg(RatatuiColor::White))]),
-        Line::from(vec![Span::styled("Used: ", Style::default().fg(RatatuiColor::Gray)), Span::styled(format!("{} MB ({:.1}%)", swap_used, swap_usage_percent), get_usage_style(swap_usage_percent))]),
-        Line::from(vec![Span::styled("Free: ", Style::default().fg(RatatuiColor::Gray)), Span::styled(format!("{} MB", swap_free), Style::default().fg(RatatuiColor::White))]),
+        Line::from(vec![Span::styled("-- SWAP --", theme.accent_alt.add_modifier(Modifier::BOLD))]),
+        Line::from(vec![Span::styled("Total: ", theme.label), Span::styled(format_megabytes(swap_total as f64, memory_unit), theme.value)]),
+        Line::from(vec![Span::styled("Used: ", theme.label), Span::styled(format!("{} ({:.1}%)", format_megabytes(swap_used as f64, memory_unit), swap_usage_percent), theme.usage(swap_usage_percent))]),
+        Line::from(vec![Span::styled("Free: ", theme.label), Span::styled(format_megabytes(swap_free as f64, memory_unit), theme.value)]),
     ];
     let widget = Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title("Memory Info"));
     frame.render_widget(widget, area);
 }
 
-pub fn render_disk_tab(frame: &mut ratatui::Frame, area: Rect) {
-    let (disk_total, disk_used) = get_disk_stats();
+pub fn render_disk_tab(frame: &mut ratatui::Frame, area: Rect, graph_data: &GraphData, theme: &Theme) {
+    let (disk_total, disk_used) = graph_data.disk_usage();
     let disk_free = disk_total.saturating_sub(disk_used);
-    // Try to get disk read/write speeds and storage type
-    let (read_speed, write_speed) = get_disk_rw_speed();
-    let storage_type = get_storage_type();
-    let read_speed_str = if read_speed > 0.0 { format!("{:.1} MB/s", read_speed) } else { "Unavailable".to_string() };
-    let write_speed_str = if write_speed > 0.0 { format!("{:.1} MB/s", write_speed) } else { "Unavailable".to_string() };
+    let root = graph_data.proc_root();
+    // Read/write speeds are sampled with the other graphs in GraphData::update
+    let storage_type = get_storage_type(root);
+    let (read_speed_str, write_speed_str) = match graph_data.disk_io_rates() {
+        Some((read_speed, write_speed)) => (format!("{:.1} MB/s", read_speed), format!("{:.1} MB/s", write_speed)),
+        None => ("Unavailable".to_string(), "Unavailable".to_string()),
+    };
     let lines = vec![
-        Line::from(vec![Span::styled("Disk Information", Style::default().fg(RatatuiColor::White).add_modifier(Modifier::BOLD))]),
-        Line::from(vec![Span::styled("Total: ", Style::default().fg(RatatuiColor::Gray)), Span::styled(format!("{} MB", disk_total), Style::default().fg(RatatuiColor::White))]),
-        Line::from(vec![Span::styled("Used: ", Style::default().fg(RatatuiColor::Gray)), Span::styled(format!("{} MB", disk_used), get_usage_style((disk_used as f64 / disk_total.max(1) as f64) * 100.0))]),
-        Line::from(vec![Span::styled("Free: ", Style::default().fg(RatatuiColor::Gray)), Span::styled(format!("{} MB", disk_free), Style::default().fg(RatatuiColor::White))]),
-        Line::from(vec![Span::styled("Read Speed: ", Style::default().fg(RatatuiColor::Gray)), Span::styled(read_speed_str, Style::default().fg(RatatuiColor::Cyan))]),
-        Line::from(vec![Span::styled("Write Speed: ", Style::default().fg(RatatuiColor::Gray)), Span::styled(write_speed_str, Style::default().fg(RatatuiColor::Magenta))]),
-        Line::from(vec![Span::styled("Storage Type: ", Style::default().fg(RatatuiColor::Gray)), Span::styled(storage_type, Style::default().fg(RatatuiColor::Yellow))]),
+        Line::from(vec![Span::styled("Disk Information", theme.heading)]),
+        Line::from(vec![Span::styled("Total: ", theme.label), Span::styled(format!("{} MB", disk_total), theme.value)]),
+        Line::from(vec![Span::styled("Used: ", theme.label), Span::styled(format!("{} MB", disk_used), theme.usage((disk_used as f64 / disk_total.max(1) as f64) * 100.0))]),
+        Line::from(vec![Span::styled("Free: ", theme.label), Span::styled(format!("{} MB", disk_free), theme.value)]),
+        Line::from(vec![Span::styled("Read Speed: ", theme.label), Span::styled(read_speed_str, theme.accent)]),
+        Line::from(vec![Span::styled("Write Speed: ", theme.label), Span::styled(write_speed_str, theme.accent_alt)]),
+        Line::from(vec![Span::styled("Storage Type: ", theme.label), Span::styled(storage_type, theme.value)]),
     ];
     let widget = Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title("Disk Info"));
     frame.render_widget(widget, area);
 }
 
-pub fn render_processes_tab(frame: &mut ratatui::Frame, area: Rect, process_list: &[ProcessInfo]) {
+// Process age buckets for the Processes tab: upper bound in seconds and label
+const AGE_BUCKETS: &[(u64, &str)] = &[(60, "< 1 min"), (600, "1-10 min"), (3_600, "10-60 min"), (86_400, "1-24 h"), (u64::MAX, "> 1 day")];
+
+// Live processes per age bucket, from their start times
+fn process_age_counts(process_list: &[ProcessInfo]) -> Vec<usize> {
+    let mut counts = vec![0; AGE_BUCKETS.len()];
+    for process in process_list {
+        let age = crate::util::seconds_since(process.start_time);
+        if let Some(bucket) = AGE_BUCKETS.iter().position(|&(limit, _)| age < limit) {
+            counts[bucket] += 1;
+        }
+    }
+    counts
+}
+
+pub fn render_processes_tab(frame: &mut ratatui::Frame, area: Rect, graph_data: &GraphData, theme: &Theme, memory_unit: MemoryUnit, process_list: &[ProcessInfo]) {
     let total_processes = process_list.len();
-    let state_counts = get_process_state_counts_from_status(process_list);
+    let state_count = |state: ProcessState| process_list.iter().filter(|p| p.state == state).count();
+    let total_threads: usize = process_list.iter().map(|p| p.thread_count).sum();
     let mut lines = vec![
-        Line::from(vec![Span::styled("Processes Overview", Style::default().fg(RatatuiColor::White).add_modifier(Modifier::BOLD))]),
-        Line::from(vec![Span::styled("Total Processes: ", Style::default().fg(RatatuiColor::Gray)), Span::styled(total_processes.to_string(), Style::default().fg(RatatuiColor::White))]),
-        Line::from(vec![Span::styled("States: ", Style::default().fg(RatatuiColor::Gray)),
-            Span::styled(format!("Running: {}  ", state_counts.get("Running").unwrap_or(&0)), Style::default().fg(RatatuiColor::Green)),
-            Span::styled(format!("Sleeping: {}  ", state_counts.get("Sleeping").unwrap_or(&0)), Style::default().fg(RatatuiColor::Blue)),
-            Span::styled(format!("Stopped: {}  ", state_counts.get("Stopped").unwrap_or(&0)), Style::default().fg(RatatuiColor::Yellow)),
-            Span::styled(format!("Zombie: {}", state_counts.get("Zombie").unwrap_or(&0)), Style::default().fg(RatatuiColor::Red)),
+        Line::from(vec![Span::styled("Processes Overview", theme.heading)]),
+        Line::from(vec![Span::styled("Total Processes: ", theme.label), Span::styled(total_processes.to_string(), theme.value),
+            Span::raw(" | "), Span::styled("Threads: ", theme.label), Span::styled(total_threads.to_string(), theme.value)]),
+        Line::from(vec![Span::styled("States: ", theme.label),
+            Span::styled(format!("Running: {}  ", state_count(ProcessState::Running)), theme.status(ProcessState::Running)),
+            Span::styled(format!("Sleeping: {}  ", state_count(ProcessState::Sleeping)), theme.status(ProcessState::Sleeping)),
+            Span::styled(format!("Disk Sleep: {}  ", state_count(ProcessState::DiskSleep)), theme.status(ProcessState::DiskSleep)),
+            Span::styled(format!("Idle: {}  ", state_count(ProcessState::Idle)), theme.label),
+            Span::styled(format!("Stopped: {}  ", state_count(ProcessState::Stopped)), theme.status(ProcessState::Stopped)),
+            Span::styled(format!("Zombie: {}", state_count(ProcessState::Zombie)), theme.status(ProcessState::Zombie)),
         ]),
+        Line::from(vec![Span::styled("Most Threads: ", theme.label), match process_list.iter().max_by_key(|p| (p.thread_count, std::cmp::Reverse(p.pid))) {
+            Some(proc) => Span::styled(format!("{} (PID {}) - {} threads", proc.name, proc.pid, proc.thread_count), theme.value),
+            None => Span::styled("-", theme.dim),
+        }]),
         Line::from(vec![Span::styled("", Style::default())]),
-        Line::from(vec![Span::styled("Top Processes by CPU", Style::default().fg(RatatuiColor::White).add_modifier(Modifier::BOLD))]),
+        Line::from(vec![Span::styled("Process Age", theme.heading)]),
+        Line::from(vec![Span::styled("Spawn Rate: ", theme.label), match graph_data.spawn_rate() {
+            Some(rate) => Span::styled(format!("{:.0}/min", rate), theme.value),
+            None => Span::styled("measuring...", theme.dim),
+        }]),
     ];
-    let mut sorted_by_cpu = process_list.iter().enumerate().collect::<Vec<(usize, &ProcessInfo)>>();
-    sorted_by_cpu.sort_by(|a, b| b.1.cpu_usage.partial_cmp(&a.1.cpu_usage).unwrap_or(std::cmp::Ordering::Equal));
-    for &(i, proc) in &sorted_by_cpu.iter().take(5).collect::<Vec<_>>() {
+    // A fork bomb or a crash-looping service shows up as a swelling "< 1 min" bar
+    let age_counts = process_age_counts(process_list);
+    let widest = age_counts.iter().copied().max().unwrap_or(0).max(1);
+    let bar_width = (area.width as usize).saturating_sub(30).min(40);
+    for (&(_, label), &count) in AGE_BUCKETS.iter().zip(&age_counts) {
+        lines.push(Line::from(vec![
+            Span::styled(format!("{:>10} {:>5} ", label, count), theme.label),
+            Span::styled("█".repeat(count * bar_width / widest), theme.accent),
+        ]));
+    }
+    lines.push(Line::from(vec![Span::styled("", Style::default())]));
+    lines.push(Line::from(vec![Span::styled("Top Processes by CPU", theme.heading)]));
+    let mut sorted_by_cpu: Vec<&ProcessInfo> = process_list.iter().collect();
+    sorted_by_cpu.sort_by(|a, b| b.cpu_usage.total_cmp(&a.cpu_usage).then(a.pid.cmp(&b.pid)));
+    for (i, proc) in sorted_by_cpu.iter().take(5).enumerate() {
         lines.push(Line::from(vec![Span::styled(
             format!("{}. {} (PID {}) - CPU: {:.2}%", i + 1, proc.name, proc.pid, proc.cpu_usage),
-            Style::default().fg(RatatuiColor::Yellow)
+            theme.series[0]
         )]));
     }
     lines.push(Line::from(vec![Span::styled("", Style::default())]));
-    lines.push(Line::from(vec![Span::styled("Top Processes by Memory", Style::default().fg(RatatuiColor::White).add_modifier(Modifier::BOLD))]));
-    let mut sorted_by_mem = process_list.iter().enumerate().collect::<Vec<(usize, &ProcessInfo)>>();
-    sorted_by_mem.sort_by(|a, b| b.1.memory_usage.partial_cmp(&a.1.memory_usage).unwrap_or(std::cmp::Ordering::Equal));
-    for &(i, proc) in &sorted_by_mem.iter().take(5).collect::<Vec<_>>() {
+    lines.push(Line::from(vec![Span::styled("Top Processes by Memory", theme.heading)]));
+    let mut sorted_by_mem: Vec<&ProcessInfo> = process_list.iter().collect();
+    sorted_by_mem.sort_by(|a, b| b.memory_usage.cmp(&a.memory_usage).then(a.pid.cmp(&b.pid)));
+    for (i, proc) in sorted_by_mem.iter().take(5).enumerate() {
         lines.push(Line::from(vec![Span::styled(
-            format!("{}. {} (PID {}) - MEM: {:.2} MB", i + 1, proc.name, proc.pid, proc.memory_usage as f64 / 1024.0 / 1024.0),
-            Style::default().fg(RatatuiColor::Blue)
+            format!("{}. {} (PID {}) - MEM: {}", i + 1, proc.name, proc.pid, format_bytes(proc.memory_usage, memory_unit)),
+            theme.series[1]
         )]));
     }
     let widget = Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title("Processes Info"));
     frame.render_widget(widget, area);
 }
 
-pub fn render_advanced_tab(frame: &mut ratatui::Frame, area: Rect, _graph_data: &GraphData) {
-    let (pgfault, pswpin, pswpout, iowait) = get_vm_stats();
-    let (ctxt, processes, procs_running, procs_blocked, interrupts) = get_cpu_stats();
+pub fn render_advanced_tab(frame: &mut ratatui::Frame, area: Rect, graph_data: &GraphData, theme: &Theme, scroll: usize) -> usize {
+    let root = graph_data.proc_root();
+    let (pgfault, pswpin, pswpout, iowait) = get_vm_stats(root);
+    let (ctxt, processes, procs_running, procs_blocked, interrupts) = get_cpu_stats(root);
     // Advanced: CPU temperature and per-core frequency
-    let cpu_temp = get_cpu_temp();
-    let per_core_freqs = get_per_core_freq();
+    let cpu_temp = graph_data.cpu_temperature();
+    let per_core_freqs = get_per_core_freq(graph_data.cpu_count());
     let mut lines = vec![
-        Line::from(vec![Span::styled("Advanced System Stats", Style::default().fg(RatatuiColor::White).add_modifier(Modifier::BOLD))]),
-        Line::from(vec![Span::styled("Page Faults: ", Style::default().fg(RatatuiColor::Gray)), Span::styled(format!("{}", pgfault), Style::default().fg(RatatuiColor::White))]),
-        Line::from(vec![Span::styled("Swap In: ", Style::default().fg(RatatuiColor::Gray)), Span::styled(format!("{}", pswpin), Style::default().fg(RatatuiColor::White)), Span::raw(" | "), Span::styled("Swap Out: ", Style::default().fg(RatatuiColor::Gray)), Span::styled(format!("{}", pswpout), Style::default().fg(RatatuiColor::White))]),
-        Line::from(vec![Span::styled("IO Wait: ", Style::default().fg(RatatuiColor::Gray)), Span::styled(format!("{}", iowait), Style::default().fg(RatatuiColor::White))]),
-        Line::from(vec![Span::styled("Context Switches: ", Style::default().fg(RatatuiColor::Gray)), Span::styled(format!("{}", ctxt), Style::default().fg(RatatuiColor::White))]),
-        Line::from(vec![Span::styled("Interrupts: ", Style::default().fg(RatatuiColor::Gray)), Span::styled(format!("{}", interrupts), Style::default().fg(RatatuiColor::White))]),
-        Line::from(vec![Span::styled("Processes Since Boot Time: ", Style::default().fg(RatatuiColor::Gray)), Span::styled(format!("{}", processes), Style::default().fg(RatatuiColor::White)), Span::raw(" | "), Span::styled("Running: ", Style::default().fg(RatatuiColor::Gray)), Span::styled(format!("{}", procs_running), Style::default().fg(RatatuiColor::White)), Span::raw(" | "), Span::styled("Blocked: ", Style::default().fg(RatatuiColor::Gray)), Span::styled(format!("{}", procs_blocked), Style::default().fg(RatatuiColor::White))]),
+        Line::from(vec![Span::styled("Advanced System Stats", theme.heading)]),
+        Line::from(vec![Span::styled("Page Faults: ", theme.label), Span::styled(format!("{}", pgfault), theme.value)]),
+        Line::from(vec![Span::styled("Swap In: ", theme.label), Span::styled(format!("{}", pswpin), theme.value), Span::raw(" | "), Span::styled("Swap Out: ", theme.label), Span::styled(format!("{}", pswpout), theme.value)]),
+        Line::from(vec![Span::styled("IO Wait: ", theme.label), Span::styled(format!("{}", iowait), theme.value)]),
+        Line::from(vec![Span::styled("Context Switches: ", theme.label), Span::styled(format!("{}", ctxt), theme.value)]),
+        Line::from(vec![Span::styled("Interrupts: ", theme.label), Span::styled(format!("{}", interrupts), theme.value)]),
+        Line::from(vec![Span::styled("Processes Since Boot Time: ", theme.label), Span::styled(format!("{}", processes), theme.value), Span::raw(" | "), Span::styled("Running: ", theme.label), Span::styled(format!("{}", procs_running), theme.value), Span::raw(" | "), Span::styled("Blocked: ", theme.label), Span::styled(format!("{}", procs_blocked), theme.value)]),
     ];
     // Add CPU temperature if available, else show Unavailable
-    lines.push(Line::from(vec![Span::styled("CPU Temperature: ", Style::default().fg(RatatuiColor::Gray)),
-        Span::styled(match cpu_temp { Some(temp) => format!("{:.1} °C", temp), None => "Unavailable".to_string() }, Style::default().fg(RatatuiColor::Red))]));
+    lines.push(Line::from(vec![Span::styled("CPU Temperature: ", theme.label),
+        Span::styled(match cpu_temp { Some(temp) => format!("{:.1} °C", temp), None => "Unavailable".to_string() }, theme.series[3])]));
+    // Every thermal zone and hwmon sensor
+    let temperatures = graph_data.temperatures();
+    if !temperatures.is_empty() {
+        lines.push(Line::from(vec![Span::styled("Temperature Sensors:", theme.heading)]));
+        for (label, temp) in temperatures {
+            lines.push(Line::from(vec![Span::styled(format!("{}: ", label), theme.label), Span::styled(format!("{:.1} °C", temp), theme.value)]));
+        }
+    } else {
+        lines.push(Line::from(vec![Span::styled("Temperature Sensors: ", theme.label), Span::styled("Unavailable", theme.message_error)]));
+    }
+    // Fans and package power
+    let fans = get_fan_speeds();
+    if !fans.is_empty() {
+        lines.push(Line::from(vec![Span::styled("Fans:", theme.heading)]));
+        for (label, rpm) in &fans {
+            lines.push(Line::from(vec![Span::styled(format!("{}: ", label), theme.label), Span::styled(format!("{} RPM", rpm), theme.value)]));
+        }
+    } else {
+        lines.push(Line::from(vec![Span::styled("Fans: ", theme.label), Span::styled("Unavailable", theme.message_error)]));
+    }
+    lines.push(Line::from(vec![Span::styled("Package Power: ", theme.label),
+        match graph_data.package_power {
+            Some(watts) => Span::styled(format!("{:.1} W", watts), theme.accent),
+            None => Span::styled("Unavailable", theme.message_error),
+        }]));
     // Add per-core frequencies or Unavailable
     if !per_core_freqs.is_empty() {
-        lines.push(Line::from(vec![Span::styled("Per-Core Frequency (MHz):", Style::default().fg(RatatuiColor::Cyan).add_modifier(Modifier::BOLD))]));
+        lines.push(Line::from(vec![Span::styled("Per-Core Frequency (MHz):", theme.heading)]));
         for (i, freq) in per_core_freqs.iter().enumerate() {
-            lines.push(Line::from(vec![Span::styled(format!("Core {:2}: ", i), Style::default().fg(RatatuiColor::Gray)), Span::styled(format!("{:.0} MHz", freq), Style::default().fg(RatatuiColor::Cyan))]));
+            lines.push(Line::from(vec![Span::styled(format!("Core {:2}: ", i), theme.label), Span::styled(format!("{:.0} MHz", freq), theme.accent)]));
         }
     } else {
-        lines.push(Line::from(vec![Span::styled("Per-Core Frequency: ", Style::default().fg(RatatuiColor::Cyan)), Span::styled("Unavailable", Style::default().fg(RatatuiColor::Red))]));
+        lines.push(Line::from(vec![Span::styled("Per-Core Frequency: ", theme.label), Span::styled("Unavailable", theme.message_error)]));
     }
-    let widget = Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title("Advanced Info"));
+    let scroll = scroll.min(max_scroll(lines.len(), area));
+    let widget = Paragraph::new(lines)
+        .block(Block::default().borders(Borders::ALL).title("Advanced Info"))
+        .scroll((scroll as u16, 0));
     frame.render_widget(widget, area);
+    scroll
+}
+
+pub fn render_gpu_tab(frame: &mut ratatui::Frame, area: Rect, graph_data: &GraphData, theme: &Theme, memory_unit: MemoryUnit) {
+    let gpus = graph_data.gpus();
+    if gpus.is_empty() {
+        let widget = Paragraph::new("No GPU detected (needs nvidia-smi for NVIDIA or the amdgpu driver for AMD)")
+            .style(theme.label)
+            .block(Block::default().borders(Borders::ALL).title("GPU Info"));
+        frame.render_widget(widget, area);
+        return;
+    }
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(gpus.len() as u16 * 5 + 2),
+            Constraint::Min(8),
+        ])
+        .split(area);
+
+    let unavailable = || "Unavailable".to_string();
+    let mut lines = Vec::new();
+    for (i, gpu) in gpus.iter().enumerate() {
+        lines.push(Line::from(vec![Span::styled(format!("GPU {}: {}", i, gpu.name), theme.heading)]));
+        lines.push(Line::from(vec![Span::styled("Utilization: ", theme.label),
+            match gpu.utilization {
+                Some(usage) => Span::styled(format!("{:.0}%", usage), theme.usage(usage as f64)),
+                None => Span::styled(unavailable(), theme.message_error),
+            }]));
+        let memory = match (gpu.memory_used_mb, gpu.memory_total_mb) {
+            (Some(used), Some(total)) => format!("{} / {}", format_megabytes(used as f64, memory_unit), format_megabytes(total as f64, memory_unit)),
+            (Some(used), None) => format_megabytes(used as f64, memory_unit),
+            _ => unavailable(),
+        };
+        lines.push(Line::from(vec![Span::styled("Memory: ", theme.label), Span::styled(memory, theme.value)]));
+        lines.push(Line::from(vec![Span::styled("Temperature: ", theme.label),
+            Span::styled(gpu.temperature.map(|t| format!("{:.1} °C", t)).unwrap_or_else(unavailable), theme.value)]));
+        lines.push(Line::from(""));
+    }
+    let widget = Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title("GPU Info"));
+    frame.render_widget(widget, chunks[0]);
+
+    let gpu_data = graph_data.history_points(&graph_data.gpu_history, |value| value as f64);
+    let dataset = Dataset::default()
+        .name("GPU 0 Utilization")
+        .marker(ratatui::symbols::Marker::Braille)
+        .graph_type(GraphType::Line)
+        .style(theme.series[1])
+        .data(&gpu_data);
+    let chart = Chart::new(vec![dataset])
+        .block(Block::default()
+            .title("GPU Utilization Over Time (%)")
+            .borders(Borders::ALL))
+        .x_axis(time_axis(graph_data.history_window_secs()))
+        .y_axis(ratatui::widgets::Axis::default()
+            .bounds([0.0, 100.0])
+            .labels(vec![Span::from("0%"), Span::from("50%"), Span::from("100%")]));
+    frame.render_widget(chart, chunks[1]);
+}
+
+pub fn render_battery_tab(frame: &mut ratatui::Frame, area: Rect, graph_data: &GraphData, theme: &Theme) {
+    let Some(battery) = &graph_data.battery else {
+        let widget = Paragraph::new("No battery detected")
+            .style(theme.label)
+            .block(Block::default().borders(Borders::ALL).title("Battery"));
+        frame.render_widget(widget, area);
+        return;
+    };
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(9), Constraint::Min(8)])
+        .split(area);
+
+    let discharging = battery.status == "Discharging";
+    let rate = graph_data.battery_drain_rate();
+    let rate_str = match rate {
+        // energy_now is in µWh, so the hourly change is µW; charge_now is µAh, giving µA
+        Some(rate) if battery.energy_based => format!("{:.1} W", rate.abs() / 1_000_000.0),
+        Some(rate) => format!("{:.0} mA", rate.abs() / 1000.0),
+        None => "Measuring...".to_string(),
+    };
+    let remaining = match (rate, battery.now, battery.full) {
+        (Some(rate), Some(now), _) if discharging && rate > 0.0 => {
+            format!("{} until empty", crate::util::format_duration((now / rate * 3600.0) as u64))
+        }
+        (Some(rate), Some(now), Some(full)) if battery.status == "Charging" && rate < 0.0 => {
+            format!("{} until full", crate::util::format_duration(((full - now) / -rate * 3600.0) as u64))
+        }
+        _ => "Unknown".to_string(),
+    };
+    let capacity_str = battery.capacity.map(|c| format!("{:.0}%", c)).unwrap_or_else(|| "Unavailable".to_string());
+    let ac_str = match battery.ac_online {
+        Some(true) => "Connected",
+        Some(false) => "Disconnected",
+        None => "Unknown",
+    };
+    let lines = vec![
+        Line::from(vec![Span::styled(format!("Battery ({})", battery.name), theme.heading)]),
+        Line::from(vec![Span::styled("Charge: ", theme.label),
+            Span::styled(capacity_str, theme.usage(100.0 - battery.capacity.unwrap_or(100.0) as f64))]),
+        Line::from(vec![Span::styled("Status: ", theme.label), Span::styled(battery.status.clone(), theme.value)]),
+        Line::from(vec![Span::styled("AC Adapter: ", theme.label), Span::styled(ac_str, theme.value)]),
+        Line::from(vec![Span::styled(if discharging { "Discharge Rate: " } else { "Charge Rate: " }, theme.label), Span::styled(rate_str, theme.accent)]),
+        Line::from(vec![Span::styled("Time Remaining: ", theme.label), Span::styled(remaining, theme.value)]),
+    ];
+    let widget = Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title("Battery"));
+    frame.render_widget(widget, chunks[0]);
+
+    let battery_data = graph_data.history_points(&graph_data.battery_history, |value| value as f64);
+    let dataset = Dataset::default()
+        .name("Charge")
+        .marker(ratatui::symbols::Marker::Braille)
+        .graph_type(GraphType::Line)
+        .style(theme.series[2])
+        .data(&battery_data);
+    let chart = Chart::new(vec![dataset])
+        .block(Block::default()
+            .title("Battery Charge Over Time (%)")
+            .borders(Borders::ALL))
+        .x_axis(time_axis(graph_data.history_window_secs()))
+        .y_axis(ratatui::widgets::Axis::default()
+            .bounds([0.0, 100.0])
+            .labels(vec![Span::from("0%"), Span::from("50%"), Span::from("100%")]));
+    frame.render_widget(chart, chunks[1]);
 }
 
 fn render_cpu_graph(
     frame: &mut ratatui::Frame,
     area: Rect,
     graph_data: &GraphData,
+    theme: &Theme,
 ) {
-    let cpu_data: Vec<(f64, f64)> = graph_data
-        .get_cpu_history()
-        .iter()
-        .enumerate()
-        .map(|(i, &value)| (i as f64, value as f64))
-        .collect();
+    let cpu_data = graph_data.history_points(graph_data.get_cpu_history(), |value| value as f64);
 
     // Determine y-axis labels based on height
     let y_labels = if area.height > 15 {
@@ -685,16 +1373,14 @@ fn render_cpu_graph(
         .name("CPU Usage")
         .marker(ratatui::symbols::Marker::Braille)
         .graph_type(GraphType::Line)
-        .style(Style::default().fg(RatatuiColor::Cyan))
+        .style(theme.series[0])
         .data(&cpu_data);
 
     let chart = Chart::new(vec![dataset])
         .block(Block::default()
             .title("CPU Usage Over Time (%)")
             .borders(Borders::ALL))
-        .x_axis(ratatui::widgets::Axis::default()
-            .bounds([0.0, graph_data.max_points as f64])
-            .labels(vec![]))
+        .x_axis(time_axis(graph_data.history_window_secs()))
         .y_axis(ratatui::widgets::Axis::default()
             .bounds([0.0, 100.0])
             .labels(y_labels
@@ -709,13 +1395,10 @@ fn render_memory_graph(
     frame: &mut ratatui::Frame,
     area: Rect,
     graph_data: &GraphData,
+    theme: &Theme,
+    memory_unit: MemoryUnit,
 ) {
-    let memory_data: Vec<(f64, f64)> = graph_data
-        .get_memory_history()
-        .iter()
-        .enumerate()
-        .map(|(i, &value)| (i as f64, value as f64)) // value is already in MB
-        .collect();
+    let memory_data = graph_data.history_points(graph_data.get_memory_history(), |value| value as f64); // value is already in MB
 
     let max_memory = memory_data
         .iter()
@@ -724,22 +1407,22 @@ fn render_memory_graph(
 
     let y_labels = if area.height > 15 {
         vec![
-            format!("0 MB"),
-            format!("{:.0} MB", max_memory / 4.0),
-            format!("{:.0} MB", max_memory / 2.0),
-            format!("{:.0} MB", max_memory * 3.0 / 4.0),
-            format!("{:.0} MB", max_memory),
+            "0".to_string(),
+            format_megabytes(max_memory / 4.0, memory_unit),
+            format_megabytes(max_memory / 2.0, memory_unit),
+            format_megabytes(max_memory * 3.0 / 4.0, memory_unit),
+            format_megabytes(max_memory, memory_unit),
         ]
     } else if area.height > 10 {
         vec![
-            format!("0 MB"),
-            format!("{:.0} MB", max_memory / 2.0),
-            format!("{:.0} MB", max_memory),
+            "0".to_string(),
+            format_megabytes(max_memory / 2.0, memory_unit),
+            format_megabytes(max_memory, memory_unit),
         ]
     } else {
         vec![
-            format!("0 MB"),
-            format!("{:.0} MB", max_memory),
+            "0".to_string(),
+            format_megabytes(max_memory, memory_unit),
         ]
     };
 
@@ -747,16 +1430,14 @@ fn render_memory_graph(
         .name("Memory Usage")
         .marker(ratatui::symbols::Marker::Braille)
         .graph_type(GraphType::Line)
-        .style(Style::default().fg(RatatuiColor::Green))
+        .style(theme.series[1])
         .data(&memory_data);
 
     let chart = Chart::new(vec![dataset])
         .block(Block::default()
-            .title("Memory Usage Over Time (MB)")
+            .title("Memory Usage Over Time")
             .borders(Borders::ALL))
-        .x_axis(ratatui::widgets::Axis::default()
-            .bounds([0.0, graph_data.max_points as f64])
-            .labels(vec![]))
+        .x_axis(time_axis(graph_data.history_window_secs()))
         .y_axis(ratatui::widgets::Axis::default()
             .bounds([0.0, max_memory])
             .labels(y_labels
@@ -790,190 +1471,318 @@ fn render_memory_graph(
 // }
 //warning
 
-fn get_memory_info() -> (u64, u64, u64, u64, u64) { // Returns (total, used, free, cached, available) in KB
-    let mut total = 0;
-    let mut free = 0;
-    let mut cached = 0;
-    let mut buffers = 0;
-    let mut available = 0;
-    if let Ok(meminfo) = std::fs::read_to_string("/proc/meminfo") {
-        for line in meminfo.lines() {
-            if line.starts_with("MemTotal:") {
-                total = line.split_whitespace().nth(1).unwrap_or("0").parse().unwrap_or(0);
-            } else if line.starts_with("MemFree:") {
-                free = line.split_whitespace().nth(1).unwrap_or("0").parse().unwrap_or(0);
-            } else if line.starts_with("Cached:") {
-                cached = line.split_whitespace().nth(1).unwrap_or("0").parse().unwrap_or(0);
-            } else if line.starts_with("Buffers:") {
-                buffers = line.split_whitespace().nth(1).unwrap_or("0").parse().unwrap_or(0);
-            } else if line.starts_with("MemAvailable:") {
-                available = line.split_whitespace().nth(1).unwrap_or("0").parse().unwrap_or(0);
-            }
-        }
+fn get_memory_info(root: &Path) -> (u64, u64, u64, u64, u64) { // Returns (total, used, free, cached, available) in KB
+    let info = read_meminfo(root);
+    (info.total, info.used(), info.free, info.cached + info.buffers, info.available.unwrap_or(0))
+}
+
+fn read_meminfo(root: &Path) -> procfs_parse::MemInfo {
+    diagnostics::read_proc(root, "meminfo", "memory stats disabled")
+        .map(|text| procfs_parse::parse_meminfo(&text))
+        .unwrap_or_default()
+}
+
+// (total, used) in MB of the filesystem holding `path`, counted the way df does
+fn statvfs_usage(path: &str) -> Option<(u64, u64)> {
+    let path = std::ffi::CString::new(path).ok()?;
+    // SAFETY: statvfs is plain data, so all-zero is a valid value to be overwritten
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    // SAFETY: path is NUL-terminated and stat is a valid out-pointer
+    if unsafe { libc::statvfs(path.as_ptr(), &mut stat) } != 0 {
+        return None;
     }
-    let used = if available > 0 {
-        total - available
-    } else {
-        total - free - cached - buffers
+    let block_size = stat.f_frsize as u64;
+    let total = stat.f_blocks as u64 * block_size;
+    let used = (stat.f_blocks as u64).saturating_sub(stat.f_bfree as u64) * block_size;
+    Some((total / (1024 * 1024), used / (1024 * 1024)))
+}
+
+// GPU readings from nvidia-smi (NVIDIA) or the amdgpu sysfs files (AMD). Empty when neither is present.
+fn read_gpu_info() -> Vec<GpuInfo> {
+    let mut gpus = read_nvidia_gpus();
+    gpus.extend(read_amd_gpus());
+    gpus
+}
+
+fn read_nvidia_gpus() -> Vec<GpuInfo> {
+    // Don't keep trying to spawn a binary that isn't installed on every sample
+    static NVIDIA_SMI_MISSING: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+    if NVIDIA_SMI_MISSING.load(std::sync::atomic::Ordering::Relaxed) {
+        return Vec::new();
+    }
+    // A missing binary or a driver error just means no NVIDIA GPUs
+    let output = match std::process::Command::new("nvidia-smi")
+        .args(["--query-gpu=name,utilization.gpu,memory.used,memory.total,temperature.gpu", "--format=csv,noheader,nounits"])
+        .output()
+    {
+        Ok(output) => output,
+        Err(_) => {
+            NVIDIA_SMI_MISSING.store(true, std::sync::atomic::Ordering::Relaxed);
+            return Vec::new();
+        }
     };
-    (total, used, free, cached + buffers, available)
-}
-
-fn get_disk_stats() -> (u64, u64) { // Returns (total, used) in MB
-    if let Ok(output) = std::process::Command::new("df")
-        .arg("-BM")  // Force output in MB
-        .arg("/")
-        .output() {
-        if let Ok(output_str) = String::from_utf8(output.stdout) {
-            let lines: Vec<&str> = output_str.lines().collect();
-            if lines.len() > 1 {
-                let stats: Vec<&str> = lines[1].split_whitespace().collect();
-                if stats.len() >= 3 {
-                    let total: u64 = stats[1].trim_end_matches('M').parse().unwrap_or(0);
-                    let used: u64 = stats[2].trim_end_matches('M').parse().unwrap_or(0);
-                    return (total, used);
-                }
+    if !output.status.success() {
+        return Vec::new();
+    }
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split(',').map(|f| f.trim()).collect();
+            if fields.len() < 5 {
+                return None;
             }
+            Some(GpuInfo {
+                name: fields[0].to_string(),
+                utilization: fields[1].parse().ok(),
+                memory_used_mb: fields[2].parse().ok(),
+                memory_total_mb: fields[3].parse().ok(),
+                temperature: fields[4].parse().ok(),
+            })
+        })
+        .collect()
+}
+
+fn read_amd_gpus() -> Vec<GpuInfo> {
+    let Ok(entries) = std::fs::read_dir("/sys/class/drm") else {
+        return Vec::new();
+    };
+    let mut cards: Vec<_> = entries
+        .flatten()
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        // cardN is the GPU itself; cardN-DP-1 and friends are its connectors
+        .filter(|name| name.starts_with("card") && !name.contains('-'))
+        .collect();
+    cards.sort();
+    cards
+        .into_iter()
+        .filter_map(|card| {
+            let device = std::path::Path::new("/sys/class/drm").join(&card).join("device");
+            let read_u64 = |file: &str| -> Option<u64> {
+                std::fs::read_to_string(device.join(file)).ok()?.trim().parse().ok()
+            };
+            // Only amdgpu exposes gpu_busy_percent
+            let utilization = read_u64("gpu_busy_percent")? as f32;
+            let temperature = std::fs::read_dir(device.join("hwmon"))
+                .ok()
+                .and_then(|mut dirs| dirs.next())
+                .and_then(|dir| dir.ok())
+                .and_then(|dir| std::fs::read_to_string(dir.path().join("temp1_input")).ok())
+                .and_then(|temp| temp.trim().parse::<f64>().ok())
+                .map(|milli| milli / 1000.0);
+            Some(GpuInfo {
+                name: format!("AMD GPU ({})", card),
+                utilization: Some(utilization),
+                memory_used_mb: read_u64("mem_info_vram_used").map(|b| b / (1024 * 1024)),
+                memory_total_mb: read_u64("mem_info_vram_total").map(|b| b / (1024 * 1024)),
+                temperature,
+            })
+        })
+        .collect()
+}
+
+// Power supplies of a given kind ("Battery" or "Mains"), sorted by name
+fn power_supplies(kind: &str) -> Vec<std::path::PathBuf> {
+    let Ok(entries) = std::fs::read_dir("/sys/class/power_supply") else {
+        return Vec::new();
+    };
+    let mut supplies: Vec<_> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| std::fs::read_to_string(path.join("type")).is_ok_and(|t| t.trim() == kind))
+        .collect();
+    supplies.sort();
+    supplies
+}
+
+fn read_battery_info() -> Option<BatteryInfo> {
+    let path = power_supplies("Battery").into_iter().next()?;
+    let read = |file: &str| std::fs::read_to_string(path.join(file)).ok().map(|s| s.trim().to_string());
+    let read_f64 = |file: &str| read(file).and_then(|s| s.parse::<f64>().ok());
+    // Some batteries report energy (µWh), others only charge (µAh)
+    let (now, full, energy_based) = match read_f64("energy_now") {
+        Some(now) => (Some(now), read_f64("energy_full"), true),
+        None => (read_f64("charge_now"), read_f64("charge_full"), false),
+    };
+    let capacity = read_f64("capacity")
+        .map(|c| c as f32)
+        .or_else(|| Some((now? / full? * 100.0) as f32));
+    let ac_online = power_supplies("Mains")
+        .first()
+        .and_then(|ac| std::fs::read_to_string(ac.join("online")).ok())
+        .map(|online| online.trim() == "1");
+    Some(BatteryInfo {
+        name: path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default(),
+        capacity,
+        status: read("status").unwrap_or_else(|| "Unknown".to_string()),
+        now,
+        full,
+        energy_based,
+        ac_online,
+    })
+}
+
+// Fan speeds from hwmon as (label, RPM), labeled like the temperature sensors
+fn get_fan_speeds() -> Vec<(String, u64)> {
+    let Ok(entries) = std::fs::read_dir("/sys/class/hwmon") else {
+        return Vec::new();
+    };
+    let mut hwmons: Vec<_> = entries.flatten().map(|e| e.path()).collect();
+    hwmons.sort();
+    let mut fans = Vec::new();
+    for hwmon in hwmons {
+        let chip = std::fs::read_to_string(hwmon.join("name")).map(|n| n.trim().to_string()).unwrap_or_default();
+        let Ok(files) = std::fs::read_dir(&hwmon) else { continue };
+        let mut inputs: Vec<String> = files
+            .flatten()
+            .map(|f| f.file_name().to_string_lossy().to_string())
+            .filter(|name| name.starts_with("fan") && name.ends_with("_input"))
+            .collect();
+        inputs.sort();
+        for input in inputs {
+            let Some(rpm) = std::fs::read_to_string(hwmon.join(&input)).ok().and_then(|v| v.trim().parse::<u64>().ok()) else {
+                continue;
+            };
+            let sensor = input.trim_end_matches("_input");
+            let label = std::fs::read_to_string(hwmon.join(format!("{}_label", sensor)))
+                .map(|l| l.trim().to_string())
+                .unwrap_or_else(|_| sensor.to_string());
+            fans.push((format!("{} {}", chip, label), rpm));
         }
     }
-    (0, 0)
+    fans
+}
+
+// Hottest CPU sensor, falling back to thermal_zone0 when no sensor is labeled as the CPU
+fn cpu_temp(temps: &[(String, f64)]) -> Option<f64> {
+    temps
+        .iter()
+        .filter(|(label, _)| is_cpu_sensor(label))
+        .map(|(_, temp)| *temp)
+        .fold(None, |hottest: Option<f64>, temp| Some(hottest.map_or(temp, |h| h.max(temp))))
+        .or_else(|| temps.iter().find(|(label, _)| label.starts_with("thermal_zone0 ")).map(|(_, temp)| *temp))
+}
+
+fn is_cpu_sensor(label: &str) -> bool {
+    let label = label.to_lowercase();
+    ["cpu", "x86_pkg_temp", "coretemp", "k10temp", "zenpower", "package", "tctl", "tdie"]
+        .iter()
+        .any(|name| label.contains(name))
 }
 
-// Add these new helper functions
-fn get_cpu_temp() -> Option<f64> {
-    if let Ok(temp) = std::fs::read_to_string("/sys/class/thermal/thermal_zone0/temp") {
-        if let Ok(temp_val) = temp.trim().parse::<u32>() {
-            return Some(temp_val as f64 / 1000.0);
+// Every readable temperature sensor as (label, °C): thermal zones labeled by their `type`,
+// then hwmon sensors labeled by chip name and the sensor's own label when it has one.
+// Unreadable files (permissions, sensors that vanish) are skipped.
+fn get_temperatures() -> Vec<(String, f64)> {
+    let read_millidegrees = |path: &std::path::Path| -> Option<f64> {
+        Some(std::fs::read_to_string(path).ok()?.trim().parse::<i64>().ok()? as f64 / 1000.0)
+    };
+    let sorted_entries = |dir: &str, prefix: &str| -> Vec<std::path::PathBuf> {
+        let mut paths: Vec<_> = std::fs::read_dir(dir)
+            .map(|entries| entries.flatten().map(|e| e.path()).collect())
+            .unwrap_or_default();
+        paths.retain(|p| p.file_name().is_some_and(|n| n.to_string_lossy().starts_with(prefix)));
+        paths.sort();
+        paths
+    };
+
+    let mut temps = Vec::new();
+    for zone in sorted_entries("/sys/class/thermal", "thermal_zone") {
+        let Some(temp) = read_millidegrees(&zone.join("temp")) else { continue };
+        let zone_name = zone.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        let kind = std::fs::read_to_string(zone.join("type")).map(|t| t.trim().to_string()).unwrap_or_default();
+        temps.push((format!("{} ({})", zone_name, kind), temp));
+    }
+    for hwmon in sorted_entries("/sys/class/hwmon", "hwmon") {
+        let chip = std::fs::read_to_string(hwmon.join("name")).map(|n| n.trim().to_string()).unwrap_or_default();
+        let mut inputs = sorted_entries(&hwmon.to_string_lossy(), "temp");
+        inputs.retain(|p| p.to_string_lossy().ends_with("_input"));
+        for input in inputs {
+            let Some(temp) = read_millidegrees(&input) else { continue };
+            let sensor = input.file_name().map(|n| n.to_string_lossy().trim_end_matches("_input").to_string()).unwrap_or_default();
+            let label = std::fs::read_to_string(hwmon.join(format!("{}_label", sensor)))
+                .map(|l| l.trim().to_string())
+                .unwrap_or(sensor);
+            temps.push((format!("{} {}", chip, label), temp));
         }
     }
-    None
+    temps
 }
 
-fn get_per_core_freq() -> Vec<f64> {
+fn get_per_core_freq(cpu_count: usize) -> Vec<f64> {
     let mut freqs = Vec::new();
-    let cpu_count = get_cpu_count();
     
     for i in 0..cpu_count {
-        if let Ok(freq) = std::fs::read_to_string(format!("/sys/devices/system/cpu/cpu{}/cpufreq/scaling_cur_freq", i)) {
-            if let Ok(freq_val) = freq.trim().parse::<u32>() {
-                freqs.push(freq_val as f64 / 1000.0); // Convert to MHz
-            }
+        if let Ok(freq) = std::fs::read_to_string(format!("/sys/devices/system/cpu/cpu{}/cpufreq/scaling_cur_freq", i))
+            && let Ok(freq_val) = freq.trim().parse::<u32>()
+        {
+            freqs.push(freq_val as f64 / 1000.0); // Convert to MHz
         }
     }
     freqs
 }
 
-fn get_cpu_stats() -> (u64, u64, u64, u64, u64) { // Returns (ctxt, processes, procs_running, procs_blocked, interrupts)
-    let mut ctxt = 0;
-    let mut processes = 0;
-    let mut procs_running = 0;
-    let mut procs_blocked = 0;
-    let mut interrupts = 0;
-
-    if let Ok(stat) = std::fs::read_to_string("/proc/stat") {
-        for line in stat.lines() {
-            match line.split_whitespace().next() {
-                Some("ctxt") => {
-                    ctxt = line.split_whitespace().nth(1).unwrap_or("0").parse().unwrap_or(0);
-                }
-                Some("processes") => {
-                    processes = line.split_whitespace().nth(1).unwrap_or("0").parse().unwrap_or(0);
-                }
-                Some("procs_running") => {
-                    procs_running = line.split_whitespace().nth(1).unwrap_or("0").parse().unwrap_or(0);
-                }
-                Some("procs_blocked") => {
-                    procs_blocked = line.split_whitespace().nth(1).unwrap_or("0").parse().unwrap_or(0);
-                }
-                Some("intr") => {
-                    interrupts = line.split_whitespace().nth(1).unwrap_or("0").parse().unwrap_or(0);
-                }
-                _ => {}
-            }
-        }
-    }
-    (ctxt, processes, procs_running, procs_blocked, interrupts)
+fn get_cpu_stats(root: &Path) -> (u64, u64, u64, u64, u64) { // Returns (ctxt, processes, procs_running, procs_blocked, interrupts)
+    let stat = read_stat(root);
+    (stat.ctxt, stat.processes, stat.procs_running, stat.procs_blocked, stat.interrupts)
 }
 
-fn get_vm_stats() -> (u64, u64, u64, u64) { // Returns (page_faults, swap_in, swap_out, io_wait)
-    let mut pgfault = 0;
-    let mut pswpin = 0;
-    let mut pswpout = 0;
-    let mut iowait = 0;
-
-    if let Ok(vmstat) = std::fs::read_to_string("/proc/vmstat") {
-        for line in vmstat.lines() {
-            match line.split_whitespace().next() {
-                Some("pgfault") => {
-                    pgfault = line.split_whitespace().nth(1).unwrap_or("0").parse().unwrap_or(0);
-                }
-                Some("pswpin") => {
-                    pswpin = line.split_whitespace().nth(1).unwrap_or("0").parse().unwrap_or(0);
-                }
-                Some("pswpout") => {
-                    pswpout = line.split_whitespace().nth(1).unwrap_or("0").parse().unwrap_or(0);
-                }
-                _ => {}
-            }
-        }
-    }
+fn read_stat(root: &Path) -> procfs_parse::Stat {
+    diagnostics::read_proc(root, "stat", "CPU usage disabled")
+        .map(|text| procfs_parse::parse_stat(&text))
+        .unwrap_or_default()
+}
 
-    // Get IO wait from /proc/stat
-    if let Ok(stat) = std::fs::read_to_string("/proc/stat") {
-        if let Some(cpu_line) = stat.lines().next() {
-            let values: Vec<u64> = cpu_line.split_whitespace()
-                .skip(1)
-                .filter_map(|val| val.parse().ok())
-                .collect();
-            if values.len() >= 6 {
-                iowait = values[4]; // iowait is the 5th value
-            }
-        }
-    }
+fn get_vm_stats(root: &Path) -> (u64, u64, u64, u64) { // Returns (page_faults, swap_in, swap_out, io_wait)
+    let vmstat = diagnostics::read_proc(root, "vmstat", "paging stats disabled")
+        .map(|text| procfs_parse::parse_vmstat(&text))
+        .unwrap_or_default();
+    // IO wait comes from the aggregate cpu line of /proc/stat
+    (vmstat.pgfault, vmstat.pswpin, vmstat.pswpout, read_stat(root).cpu.iowait())
+}
 
-    (pgfault, pswpin, pswpout, iowait)
+fn read_uptime(root: &Path) -> Option<f64> {
+    procfs_parse::parse_uptime(&diagnostics::read_proc(root, "uptime", "uptime disabled")?)
 }
 
-fn get_boot_time() -> (String, String) { // Returns (boot_time, last_reboot)
+fn get_boot_time(root: &Path) -> (String, String) { // Returns (boot_time, last_reboot)
     let mut boot_time = String::from("Unknown");
-    let mut last_reboot = String::from("Unknown");
-
-    if let Ok(uptime) = std::fs::read_to_string("/proc/uptime") {
-        if let Some(secs_str) = uptime.split_whitespace().next() {
-            if let Ok(secs) = secs_str.parse::<f64>() {
-                let now = std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .unwrap_or_default()
-                    .as_secs() as f64;
-                let boot_timestamp = now - secs;
-                
-                // Format boot time using DateTime::from_timestamp
-                let datetime = chrono::DateTime::from_timestamp(boot_timestamp as i64, 0)
-                    .unwrap_or_default()
-                    .naive_local();
-                boot_time = datetime.format("%Y-%m-%d %H:%M:%S").to_string();
-                
-                // Try to get last reboot from wtmp (if available)
-                if let Ok(output) = std::process::Command::new("last")
-                    .arg("-x")
-                    .arg("reboot")
-                    .arg("-F")
-                    .output() {
-                    if let Ok(output_str) = String::from_utf8(output.stdout) {
-                        if let Some(last_reboot_line) = output_str.lines().next() {
-                            last_reboot = last_reboot_line.to_string();
-                        }
-                    }
-                }
-            }
-        }
+
+    if let Some(secs) = read_uptime(root) {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs() as f64;
+        let boot_timestamp = now - secs;
+        
+        // Format boot time using DateTime::from_timestamp
+        let datetime = chrono::DateTime::from_timestamp(boot_timestamp as i64, 0)
+            .unwrap_or_default()
+            .naive_local();
+        boot_time = datetime.format("%Y-%m-%d %H:%M:%S").to_string();
     }
-    (boot_time, last_reboot)
+    (boot_time, get_last_reboot().to_string())
 }
 
-fn get_cpu_count() -> usize {
-    if let Ok(cpuinfo) = std::fs::read_to_string("/proc/cpuinfo") {
-        return cpuinfo.lines().filter(|line| line.starts_with("processor")).count();
+// Last reboot from wtmp (if available). Spawning `last` is slow, and the answer can't change while
+// we run, so it is asked once.
+fn get_last_reboot() -> &'static str {
+    static LAST_REBOOT: std::sync::OnceLock<String> = std::sync::OnceLock::new();
+    LAST_REBOOT.get_or_init(|| {
+        std::process::Command::new("last")
+            .arg("-x")
+            .arg("reboot")
+            .arg("-F")
+            .output()
+            .ok()
+            .and_then(|output| String::from_utf8(output.stdout).ok())
+            .and_then(|output| output.lines().next().map(str::to_string))
+            .unwrap_or_else(|| "Unknown".to_string())
+    })
+}
+
+pub fn get_cpu_count(root: &Path) -> usize {
+    if let Ok(cpuinfo) = std::fs::read_to_string(root.join("cpuinfo")) {
+        return procfs_parse::parse_cpuinfo(&cpuinfo).processors;
     }
     1
 }
@@ -990,103 +1799,52 @@ fn get_os_info() -> String {
         })
         .unwrap_or_else(|_| "Unknown".to_string())
 }
-fn get_system_uptime() -> String {
-    if let Ok(uptime) = std::fs::read_to_string("/proc/uptime") {
-        if let Some(secs_str) = uptime.split_whitespace().next() {
-            if let Ok(secs) = secs_str.parse::<f64>() {
-                let days = (secs / 86400.0) as u64;
-                let hours = ((secs % 86400.0) / 3600.0) as u64;
-                let minutes = ((secs % 3600.0) / 60.0) as u64;
-                return format!("{}d {}h {}m", days, hours, minutes);
-            }
-        }
+fn get_system_uptime(root: &Path) -> String {
+    if let Some(secs) = read_uptime(root) {
+        let days = (secs / 86400.0) as u64;
+        let hours = ((secs % 86400.0) / 3600.0) as u64;
+        let minutes = ((secs % 3600.0) / 60.0) as u64;
+        let seconds = (secs % 60.0) as u64;
+        return format!("{}d {}h {}m {}s", days, hours, minutes, seconds);
     }
     "Unknown".to_string()
 }
-fn get_cpu_details() -> (String, String, String) {
-    let mut model = String::new();
-    let mut freq = String::new();
-    let mut cache = String::new();
-    if let Ok(cpuinfo) = std::fs::read_to_string("/proc/cpuinfo") {
-        for line in cpuinfo.lines() {
-            if line.starts_with("model name") {
-                model = line.split(':').nth(1).unwrap_or("").trim().to_string();
-            } else if line.starts_with("cpu MHz") {
-                freq = format!("{:.2} MHz", line.split(':').nth(1).unwrap_or("0").trim().parse::<f64>().unwrap_or(0.0));
-            } else if line.starts_with("cache size") {
-                cache = line.split(':').nth(1).unwrap_or("").trim().to_string();
-            }
-        }
-    }
-    (model, freq, cache)
-}
-fn get_load_average() -> (f64, f64, f64) {
-    if let Ok(loadavg) = std::fs::read_to_string("/proc/loadavg") {
-        let values: Vec<f64> = loadavg.split_whitespace().take(3).filter_map(|s| s.parse().ok()).collect();
-        if values.len() == 3 {
-            return (values[0], values[1], values[2]);
-        }
-    }
-    (0.0, 0.0, 0.0)
+fn get_cpu_details(root: &Path) -> (String, String, String) {
+    let info = std::fs::read_to_string(root.join("cpuinfo"))
+        .map(|text| procfs_parse::parse_cpuinfo(&text))
+        .unwrap_or_default();
+    let freq = format!("{:.2} MHz", info.mhz.unwrap_or(0.0));
+    (info.model, freq, info.cache)
 }
-fn get_usage_style(usage: f64) -> ratatui::style::Style {
-    use ratatui::style::Color as RatatuiColor;
-    match usage {
-        u if u > 90.0 => ratatui::style::Style::default().fg(RatatuiColor::Red),
-        u if u > 70.0 => ratatui::style::Style::default().fg(RatatuiColor::Yellow),
-        _ => ratatui::style::Style::default().fg(RatatuiColor::Green),
-    }
+fn get_load_average(root: &Path) -> (f64, f64, f64) {
+    diagnostics::read_proc(root, "loadavg", "load average disabled")
+        .and_then(|text| procfs_parse::parse_loadavg(&text))
+        .unwrap_or((0.0, 0.0, 0.0))
 }
 
-// Helper: Simulate or get disk read/write speeds (MB/s)
-fn get_disk_rw_speed() -> (f64, f64) {
-    #[cfg(target_os = "linux")]
-    {
-        // use std::sync::Mutex; delete after debugging
-        use std::time::Instant;
-        static mut LAST_READ: Option<(u64, u64, Instant)> = None;
-        let mut read_bytes = 0u64;
-        let mut write_bytes = 0u64;
-        if let Ok(stats) = std::fs::read_to_string("/proc/diskstats") {
-            for line in stats.lines() {
-                if line.contains(" sda ") || line.contains(" vda ") || line.contains(" nvme0n1 ") {
-                    let parts: Vec<&str> = line.split_whitespace().collect();
-                    if parts.len() > 9 {
-                        let sectors_read: u64 = parts[5].parse().unwrap_or(0);
-                        let sectors_written: u64 = parts[9].parse().unwrap_or(0);
-                        // Assume 512 bytes per sector
-                        read_bytes = sectors_read * 512;
-                        write_bytes = sectors_written * 512;
-                    }
-                }
-            }
-        }
-        let now = Instant::now();
-        unsafe {
-            if let Some((last_read, last_write, last_time)) = LAST_READ {
-                let dt = now.duration_since(last_time).as_secs_f64().max(0.1);
-                let read_speed = (read_bytes.saturating_sub(last_read)) as f64 / 1_048_576.0 / dt;
-                let write_speed = (write_bytes.saturating_sub(last_write)) as f64 / 1_048_576.0 / dt;
-                LAST_READ = Some((read_bytes, write_bytes, now));
-                (read_speed, write_speed)
-            } else {
-                LAST_READ = Some((read_bytes, write_bytes, now));
-                (0.0, 0.0)
+// Total bytes (read, written) on the main disk since boot, from /proc/diskstats
+fn read_disk_bytes(root: &Path) -> Option<(u64, u64)> {
+    let stats = diagnostics::read_proc(root, "diskstats", "disk read/write speeds disabled")?;
+    let mut totals = None;
+    for line in stats.lines() {
+        if line.contains(" sda ") || line.contains(" vda ") || line.contains(" nvme0n1 ") {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.len() > 9 {
+                let sectors_read: u64 = parts[5].parse().unwrap_or(0);
+                let sectors_written: u64 = parts[9].parse().unwrap_or(0);
+                // Assume 512 bytes per sector
+                totals = Some((sectors_read * 512, sectors_written * 512));
             }
         }
     }
-    #[cfg(not(target_os = "linux"))]
-    {
-        // Simulate values for non-Linux
-        (0.0, 0.0)
-    }
+    totals
 }
 
 // Helper: Get storage type (filesystem)
-fn get_storage_type() -> String {
+fn get_storage_type(root: &Path) -> String {
     #[cfg(target_os = "linux")]
     {
-        if let Ok(mounts) = std::fs::read_to_string("/proc/mounts") {
+        if let Ok(mounts) = std::fs::read_to_string(root.join("mounts")) {
             for line in mounts.lines() {
                 let parts: Vec<&str> = line.split_whitespace().collect();
                 if parts.len() > 2 && parts[1] == "/" {
@@ -1127,7 +1885,7 @@ fn get_process_state_counts_from_status(processes: &[ProcessInfo]) -> std::colle
 }
 
 // Add this function to get total CPU usage like htop/top
-fn get_total_cpu_usage() -> f32 {
+fn get_total_cpu_usage(root: &Path) -> f32 {
     use std::sync::OnceLock;
     static LAST_TOTAL: OnceLock<std::sync::Mutex<(u64, u64)>> = OnceLock::new();
     let last_idle;
@@ -1138,25 +1896,21 @@ fn get_total_cpu_usage() -> f32 {
         last_idle = li;
         last_total = lt;
     }
-    if let Ok(stat) = std::fs::read_to_string("/proc/stat") {
-        if let Some(line) = stat.lines().next() {
-            let values: Vec<u64> = line.split_whitespace()
-                .skip(1)
-                .filter_map(|val| val.parse().ok())
-                .collect();
-            if values.len() >= 4 {
-                let idle = values[3];
-                let total: u64 = values.iter().sum();
-                let idle_delta = idle - last_idle;
-                let total_delta = total - last_total;
-                // Update static for next call
-                let lock = LAST_TOTAL.get_or_init(|| std::sync::Mutex::new((0, 0)));
-                *lock.lock().unwrap() = (idle, total);
-                if total_delta > 0 {
-                    return 100.0 * (1.0 - (idle_delta as f32 / total_delta as f32));
-                }
+    if let Ok(text) = std::fs::read_to_string(root.join("stat")) {
+        let times = procfs_parse::parse_stat(&text).cpu;
+        if times.0.len() >= 4 {
+            let idle = times.idle();
+            let total = times.total();
+            let idle_delta = idle - last_idle;
+            let total_delta = total - last_total;
+            // Update static for next call
+            let lock = LAST_TOTAL.get_or_init(|| std::sync::Mutex::new((0, 0)));
+            *lock.lock().unwrap() = (idle, total);
+            if total_delta > 0 {
+                return 100.0 * (1.0 - (idle_delta as f32 / total_delta as f32));
             }
         }
     }
     0.0
 }
+
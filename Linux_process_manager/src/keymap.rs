@@ -0,0 +1,498 @@
+//! Keybinding module
+// Maps logical actions to keys so the defaults can be remapped from the [keys] table of the
+// config file, e.g. `quit = "x"` or `down = ["Down", "n"]`.
+
+use crossterm::event::KeyCode;
+use std::collections::HashMap;
+
+/// Logical actions that can be bound to keys.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Action {
+    Quit,
+    Statistics,
+    QuickFilter,
+    ToggleSortOrder,
+    Pin,
+    Up,
+    Down,
+    PageUp,
+    PageDown,
+    Top,
+    Bottom,
+    FilterSortMenu,
+    ChangeNiceMenu,
+    KillStopMenu,
+    PerProcessGraph,
+    ProcessLog,
+    Help,
+    ColumnsMenu,
+    Environment,
+    ToggleKernelThreads,
+    SortByCpu,
+    SortByMemory,
+    ToggleRunningOnly,
+    JumpToPid,
+    GroupByName,
+    Snapshot,
+    CopyPid,
+    CopyCommand,
+    Watch,
+    Sockets,
+    Highlight,
+    CpuScale,
+    ResetView,
+    Compact,
+    NextMatch,
+    PreviousMatch,
+    RestartWithSudo,
+    Kill,
+    Stop,
+    Continue,
+    Terminate,
+    TerminateThenKill,
+    KillTree,
+    StopTree,
+    ContinueTree,
+    Restart,
+    ByName,
+    ByPort,
+    OomScoreAdj,
+    UndoNice,
+    GraphsTab,
+    OverviewTab,
+    CpuTab,
+    MemoryTab,
+    DiskTab,
+    ProcessesTab,
+    AdvancedTab,
+    GpuTab,
+    BatteryTab,
+    ExportGraphs,
+    MaximizeCpuGraph,
+    MaximizeMemoryGraph,
+    GroupLog,
+    UngroupLog,
+    ClearLog,
+    CycleLogSort,
+    PreviousProcess,
+    NextProcess,
+    ToggleCompare,
+    ClearCompare,
+    ZoomIn,
+    ZoomOut,
+    ToggleVirtualMemory,
+    ToggleOverlay,
+    ToggleSmaps,
+    PageMaps,
+    PageSmaps,
+    PageStack,
+    ToggleColumn,
+    MoveColumnUp,
+    MoveColumnDown,
+    ToggleGroupBy,
+    GroupSortCpu,
+    GroupSortMemory,
+    GroupSortCount,
+    Reload,
+    RevealSecrets,
+}
+
+// Config name and default keys for every action
+const DEFAULT_BINDINGS: &[(Action, &str, &[KeyCode])] = &[
+    (Action::Quit, "quit", &[KeyCode::Char('q')]),
+    (Action::Statistics, "statistics", &[KeyCode::Char('s'), KeyCode::Char('S')]),
+    (Action::QuickFilter, "quick_filter", &[KeyCode::Char('/')]),
+    (Action::ToggleSortOrder, "toggle_sort_order", &[KeyCode::Char('a')]),
+    (Action::Pin, "pin", &[KeyCode::Char('p')]),
+    (Action::Up, "up", &[KeyCode::Up, KeyCode::Char('k')]),
+    (Action::Down, "down", &[KeyCode::Down, KeyCode::Char('j')]),
+    (Action::PageUp, "page_up", &[KeyCode::PageUp]),
+    (Action::PageDown, "page_down", &[KeyCode::PageDown]),
+    (Action::Top, "top", &[KeyCode::Home, KeyCode::Char('g')]),
+    (Action::Bottom, "bottom", &[KeyCode::End, KeyCode::Char('G')]),
+    (Action::FilterSortMenu, "filter_sort_menu", &[KeyCode::Char('1')]),
+    (Action::ChangeNiceMenu, "change_nice_menu", &[KeyCode::Char('2')]),
+    (Action::KillStopMenu, "kill_stop_menu", &[KeyCode::Char('3')]),
+    (Action::PerProcessGraph, "per_process_graph", &[KeyCode::Char('4')]),
+    (Action::ProcessLog, "process_log", &[KeyCode::Char('5')]),
+    (Action::Help, "help", &[KeyCode::Char('6')]),
+    (Action::ColumnsMenu, "columns_menu", &[KeyCode::Char('c')]),
+    (Action::Environment, "environment", &[KeyCode::Char('e')]),
+    (Action::ToggleKernelThreads, "toggle_kernel_threads", &[KeyCode::Char('K')]),
+    (Action::SortByCpu, "sort_by_cpu", &[KeyCode::Char('C')]),
+    (Action::SortByMemory, "sort_by_memory", &[KeyCode::Char('M')]),
+    (Action::ToggleRunningOnly, "toggle_running_only", &[KeyCode::Char('R')]),
+    (Action::JumpToPid, "jump_to_pid", &[KeyCode::Char(':')]),
+    (Action::GroupByName, "group_by_name", &[KeyCode::Char('A')]),
+    (Action::Snapshot, "snapshot", &[KeyCode::Char('x')]),
+    (Action::CopyPid, "copy_pid", &[KeyCode::Char('y')]),
+    (Action::CopyCommand, "copy_command", &[KeyCode::Char('Y')]),
+    (Action::Watch, "watch", &[KeyCode::Char('w')]),
+    (Action::Sockets, "sockets", &[KeyCode::Char('o')]),
+    (Action::Highlight, "highlight", &[KeyCode::Char('*')]),
+    (Action::CpuScale, "cpu_scale", &[KeyCode::Char('%')]),
+    (Action::ResetView, "reset_view", &[KeyCode::Char('0')]),
+    (Action::Compact, "compact", &[KeyCode::Char('z')]),
+    (Action::NextMatch, "next_match", &[KeyCode::Char('n')]),
+    (Action::PreviousMatch, "previous_match", &[KeyCode::Char('N')]),
+    (Action::RestartWithSudo, "restart_with_sudo", &[KeyCode::Char('U')]),
+    (Action::Kill, "kill", &[KeyCode::Char('k')]),
+    (Action::Stop, "stop", &[KeyCode::Char('s')]),
+    (Action::Continue, "continue", &[KeyCode::Char('c')]),
+    (Action::Terminate, "terminate", &[KeyCode::Char('t')]),
+    (Action::TerminateThenKill, "terminate_then_kill", &[KeyCode::Char('n')]),
+    (Action::KillTree, "kill_tree", &[KeyCode::Char('T')]),
+    (Action::StopTree, "stop_tree", &[KeyCode::Char('S')]),
+    (Action::ContinueTree, "continue_tree", &[KeyCode::Char('C')]),
+    (Action::Restart, "restart", &[KeyCode::Char('r')]),
+    (Action::ByName, "by_name", &[KeyCode::Char('N')]),
+    (Action::ByPort, "by_port", &[KeyCode::Char('P')]),
+    (Action::OomScoreAdj, "oom_score_adj", &[KeyCode::Char('O')]),
+    (Action::UndoNice, "undo_nice", &[KeyCode::Char('u')]),
+    (Action::GraphsTab, "graphs_tab", &[KeyCode::Char('1')]),
+    (Action::OverviewTab, "overview_tab", &[KeyCode::Char('2')]),
+    (Action::CpuTab, "cpu_tab", &[KeyCode::Char('3')]),
+    (Action::MemoryTab, "memory_tab", &[KeyCode::Char('4')]),
+    (Action::DiskTab, "disk_tab", &[KeyCode::Char('5')]),
+    (Action::ProcessesTab, "processes_tab", &[KeyCode::Char('6')]),
+    (Action::AdvancedTab, "advanced_tab", &[KeyCode::Char('7')]),
+    (Action::GpuTab, "gpu_tab", &[KeyCode::Char('8')]),
+    (Action::BatteryTab, "battery_tab", &[KeyCode::Char('9')]),
+    (Action::ExportGraphs, "export_graphs", &[KeyCode::Char('e')]),
+    (Action::MaximizeCpuGraph, "maximize_cpu_graph", &[KeyCode::Char('c')]),
+    (Action::MaximizeMemoryGraph, "maximize_memory_graph", &[KeyCode::Char('m')]),
+    (Action::GroupLog, "group_log", &[KeyCode::Char('g')]),
+    (Action::UngroupLog, "ungroup_log", &[KeyCode::Char('u')]),
+    (Action::ClearLog, "clear_log", &[KeyCode::Char('c')]),
+    (Action::CycleLogSort, "cycle_log_sort", &[KeyCode::Char('s')]),
+    (Action::PreviousProcess, "previous_process", &[KeyCode::Left]),
+    (Action::NextProcess, "next_process", &[KeyCode::Right]),
+    (Action::ToggleCompare, "toggle_compare", &[KeyCode::Char('c')]),
+    (Action::ClearCompare, "clear_compare", &[KeyCode::Char('C')]),
+    (Action::ZoomIn, "zoom_in", &[KeyCode::Char('+'), KeyCode::Char('=')]),
+    (Action::ZoomOut, "zoom_out", &[KeyCode::Char('-')]),
+    (Action::ToggleVirtualMemory, "toggle_virtual_memory", &[KeyCode::Char('v')]),
+    (Action::ToggleOverlay, "toggle_overlay", &[KeyCode::Char('o')]),
+    (Action::ToggleSmaps, "toggle_smaps", &[KeyCode::Char('p')]),
+    (Action::PageMaps, "page_maps", &[KeyCode::Char('m')]),
+    (Action::PageSmaps, "page_smaps", &[KeyCode::Char('M')]),
+    (Action::PageStack, "page_stack", &[KeyCode::Char('t')]),
+    (Action::ToggleColumn, "toggle_column", &[KeyCode::Char(' '), KeyCode::Enter]),
+    (Action::MoveColumnUp, "move_column_up", &[KeyCode::Char('K')]),
+    (Action::MoveColumnDown, "move_column_down", &[KeyCode::Char('J')]),
+    (Action::ToggleGroupBy, "toggle_group_by", &[KeyCode::Char('u')]),
+    (Action::GroupSortCpu, "group_sort_cpu", &[KeyCode::Char('c')]),
+    (Action::GroupSortMemory, "group_sort_memory", &[KeyCode::Char('m')]),
+    (Action::GroupSortCount, "group_sort_count", &[KeyCode::Char('n')]),
+    (Action::Reload, "reload", &[KeyCode::Char('r')]),
+    (Action::RevealSecrets, "reveal_secrets", &[KeyCode::Char('r')]),
+];
+
+/// Actions available on the main process list. No two may share a key.
+pub const PROCESS_LIST_ACTIONS: &[Action] = &[
+    Action::Quit,
+    Action::Statistics,
+    Action::QuickFilter,
+    Action::ToggleSortOrder,
+    Action::Pin,
+    Action::Up,
+    Action::Down,
+    Action::PageUp,
+    Action::PageDown,
+    Action::Top,
+    Action::Bottom,
+    Action::FilterSortMenu,
+    Action::ChangeNiceMenu,
+    Action::KillStopMenu,
+    Action::PerProcessGraph,
+    Action::ProcessLog,
+    Action::Help,
+    Action::ColumnsMenu,
+    Action::Environment,
+    Action::ToggleKernelThreads,
+    Action::SortByCpu,
+    Action::SortByMemory,
+    Action::ToggleRunningOnly,
+    Action::JumpToPid,
+    Action::GroupByName,
+    Action::Snapshot,
+    Action::CopyPid,
+    Action::CopyCommand,
+    Action::Watch,
+    Action::Sockets,
+    Action::Highlight,
+    Action::CpuScale,
+    Action::ResetView,
+    Action::Compact,
+    Action::NextMatch,
+    Action::PreviousMatch,
+    Action::RestartWithSudo,
+];
+
+/// Cursor and scroll movement, shared by every list and scrolled view.
+pub const NAVIGATION_ACTIONS: &[Action] = &[Action::Up, Action::Down, Action::PageUp, Action::PageDown, Action::Top, Action::Bottom];
+
+/// Keys of the kill/stop menu while choosing a process.
+pub const KILL_STOP_MENU_ACTIONS: &[Action] = &[Action::Up, Action::Down, Action::PageUp, Action::PageDown, Action::Top, Action::Bottom, Action::ByName, Action::ByPort];
+
+/// Keys of the nice menu while choosing a process.
+pub const NICE_MENU_ACTIONS: &[Action] = &[Action::Up, Action::Down, Action::PageUp, Action::PageDown, Action::Top, Action::Bottom, Action::ByName, Action::OomScoreAdj, Action::UndoNice];
+
+/// Actions offered once a process is chosen in the kill/stop menu.
+pub const KILL_STOP_ACTIONS: &[Action] = &[Action::Kill, Action::Stop, Action::Continue, Action::Terminate, Action::TerminateThenKill, Action::KillTree, Action::StopTree, Action::ContinueTree, Action::Restart];
+
+/// Actions of the statistics dashboard. Quit and statistics both return to the process list;
+/// Esc always does too unless it's bound here.
+pub const STATISTICS_ACTIONS: &[Action] = &[
+    Action::Quit,
+    Action::Statistics,
+    Action::Up,
+    Action::Down,
+    Action::PageUp,
+    Action::PageDown,
+    Action::Top,
+    Action::Bottom,
+    Action::GraphsTab,
+    Action::OverviewTab,
+    Action::CpuTab,
+    Action::MemoryTab,
+    Action::DiskTab,
+    Action::ProcessesTab,
+    Action::AdvancedTab,
+    Action::GpuTab,
+    Action::BatteryTab,
+    Action::ExportGraphs,
+    Action::MaximizeCpuGraph,
+    Action::MaximizeMemoryGraph,
+];
+
+/// Actions of the process exit log. `top` is left out because its default 'g' groups the log
+/// here; Home jumps to the top instead, and Esc returns like quit, unless bound here.
+pub const PROCESS_LOG_ACTIONS: &[Action] = &[
+    Action::Quit,
+    Action::QuickFilter,
+    Action::ToggleSortOrder,
+    Action::Up,
+    Action::Down,
+    Action::PageUp,
+    Action::PageDown,
+    Action::Bottom,
+    Action::GroupLog,
+    Action::UngroupLog,
+    Action::ClearLog,
+    Action::CycleLogSort,
+];
+
+/// Actions of the per-process graph. Quit returns to the process list; Esc first goes back from
+/// a graph to the selection list.
+pub const PER_PROCESS_GRAPH_ACTIONS: &[Action] = &[
+    Action::Quit,
+    Action::Up,
+    Action::Down,
+    Action::QuickFilter,
+    Action::PreviousProcess,
+    Action::NextProcess,
+    Action::ToggleCompare,
+    Action::ClearCompare,
+    Action::ZoomIn,
+    Action::ZoomOut,
+    Action::ToggleVirtualMemory,
+    Action::ToggleOverlay,
+    Action::ToggleSmaps,
+    Action::CopyPid,
+    Action::CopyCommand,
+    Action::PageMaps,
+    Action::PageSmaps,
+    Action::PageStack,
+];
+
+/// Actions of the columns menu. Quit (or Esc) saves and returns.
+pub const COLUMNS_ACTIONS: &[Action] = &[Action::Quit, Action::Up, Action::Down, Action::ToggleColumn, Action::MoveColumnUp, Action::MoveColumnDown];
+
+/// Actions of the grouped process view.
+pub const GROUPED_ACTIONS: &[Action] = &[
+    Action::Quit,
+    Action::Up,
+    Action::Down,
+    Action::PageUp,
+    Action::PageDown,
+    Action::Top,
+    Action::Bottom,
+    Action::ToggleGroupBy,
+    Action::GroupSortCpu,
+    Action::GroupSortMemory,
+    Action::GroupSortCount,
+];
+
+/// Actions of the sockets view.
+pub const SOCKETS_ACTIONS: &[Action] = &[Action::Quit, Action::Up, Action::Down, Action::PageUp, Action::PageDown, Action::Top, Action::Bottom, Action::Reload];
+
+/// Actions of the environment view.
+pub const ENVIRONMENT_ACTIONS: &[Action] = &[Action::Quit, Action::Up, Action::Down, Action::PageUp, Action::PageDown, Action::Top, Action::Bottom, Action::RevealSecrets];
+
+/// Actions of the snapshot diff view. Snapshot takes another one and compares again.
+pub const SNAPSHOT_DIFF_ACTIONS: &[Action] = &[Action::Quit, Action::Up, Action::Down, Action::PageUp, Action::PageDown, Action::Top, Action::Bottom, Action::Snapshot];
+
+/// Actions of the help screen. Help, like quit, closes it again.
+pub const HELP_ACTIONS: &[Action] = &[Action::Quit, Action::Help, Action::Up, Action::Down, Action::PageUp, Action::PageDown, Action::Top, Action::Bottom];
+
+/// Action to key bindings used by the input handlers.
+#[derive(Clone)]
+pub struct KeyMap {
+    bindings: HashMap<Action, Vec<KeyCode>>,
+}
+
+impl Default for KeyMap {
+    fn default() -> Self {
+        Self {
+            bindings: DEFAULT_BINDINGS
+                .iter()
+                .map(|(action, _, keys)| (*action, keys.to_vec()))
+                .collect(),
+        }
+    }
+}
+
+impl KeyMap {
+    /// Build a keymap from the [keys] config table. Each entry replaces the default keys of one
+    /// action. If the result binds one key to two actions on the same screen, the defaults are kept.
+    pub fn from_table(table: &toml::Table, warnings: &mut Vec<String>) -> Self {
+        let mut keymap = Self::default();
+        for (name, value) in table {
+            let Some((action, _, _)) = DEFAULT_BINDINGS.iter().find(|(_, n, _)| n == name) else {
+                warnings.push(format!("Unknown key action '{}'", name));
+                continue;
+            };
+            let names: Vec<&str> = match value {
+                toml::Value::String(key) => vec![key.as_str()],
+                toml::Value::Array(keys) => keys.iter().filter_map(|k| k.as_str()).collect(),
+                _ => {
+                    warnings.push(format!("Key binding for '{}' must be a string or list of strings", name));
+                    continue;
+                }
+            };
+            let mut keys = Vec::new();
+            for key_name in names {
+                match parse_key(key_name) {
+                    Some(key) => keys.push(key),
+                    None => warnings.push(format!("Unknown key '{}' for '{}'", key_name, name)),
+                }
+            }
+            if !keys.is_empty() {
+                keymap.bindings.insert(*action, keys);
+            }
+        }
+
+        let conflicts: Vec<String> = [
+            PROCESS_LIST_ACTIONS,
+            KILL_STOP_MENU_ACTIONS,
+            KILL_STOP_ACTIONS,
+            NICE_MENU_ACTIONS,
+            STATISTICS_ACTIONS,
+            PROCESS_LOG_ACTIONS,
+            PER_PROCESS_GRAPH_ACTIONS,
+            COLUMNS_ACTIONS,
+            GROUPED_ACTIONS,
+            SOCKETS_ACTIONS,
+            ENVIRONMENT_ACTIONS,
+            SNAPSHOT_DIFF_ACTIONS,
+            HELP_ACTIONS,
+        ]
+            .iter()
+            .flat_map(|context| keymap.conflicts(context))
+            .collect();
+        if !conflicts.is_empty() {
+            warnings.push(format!("Conflicting key bindings ({}), using default keys", conflicts.join(", ")));
+            return Self::default();
+        }
+        keymap
+    }
+
+    /// Whether `key` triggers `action`.
+    pub fn is(&self, action: Action, key: KeyCode) -> bool {
+        self.bindings.get(&action).is_some_and(|keys| keys.contains(&key))
+    }
+
+    /// The action within `context` bound to `key`, if any.
+    pub fn action(&self, context: &[Action], key: KeyCode) -> Option<Action> {
+        context.iter().copied().find(|action| self.is(*action, key))
+    }
+
+    /// Every key bound to `action`, as "↑/k", for the help screen.
+    pub fn keys(&self, action: Action) -> String {
+        self.bindings
+            .get(&action)
+            .map(|keys| keys.iter().map(key_name).collect::<Vec<_>>().join("/"))
+            .unwrap_or_default()
+    }
+
+    /// Display name of the first key bound to `action`, for menus and prompts.
+    pub fn label(&self, action: Action) -> String {
+        self.bindings
+            .get(&action)
+            .and_then(|keys| keys.first())
+            .map(key_name)
+            .unwrap_or_default()
+    }
+
+    // Describe every key bound to more than one action in `context`
+    fn conflicts(&self, context: &[Action]) -> Vec<String> {
+        let mut seen: HashMap<KeyCode, Action> = HashMap::new();
+        let mut conflicts = Vec::new();
+        for action in context {
+            for key in self.bindings.get(action).into_iter().flatten() {
+                if let Some(other) = seen.insert(*key, *action) {
+                    conflicts.push(format!("'{}' bound to both {:?} and {:?}", key_name(key), other, action));
+                }
+            }
+        }
+        conflicts
+    }
+}
+
+// Parse a key as written in the config: a single character or a named key such as "Up" or "F5"
+fn parse_key(name: &str) -> Option<KeyCode> {
+    let mut chars = name.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Some(KeyCode::Char(c));
+    }
+    let key = match name.to_lowercase().as_str() {
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" => KeyCode::PageUp,
+        "pagedown" => KeyCode::PageDown,
+        "enter" => KeyCode::Enter,
+        "esc" => KeyCode::Esc,
+        "tab" => KeyCode::Tab,
+        "backspace" => KeyCode::Backspace,
+        "delete" => KeyCode::Delete,
+        "space" => KeyCode::Char(' '),
+        other => {
+            let number = other.strip_prefix('f')?.parse::<u8>().ok()?;
+            if !(1..=12).contains(&number) {
+                return None;
+            }
+            KeyCode::F(number)
+        }
+    };
+    Some(key)
+}
+
+fn key_name(key: &KeyCode) -> String {
+    match key {
+        KeyCode::Char(' ') => "Space".to_string(),
+        KeyCode::Char(c) => c.to_string(),
+        KeyCode::F(n) => format!("F{}", n),
+        KeyCode::Up => "↑".to_string(),
+        KeyCode::Down => "↓".to_string(),
+        KeyCode::Left => "←".to_string(),
+        KeyCode::Right => "→".to_string(),
+        KeyCode::PageUp => "PgUp".to_string(),
+        KeyCode::PageDown => "PgDn".to_string(),
+        other => format!("{:?}", other),
+    }
+}
@@ -2,10 +2,38 @@
 mod process;
 mod ui;
 mod graph;
+mod per_process_graph;
 mod process_log;
 mod scripting_rules;
+mod config;
+mod cli;
+mod keymap;
+mod columns;
+mod util;
+mod procfs_parse;
+mod theme;
+mod snapshot;
+mod alerts;
+mod sockets;
+mod diagnostics;
+use clap::Parser;
 //main to start the application
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    ui::ui_renderer()
+    let args = cli::Args::parse();
+    if let Some(root) = &args.proc_root
+        && !root.join("stat").is_file()
+    {
+        return Err(format!("--proc-root {}: no stat file there, not a procfs mount", root.display()).into());
+    }
+    if args.once {
+        return cli::run_once(&args);
+    }
+    if args.stream {
+        return cli::run_stream(&args);
+    }
+    if let Some(path) = &args.prometheus {
+        return cli::run_prometheus(&args, path);
+    }
+    ui::ui_renderer(&args)
 }
 
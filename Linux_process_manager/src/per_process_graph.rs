@@ -1,165 +1,455 @@
 //! Per-process graphing module
-// This module will provide a UI tab to display CPU and memory usage graphs for a selected process over time.
+// The per-process graph view: a searchable list to pick a process from, then its CPU and memory
+// history over a zoomable window, optionally compared with other processes or overlaid on one
+// chart. The UI keeps the view's state; this module only draws it.
 
 use ratatui::{Frame, layout::Rect, widgets::{Block, Borders, Dataset, GraphType, Chart, Paragraph, Table, Row, Cell}, style::{Style, Modifier, Color}, layout::{Layout, Constraint, Direction, Alignment}, text::{Line, Span}};
-use crate::process::ProcessManager;
-use crate::graph::GraphData;
-
-const PROCESS_TABLE_HEIGHT: usize = 12;
-
-/// Render the per-process graph tab.
-pub fn render_per_process_graph_tab(
-    frame: &mut Frame, 
-    area: Rect, 
-    process_manager: &ProcessManager,
-    graph_data: &GraphData,
-    selected_process_index: usize,
-    scroll_offset: usize,
-    selected_process_for_graph: Option<u32>,
-) {
+use std::collections::VecDeque;
+use crate::process::{ProcessInfo, ProcessManager};
+use crate::graph::{self, GraphData};
+use crate::keymap::{Action, KeyMap};
+use crate::procfs_parse::SmapsSummary;
+use crate::theme::Theme;
+use crate::ui::{NAME_COLUMN_WIDTH, PROCESS_TABLE_HEIGHT, USER_COLUMN_WIDTH};
+use crate::util::{ellipsize, format_bytes, format_megabytes, MemoryUnit};
+
+// Most processes drawn on one per-process graph: the selected one plus the comparison set
+pub const MAX_GRAPH_SERIES: usize = 5;
+// Time windows the per-process graph can zoom between with '+'/'-'
+pub const GRAPH_WINDOWS_SECS: [u64; 4] = [15, 30, 60, 300];
+const GRAPH_SERIES_COLORS: [Color; MAX_GRAPH_SERIES] = [Color::Cyan, Color::Yellow, Color::Magenta, Color::Green, Color::LightRed];
+
+/// Everything the per-process graph view draws from, borrowed from the UI state.
+pub struct GraphView<'a> {
+    pub process_manager: &'a ProcessManager,
+    pub graph_data: &'a GraphData,
+    pub theme: &'a Theme,
+    pub keymap: &'a KeyMap,
+    pub memory_unit: MemoryUnit,
+    pub message: Option<&'a (String, bool)>, // Shown in place of the help line
+    pub selected_pid: Option<u32>, // None while picking a process from the list
+    pub selected_index: usize, // Cursor row in the selection list
+    pub scroll_offset: usize,
+    pub compare_pids: &'a [u32],
+    pub window_secs: u64,
+    pub overlay: bool,
+    pub smaps: Option<&'a Result<SmapsSummary, String>>, // Memory map panel, when shown
+    pub virtual_memory: bool, // Chart virtual size instead of resident memory
+    pub filter_input: &'a str,
+    pub filter_active: bool,
+}
+
+/// Processes offered by the per-process graph, narrowed by the name/PID search.
+pub fn selection_processes<'a>(manager: &'a ProcessManager, query: &str) -> Vec<&'a ProcessInfo> {
+    let query = query.to_lowercase();
+    manager
+        .get_processes()
+        .iter()
+        .filter(|p| query.is_empty() || p.name.to_lowercase().contains(&query) || p.pid.to_string().contains(&query))
+        .collect()
+}
+
+/// Draw the per-process graph view: the selection list, or the graphs once a process is picked.
+pub fn render_per_process_graph_tab(frame: &mut Frame, area: Rect, view: &GraphView) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Length(3),  // Title
+            Constraint::Length(6),  // Process info
+            Constraint::Length(if view.smaps.is_some() && view.selected_pid.is_some() { 3 } else { 0 }), // Memory map
             Constraint::Min(0),     // Content
+            Constraint::Length(3),  // Help line
         ])
         .split(area);
 
     // Title
-    let title = Paragraph::new("Per-Process Graph View")
+    let title = Paragraph::new(format!("Per-Process Graph View (last {})", crate::util::format_duration(view.window_secs)))
         .style(Style::default().fg(Color::White).add_modifier(Modifier::BOLD))
         .alignment(Alignment::Center)
         .block(Block::default().borders(Borders::ALL));
     frame.render_widget(title, chunks[0]);
 
-    if let Some(pid) = selected_process_for_graph {
-        // Show graphs for selected process
-        let graph_chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Percentage(50),  // CPU Graph
-                Constraint::Percentage(50),  // Memory Graph
-            ])
-            .split(chunks[1]);
-
-        // Get process info
-        if let Some(process) = process_manager.get_processes().iter().find(|p| p.pid == pid) {
-            // Get history data
-            if let Some((cpu_history, mem_history)) = graph_data.get_process_history(pid) {
-                // CPU Graph
-                let cpu_data: Vec<(f64, f64)> = cpu_history.iter()
-                    .enumerate()
-                    .map(|(i, &usage)| (i as f64, usage as f64))
-                    .collect();
+    if let Some(pid) = view.selected_pid {
+        let processes = view.process_manager.get_processes();
+        if let Some(process) = processes.iter().find(|p| p.pid == pid) {
+            // Process info box
+            let info_lines = vec![
+                Line::from(vec![Span::styled(format!("Name: {}", process.name), Style::default().fg(Color::Green))]),
+                Line::from(vec![Span::styled(format!("PID: {}", process.pid), Style::default().fg(Color::Yellow)), Span::raw("  "), Span::styled(format!("User: {}", process.user_label()), Style::default().fg(Color::Magenta))]),
+                Line::from(vec![Span::styled(format!("PPID: {}", process.parent_pid.unwrap_or(0)), Style::default().fg(Color::Cyan)), Span::raw("  "), Span::styled(format!("Status: {}", process.status), Style::default().fg(Color::White))]),
+                Line::from(vec![
+                    Span::styled(format!("Start: {}", process.start_time_str), Style::default().fg(Color::White)),
+                    Span::raw("  "),
+                    Span::styled(format!("Threads: {}", process.thread_count), Style::default().fg(Color::White)),
+                    Span::raw("  "),
+                    Span::styled(
+                        format!("RSS: {}  VIRT: {}", format_bytes(process.memory_usage, view.memory_unit), format_bytes(process.virtual_memory, view.memory_unit)),
+                        Style::default().fg(Color::Green),
+                    ),
+                ]),
+            ];
+            let info_box = Paragraph::new(info_lines)
+                .block(Block::default().borders(Borders::ALL).title("Process Info"));
+            frame.render_widget(info_box, chunks[1]);
 
+            // PSS splits shared pages between the processes mapping them, so it's the fairer figure
+            // when many processes share the same libraries
+            if let Some(smaps) = view.smaps {
+                let line = match smaps {
+                    Ok(summary) => {
+                        let field = |label: &str, kb: u64| vec![
+                            Span::styled(format!("{}: ", label), Style::default().fg(Color::Gray)),
+                            Span::styled(format!("{}  ", format_bytes(kb * 1024, view.memory_unit)), Style::default().fg(Color::White)),
+                        ];
+                        Line::from([
+                            field("RSS", summary.rss),
+                            field("PSS", summary.pss),
+                            field("Private Dirty", summary.private_dirty),
+                            field("Shared", summary.shared),
+                            field("Swap", summary.swap),
+                        ].concat())
+                    }
+                    Err(e) => Line::from(Span::styled(e.clone(), Style::default().fg(Color::Red))),
+                };
+                let smaps_box = Paragraph::new(line)
+                    .block(Block::default().borders(Borders::ALL).title("Memory Map (smaps)"));
+                frame.render_widget(smaps_box, chunks[2]);
+            }
+
+            // Graphs
+            let graph_chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([
+                    Constraint::Percentage(50),  // CPU Graph
+                    Constraint::Percentage(50),  // Memory Graph
+                ])
+                .split(chunks[3]);
+
+            let mut series = vec![pid];
+            series.extend(view.compare_pids.iter().copied().filter(|&p| p != pid));
+            series.truncate(MAX_GRAPH_SERIES);
+            if series.len() > 1 {
+                render_comparison_graphs(frame, chunks[3], view, &series);
+            } else if view.overlay && let Some((cpu_history, mem_history)) = view.graph_data.get_process_history(pid) {
+                let mem_history = graph_memory_history(view, pid).unwrap_or(mem_history);
+                render_overlaid_graph(frame, chunks[3], view, process, cpu_history, mem_history);
+            } else if let Some((cpu_history, mem_history)) = view.graph_data.get_process_history(pid) {
+                let mem_history = graph_memory_history(view, pid).unwrap_or(mem_history);
+                let window = view.window_secs;
+                let interval = view.graph_data.sample_interval();
+                // CPU Graph, with live stats over the visible window
+                let cpu_data = graph::windowed_points(cpu_history, interval, window, |usage| usage as f64);
+                let current_cpu = cpu_history.back().copied().unwrap_or(0.0) as f64;
+                let min_cpu = cpu_data.iter().map(|&(_, y)| y).fold(f64::INFINITY, f64::min);
+                let max_cpu = cpu_data.iter().map(|&(_, y)| y).fold(f64::NEG_INFINITY, f64::max);
+                let avg_cpu = if !cpu_data.is_empty() {
+                    cpu_data.iter().map(|&(_, y)| y).sum::<f64>() / cpu_data.len() as f64
+                } else { 0.0 };
                 let cpu_dataset = Dataset::default()
                     .name("CPU Usage")
                     .marker(ratatui::symbols::Marker::Braille)
                     .graph_type(GraphType::Line)
                     .style(Style::default().fg(Color::Cyan))
                     .data(&cpu_data);
-
                 let cpu_chart = Chart::new(vec![cpu_dataset])
                     .block(Block::default()
-                        .title(format!("CPU Usage for {} (PID: {})", process.name, pid))
+                        .title(format!("CPU Usage for {} (PID: {}) | Now: {:.1}%  Min: {:.1}%  Max: {:.1}%  Avg: {:.1}%", process.name, pid, current_cpu, min_cpu, max_cpu, avg_cpu))
                         .borders(Borders::ALL))
-                    .x_axis(ratatui::widgets::Axis::default()
-                        .bounds([0.0, cpu_history.len() as f64])
-                        .labels(vec![]))
+                    .x_axis(graph::time_axis(window))
                     .y_axis(ratatui::widgets::Axis::default()
                         .bounds([0.0, 100.0])
                         .labels(vec!["0%".into(), "50%".into(), "100%".into()]));
-
                 frame.render_widget(cpu_chart, graph_chunks[0]);
 
-                // Memory Graph
-                let memory_data: Vec<(f64, f64)> = mem_history.iter()
-                    .enumerate()
-                    .map(|(i, &usage)| (i as f64, usage as f64 / (1024.0 * 1024.0)))
-                    .collect();
-
-                let max_memory = memory_data.iter()
+                // Memory Graph, in MB
+                let memory_data = graph::windowed_points(mem_history, interval, window, |usage| usage as f64 / (1024.0 * 1024.0));
+                let current_mem = mem_history.back().copied().unwrap_or(0) as f64 / (1024.0 * 1024.0);
+                let min_mem = memory_data.iter().map(|&(_, y)| y).fold(f64::INFINITY, f64::min);
+                let max_mem = memory_data.iter().map(|&(_, y)| y).fold(f64::NEG_INFINITY, f64::max);
+                let avg_mem = if !memory_data.is_empty() {
+                    memory_data.iter().map(|&(_, y)| y).sum::<f64>() / memory_data.len() as f64
+                } else { 0.0 };
+                // Leave headroom above the peak; the labels follow the same top
+                let memory_top = memory_data.iter()
                     .map(|&(_, y)| y)
                     .fold(0.0, f64::max)
-                    .max(1.0);  // Ensure we have at least 1MB range
-
+                    .max(1.0) * 1.2;
                 let memory_dataset = Dataset::default()
-                    .name("Memory Usage")
+                    .name(graph_memory_label(view))
                     .marker(ratatui::symbols::Marker::Braille)
                     .graph_type(GraphType::Line)
                     .style(Style::default().fg(Color::Green))
                     .data(&memory_data);
-
                 let memory_chart = Chart::new(vec![memory_dataset])
                     .block(Block::default()
-                        .title(format!("Memory Usage for {} (PID: {})", process.name, pid))
+                        .title(format!(
+                            "{} for {} (PID: {}) | Now: {}  Min: {}  Max: {}  Avg: {}",
+                            graph_memory_label(view),
+                            process.name,
+                            pid,
+                            format_megabytes(current_mem, view.memory_unit),
+                            format_megabytes(min_mem, view.memory_unit),
+                            format_megabytes(max_mem, view.memory_unit),
+                            format_megabytes(avg_mem, view.memory_unit),
+                        ))
                         .borders(Borders::ALL))
-                    .x_axis(ratatui::widgets::Axis::default()
-                        .bounds([0.0, mem_history.len() as f64])
-                        .labels(vec![]))
+                    .x_axis(graph::time_axis(window))
                     .y_axis(ratatui::widgets::Axis::default()
-                        .bounds([0.0, max_memory * 1.2])
+                        .bounds([0.0, memory_top])
                         .labels(vec![
-                            "0 MB".into(),
-                            format!("{:.1} MB", max_memory / 2.0).into(),
-                            format!("{:.1} MB", max_memory).into(),
+                            "0".into(),
+                            format_megabytes(memory_top / 2.0, view.memory_unit).into(),
+                            format_megabytes(memory_top, view.memory_unit).into(),
                         ]));
-
                 frame.render_widget(memory_chart, graph_chunks[1]);
             }
         }
+        // Help line
+        let help = match view.message {
+            Some((msg, is_error)) => Paragraph::new(msg.as_str()).style(view.theme.message(*is_error)),
+            None => {
+                let key = |action| view.keymap.label(action);
+                Paragraph::new(format!(
+                    "{}/{}: Prev/Next process  {}: Compare  {}: Clear comparison  {}/{}: Zoom  {}: RSS/VIRT  {}: Overlay  {}: smaps panel  {}/{}/{}: maps/smaps/stack in $PAGER  {}/{}: Copy PID/command  {}/{}, Esc: Back to list  {}: Back to processes",
+                    key(Action::PreviousProcess),
+                    key(Action::NextProcess),
+                    key(Action::ToggleCompare),
+                    key(Action::ClearCompare),
+                    key(Action::ZoomIn),
+                    key(Action::ZoomOut),
+                    key(Action::ToggleVirtualMemory),
+                    key(Action::ToggleOverlay),
+                    key(Action::ToggleSmaps),
+                    key(Action::PageMaps),
+                    key(Action::PageSmaps),
+                    key(Action::PageStack),
+                    key(Action::CopyPid),
+                    key(Action::CopyCommand),
+                    key(Action::Up),
+                    key(Action::Down),
+                    key(Action::Quit),
+                ))
+                .style(Style::default().fg(Color::Gray))
+            }
+        };
+        let help = help
+            .alignment(Alignment::Center)
+            .block(Block::default().borders(Borders::ALL));
+        frame.render_widget(help, chunks[4]);
     } else {
         // Show process selection list
-        let processes = process_manager.get_processes();
-        let headers = ["PID", "NAME", "CPU%", "MEM(MB)", "USER"];
-        
+        let processes = selection_processes(view.process_manager, view.filter_input);
+        let headers = ["PID", "NAME", "CPU%", "MEM", "USER"];
         let header_cells = headers
             .iter()
-            .map(|h| Cell::from(*h).style(Style::default().fg(Color::White).add_modifier(Modifier::BOLD)));
-        
+            .map(|h| Cell::from(*h).style(view.theme.header));
         let header = Row::new(header_cells)
-            .style(Style::default().bg(Color::Blue))
+            .style(view.theme.header)
             .height(1);
-
         let rows: Vec<Row> = processes
             .iter()
-            .skip(scroll_offset)
+            .skip(view.scroll_offset)
             .take(PROCESS_TABLE_HEIGHT - 2)
             .enumerate()
             .map(|(i, process)| {
-                let idx = scroll_offset + i;
-                let highlight = idx == selected_process_index;
-                let style = if highlight {
-                    Style::default().fg(Color::Black).bg(Color::Yellow).add_modifier(Modifier::BOLD)
-                } else if i % 2 == 0 {
-                    Style::default().fg(Color::Cyan)
+                let idx = view.scroll_offset + i;
+                let highlight = idx == view.selected_index;
+                let style = if highlight { view.theme.highlight } else { view.theme.row(i) };
+                let pid_text = if view.compare_pids.contains(&process.pid) {
+                    format!("+{}", process.pid)
                 } else {
-                    Style::default().fg(Color::Blue)
+                    process.pid.to_string()
                 };
-
-                let memory_mb = process.memory_usage / (1024 * 1024);
-
                 Row::new(vec![
-                    Cell::from(process.pid.to_string()).style(style),
-                    Cell::from(process.name.clone()).style(Style::default().fg(Color::Green)),
+                    Cell::from(pid_text).style(style),
+                    Cell::from(ellipsize(&process.name, NAME_COLUMN_WIDTH as usize)).style(view.theme.name),
                     Cell::from(format!("{:.1}%", process.cpu_usage)).style(style),
-                    Cell::from(format!("{}", memory_mb)).style(style),
-                    Cell::from(process.user.clone().unwrap_or_default()).style(Style::default().fg(Color::Magenta)),
+                    Cell::from(format_bytes(process.memory_usage, view.memory_unit)).style(style),
+                    Cell::from(ellipsize(&process.user_label(), USER_COLUMN_WIDTH as usize)).style(view.theme.user),
                 ])
             })
             .collect();
-
         let table = Table::new(rows)
             .header(header)
-            .block(Block::default().borders(Borders::ALL).title("Select a Process (↑↓ to move, Enter to select, Esc to return)"))
+            .block(Block::default().borders(Borders::ALL).title(if view.filter_input.is_empty() {
+                "Select a Process (↑↓ to move, Enter to select, Esc to return)".to_string()
+            } else {
+                format!("Select a Process ({} matching '{}')", processes.len(), view.filter_input)
+            }))
             .widths(&[
                 Constraint::Length(8),   // PID
-                Constraint::Length(20),  // NAME
+                Constraint::Length(NAME_COLUMN_WIDTH),  // NAME
                 Constraint::Length(8),   // CPU%
-                Constraint::Length(10),  // MEM(MB)
-                Constraint::Length(12),  // USER
+                Constraint::Length(10),  // MEM
+                Constraint::Length(USER_COLUMN_WIDTH),  // USER
             ]);
+        frame.render_widget(table, chunks[3]);
+        // Help line
+        let help_text = if view.filter_active {
+            format!("/{}  (Enter: keep  Esc: clear)", view.filter_input)
+        } else {
+            format!(
+                "{}/{}: Move  Enter: Select  {}: Search  {}: Add to comparison (+)  Esc/{}: Back",
+                view.keymap.label(Action::Up),
+                view.keymap.label(Action::Down),
+                view.keymap.label(Action::QuickFilter),
+                view.keymap.label(Action::ToggleCompare),
+                view.keymap.label(Action::Quit),
+            )
+        };
+        let help = Paragraph::new(help_text)
+            .style(Style::default().fg(Color::Gray))
+            .alignment(Alignment::Center)
+            .block(Block::default().borders(Borders::ALL));
+        frame.render_widget(help, chunks[4]);
+    }
+}
+
+// CPU and memory of one process on a single chart. The chart has one real y-axis, so memory is
+// scaled onto the CPU range and each axis label carries both readings: "CPU% | memory".
+fn render_overlaid_graph(
+    frame: &mut Frame,
+    area: Rect,
+    view: &GraphView,
+    process: &ProcessInfo,
+    cpu_history: &VecDeque<f32>,
+    mem_history: &VecDeque<u64>,
+) {
+    let window = view.window_secs;
+    let interval = view.graph_data.sample_interval();
+    let cpu_data = graph::windowed_points(cpu_history, interval, window, |usage| usage as f64);
+    let memory_mb = graph::windowed_points(mem_history, interval, window, |usage| usage as f64 / (1024.0 * 1024.0));
+
+    // Multi-threaded processes can go past 100%; widen the CPU range rather than clip them
+    let cpu_top = cpu_data.iter().map(|&(_, y)| y).fold(100.0, f64::max);
+    let memory_top = memory_mb.iter().map(|&(_, y)| y).fold(0.0, f64::max).max(1.0) * 1.2;
+    let memory_data: Vec<(f64, f64)> = memory_mb.iter().map(|&(x, mb)| (x, mb / memory_top * cpu_top)).collect();
+
+    let current_cpu = cpu_history.back().copied().unwrap_or(0.0);
+    let current_mem = mem_history.back().copied().unwrap_or(0);
+    let datasets = vec![
+        Dataset::default()
+            .name("CPU % (left)")
+            .marker(ratatui::symbols::Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(Color::Cyan))
+            .data(&cpu_data),
+        Dataset::default()
+            .name(format!("{} (right)", graph_memory_label(view)))
+            .marker(ratatui::symbols::Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(Color::Green))
+            .data(&memory_data),
+    ];
+    let label = |fraction: f64| format!("{:.0}% | {}", cpu_top * fraction, format_megabytes(memory_top * fraction, view.memory_unit));
+    let chart = Chart::new(datasets)
+        .block(Block::default()
+            .title(format!(
+                "CPU and {} for {} (PID: {}) | Now: {:.1}%  {}",
+                graph_memory_label(view),
+                process.name,
+                process.pid,
+                current_cpu,
+                format_bytes(current_mem, view.memory_unit),
+            ))
+            .borders(Borders::ALL))
+        .x_axis(graph::time_axis(window))
+        .y_axis(ratatui::widgets::Axis::default()
+            .bounds([0.0, cpu_top])
+            .labels(vec![label(0.0).into(), label(0.5).into(), label(1.0).into()]));
+    frame.render_widget(chart, area);
+}
 
-        frame.render_widget(table, chunks[1]);
+// Virtual size history when the per-process graph charts it; None means use resident memory
+fn graph_memory_history<'a>(view: &GraphView<'a>, pid: u32) -> Option<&'a VecDeque<u64>> {
+    if view.virtual_memory {
+        view.graph_data.get_process_virtual_history(pid)
+    } else {
+        None
     }
-} 
\ No newline at end of file
+}
+
+fn graph_memory_label(view: &GraphView) -> &'static str {
+    if view.virtual_memory { "Virtual Memory" } else { "Memory Usage" }
+}
+
+// Overlay the CPU and memory history of several processes on shared charts, one colour each.
+// Every series ends at "now", so shorter histories (processes that started recently) line up.
+fn render_comparison_graphs(frame: &mut Frame, area: Rect, view: &GraphView, pids: &[u32]) {
+    let histories: Vec<(u32, &str, &VecDeque<f32>, &VecDeque<u64>)> = pids
+        .iter()
+        .filter_map(|&pid| {
+            let (cpu_history, mem_history) = view.graph_data.get_process_history(pid)?;
+            let mem_history = graph_memory_history(view, pid).unwrap_or(mem_history);
+            let name = view.process_manager.find_process(pid).map_or("?", |p| p.name.as_str());
+            Some((pid, name, cpu_history, mem_history))
+        })
+        .collect();
+    let window = view.window_secs;
+    let interval = view.graph_data.sample_interval();
+
+    let cpu_data: Vec<Vec<(f64, f64)>> = histories
+        .iter()
+        .map(|(_, _, cpu, _)| graph::windowed_points(cpu, interval, window, |usage| usage as f64))
+        .collect();
+    let memory_data: Vec<Vec<(f64, f64)>> = histories
+        .iter()
+        .map(|(_, _, _, mem)| graph::windowed_points(mem, interval, window, |usage| usage as f64 / (1024.0 * 1024.0)))
+        .collect();
+    let memory_top = memory_data.iter().flatten().map(|&(_, y)| y).fold(0.0, f64::max).max(1.0) * 1.2;
+
+    let cpu_datasets: Vec<Dataset> = histories
+        .iter()
+        .zip(&cpu_data)
+        .zip(GRAPH_SERIES_COLORS)
+        .map(|(((pid, name, cpu, _), data), color)| {
+            Dataset::default()
+                .name(format!("{} ({}) {:.1}%", name, pid, cpu.back().copied().unwrap_or(0.0)))
+                .marker(ratatui::symbols::Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(color))
+                .data(data)
+        })
+        .collect();
+    let memory_datasets: Vec<Dataset> = histories
+        .iter()
+        .zip(&memory_data)
+        .zip(GRAPH_SERIES_COLORS)
+        .map(|(((pid, name, _, mem), data), color)| {
+            let current = mem.back().copied().unwrap_or(0) as f64 / (1024.0 * 1024.0);
+            Dataset::default()
+                .name(format!("{} ({}) {}", name, pid, format_megabytes(current, view.memory_unit)))
+                .marker(ratatui::symbols::Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(color))
+                .data(data)
+        })
+        .collect();
+
+    let graph_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(area);
+    // Let the legend take up to half the chart so every process name fits
+    let legend_constraints = (Constraint::Ratio(1, 2), Constraint::Ratio(1, 2));
+    let cpu_chart = Chart::new(cpu_datasets)
+        .block(Block::default().title(format!("CPU Usage ({} processes)", histories.len())).borders(Borders::ALL))
+        .hidden_legend_constraints(legend_constraints)
+        .x_axis(graph::time_axis(window))
+        .y_axis(ratatui::widgets::Axis::default()
+            .bounds([0.0, 100.0])
+            .labels(vec!["0%".into(), "50%".into(), "100%".into()]));
+    frame.render_widget(cpu_chart, graph_chunks[0]);
+    let memory_chart = Chart::new(memory_datasets)
+        .block(Block::default().title(format!("{} ({} processes)", graph_memory_label(view), histories.len())).borders(Borders::ALL))
+        .hidden_legend_constraints(legend_constraints)
+        .x_axis(graph::time_axis(window))
+        .y_axis(ratatui::widgets::Axis::default()
+            .bounds([0.0, memory_top])
+            .labels(vec![
+                "0".into(),
+                format_megabytes(memory_top / 2.0, view.memory_unit).into(),
+                format_megabytes(memory_top, view.memory_unit).into(),
+            ]));
+    frame.render_widget(memory_chart, graph_chunks[1]);
+}
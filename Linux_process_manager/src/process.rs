@@ -1,50 +1,362 @@
 use crate::scripting_rules::RuleEngine;
 use sysinfo::{ProcessExt, System, SystemExt, PidExt, UserExt};
 use procfs::process::Process as ProcfsProcess; // Import procfs for nice value
-use std::convert::TryInto; // Import the try_into function
 use chrono::{Local, TimeZone};
 use libc::{self, c_int};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use serde::Serialize;
 
-#[derive(Clone)] 
+/// Where procfs is normally mounted. Any other root (--proc-root) is read-only: its processes are
+/// listed and inspected, but never signalled.
+pub const DEFAULT_PROC_ROOT: &str = "/proc";
+
+// Sort modes accepted by `ProcessManager::set_sort`
+pub const SORT_MODES: &[&str] = &["pid", "mem", "ppid", "start", "nice", "threads", "mem_percent", "uptime", "cpu", "swap", "cpu_time"];
+
+/// Scheduler state of a process, grouped from the kernel's single-letter states.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize)]
+pub enum ProcessState {
+    Running, // R: running or waiting for a CPU
+    Sleeping, // S: interruptible sleep
+    DiskSleep, // D: uninterruptible sleep, usually I/O
+    Idle, // I: idle kernel thread
+    Stopped, // T/t: stopped by a signal or a debugger
+    Zombie, // Z: exited, waiting for its parent to reap it
+    Other,
+}
+
+impl From<sysinfo::ProcessStatus> for ProcessState {
+    fn from(status: sysinfo::ProcessStatus) -> Self {
+        use sysinfo::ProcessStatus;
+        match status {
+            ProcessStatus::Run => ProcessState::Running,
+            ProcessStatus::Sleep => ProcessState::Sleeping,
+            ProcessStatus::UninterruptibleDiskSleep => ProcessState::DiskSleep,
+            ProcessStatus::Idle => ProcessState::Idle,
+            ProcessStatus::Stop | ProcessStatus::Tracing => ProcessState::Stopped,
+            ProcessStatus::Zombie => ProcessState::Zombie,
+            _ => ProcessState::Other,
+        }
+    }
+}
+
+/// Where per-process CPU% comes from.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum CpuSource {
+    Proc, // utime+stime deltas from /proc/<pid>/stat against total CPU time, like htop
+    Sysinfo, // sysinfo's own figure, which depends on its refresh timing
+}
+
+/// Names accepted by the `cpu_source` config option.
+pub const CPU_SOURCES: &[&str] = &["proc", "sysinfo"];
+
+impl CpuSource {
+    pub fn named(name: &str) -> Option<CpuSource> {
+        match name {
+            "proc" => Some(CpuSource::Proc),
+            "sysinfo" => Some(CpuSource::Sysinfo),
+            _ => None,
+        }
+    }
+}
+
+// Per-process flag in /proc/<pid>/stat marking kernel threads
+const PF_KTHREAD: u32 = 0x0020_0000;
+
+// Minimum CPU time between two samples, in clock ticks per CPU (0.4s at the usual 100 Hz).
+// Refreshes closer together than this keep the previous figure, so a fast refresh rate doesn't
+// turn CPU% into coarse 10% steps.
+const MIN_CPU_SAMPLE_TICKS: u64 = 40;
+
+// Processes read in one refresh, with the newly opened stat handle, CPU sample and newly read
+// details of each
+type Collected = Vec<(ProcessInfo, Option<ProcfsProcess>, Option<CpuSample>, Option<CachedDetails>)>;
+
+// How long cached details are reused before being read again, so a changed working directory
+// shows up within a few seconds
+const DETAILS_MAX_AGE: std::time::Duration = std::time::Duration::from_secs(5);
+
+// Per-process files that rarely change while the process lives, read once and reused across
+// refreshes
+#[derive(Clone)]
+struct CachedDetails {
+    start_time: u64, // Of the process read, to tell a reused PID apart
+    name: String, // Changes on exec, which also changes the executable
+    read_at: std::time::Instant,
+    cgroup: Option<String>,
+    container: Option<String>,
+    cwd: Option<PathBuf>,
+    exe: Option<PathBuf>,
+}
+
+impl CachedDetails {
+    fn read(proc_root: &Path, pid: u32, start_time: u64, name: &str) -> Self {
+        let cgroup = std::fs::read_to_string(pid_path(proc_root, pid, "cgroup"))
+            .ok()
+            .and_then(|text| crate::procfs_parse::parse_cgroup(&text));
+        let container = cgroup.as_deref().and_then(crate::procfs_parse::container_from_cgroup);
+        CachedDetails {
+            start_time,
+            name: name.to_string(),
+            read_at: std::time::Instant::now(),
+            cgroup,
+            container,
+            cwd: std::fs::read_link(pid_path(proc_root, pid, "cwd")).ok(),
+            exe: std::fs::read_link(pid_path(proc_root, pid, "exe")).ok(),
+        }
+    }
+
+    // Whether these were read from the process described and are recent enough to reuse
+    fn is_current(&self, start_time: u64, name: &str) -> bool {
+        self.start_time == start_time && self.name == name && self.read_at.elapsed() < DETAILS_MAX_AGE
+    }
+}
+
+// CPU time of one process at the last sample
+#[derive(Clone, Copy)]
+struct CpuSample {
+    start_time: u64, // Start time in ticks since boot, to tell a reused PID apart
+    ticks: u64, // utime + stime
+    percent: f32, // Usage over the previous sample window, 100% = one core
+}
+
+#[derive(Clone, Serialize)] 
 pub struct ProcessInfo {
     pub pid: u32,
     pub name: String,
     pub cpu_usage: f32,
     pub memory_usage: u64,
+    pub swap_usage: u64, // Bytes swapped out (VmSwap), 0 if not reported
+    pub virtual_memory: u64, // Size of the address space in bytes, resident or not
     pub parent_pid: Option<u32>,
     pub status: String,
+    pub state: ProcessState,
     pub user: Option<String>,
+    pub uid: Option<u32>,
+    pub gid: Option<u32>,
     pub nice: i32, 
     pub start_time_str: String,
+    pub start_time: u64, // Unix timestamp in seconds
+    pub uptime_secs: u64, // Seconds since the process started, as of the last refresh
+    pub thread_count: usize,
+    pub cpu_time_secs: f64, // User plus system CPU time over the process's life (utime + stime)
+    pub mem_percent: f32, // Share of total system RAM
+    pub cmdline: String, // Full command line, space-joined (empty for kernel threads)
+    pub kernel_thread: bool,
+    pub cgroup: Option<String>, // cgroup path, e.g. /system.slice/nginx.service
+    pub container: Option<String>, // Short container label such as "docker:3f4e2a1b9c0d"
+    pub cwd: Option<PathBuf>, // None when /proc/<pid>/cwd can't be read (other users' processes, exited)
+    pub exe: Option<PathBuf>, // Ends in " (deleted)" if the binary was replaced or removed
+}
+
+/// Running processes that share a name or a user, summed, e.g. every "chrome" process together.
+pub struct ProcessGroup {
+    pub name: String, // The process name or user label the group is keyed on
+    pub count: usize,
+    pub cpu_usage: f32, // Sum over the group, so it can exceed 100%
+    pub memory_usage: u64, // Resident bytes summed over the group
+}
+
+/// What the grouped view collects processes by.
+#[derive(Clone, Copy, PartialEq)]
+pub enum GroupBy {
+    Name,
+    User,
+}
+
+/// Column the grouped view is sorted on, largest first.
+#[derive(Clone, Copy, PartialEq)]
+pub enum GroupSort {
+    Cpu,
+    Memory,
+    Count,
+}
+
+/// Outcome of applying one operation (a signal, a nice value) to several processes.
+#[derive(Default)]
+pub struct OperationSummary {
+    pub succeeded: usize,
+    pub failed: Vec<(u32, std::io::Error)>,
+}
+
+impl OperationSummary {
+    fn record(&mut self, pid: u32, result: std::io::Result<()>) {
+        match result {
+            Ok(()) => self.succeeded += 1,
+            Err(e) => self.failed.push((pid, e)),
+        }
+    }
+
+    /// Whether any failure was for lack of privileges.
+    pub fn permission_denied(&self) -> bool {
+        self.failed.iter().any(|(_, e)| e.kind() == std::io::ErrorKind::PermissionDenied)
+    }
+
+    /// One-line description for the message line, e.g. "3 succeeded, 1 failed (4242: Operation not permitted)".
+    pub fn describe(&self) -> String {
+        let mut text = format!("{} succeeded", self.succeeded);
+        if let Some((pid, e)) = self.failed.first() {
+            text.push_str(&format!(", {} failed ({}: {})", self.failed.len(), pid, e));
+        }
+        text
+    }
+}
+
+// The last OS error, reworded for the cases users hit most, with the raw error appended when
+// `show_raw` is set. The kind is kept, so callers can still match on PermissionDenied.
+fn last_os_error(show_raw: bool) -> std::io::Error {
+    let raw = std::io::Error::last_os_error();
+    let friendly = match raw.raw_os_error() {
+        Some(libc::EPERM) | Some(libc::EACCES) => "Permission denied - try running with sudo or pick your own process",
+        Some(libc::ESRCH) => "No such process - it may have already exited",
+        _ => return raw,
+    };
+    let message = if show_raw {
+        format!("{} ({})", friendly, raw)
+    } else {
+        friendly.to_string()
+    };
+    std::io::Error::new(raw.kind(), message)
+}
+
+fn send_signal(pid: u32, signal: c_int, show_raw: bool) -> std::io::Result<()> {
+    // SAFETY: kill has no memory-safety preconditions
+    if unsafe { libc::kill(pid as libc::pid_t, signal) } != 0 {
+        return Err(last_os_error(show_raw));
+    }
+    Ok(())
+}
+
+// If `pid` leads its own process group (and it is not ours), signal the whole group, catching
+// members of a tree that were re-parented away from it
+fn signal_own_group(pid: u32, signal: c_int) {
+    // SAFETY: getpgid/getpgrp/kill have no memory-safety preconditions
+    unsafe {
+        let group = libc::getpgid(pid as libc::pid_t);
+        if group == pid as libc::pid_t && group != libc::getpgrp() {
+            libc::kill(-group, signal);
+        }
+    }
+}
+
+impl ProcessInfo {
+    // Case-insensitive substring match against the name or command line.
+    // `query` is expected to already be lowercase.
+    pub fn matches_query(&self, query: &str) -> bool {
+        self.name.to_lowercase().contains(query) || self.cmdline.to_lowercase().contains(query)
+    }
+
+    /// User name for display, falling back to the numeric UID when the name can't be resolved
+    /// (e.g. inside containers without the host's passwd entries).
+    pub fn user_label(&self) -> String {
+        match (&self.user, self.uid) {
+            (Some(name), _) => name.clone(),
+            (None, Some(uid)) => uid.to_string(),
+            (None, None) => String::new(),
+        }
+    }
 }
 
 pub struct ProcessManager {
     system: System,
+    proc_root: PathBuf, // procfs mount processes are read from, /proc unless --proc-root
     filtered_processes: Vec<ProcessInfo>,// for the scripting
+    hidden_processes: Vec<ProcessInfo>, // Processes the menu filter left out at the last refresh, in PID order
     processes: Vec<ProcessInfo>,
+    previous_processes: Vec<ProcessInfo>, // List from before the last refresh, for exit detection
     sort_mode: Option<String>,
     sort_ascending: bool,
     filter_mode: Option<String>,
     filter_value: Option<String>,
+    total_memory: u64, // Total system RAM in bytes, read once at startup
+    procfs_handles: HashMap<u32, ProcfsProcess>, // Open /proc/<pid> directories, reused across refreshes
+    details: HashMap<u32, CachedDetails>, // cgroup, cwd and exe per PID, reused across refreshes
+    cpu_source: CpuSource,
+    cpu_samples: HashMap<u32, CpuSample>, // Per-PID CPU time at the last sample, for CpuSource::Proc
+    sampled_cpu_ticks: u64, // Total CPU time across all CPUs at the last sample
+    kernel_threads: usize,
+    own_usage: Option<(f32, u64)>, // This program's CPU% and resident bytes at the last refresh
+    show_raw_errors: bool, // Append the raw OS error to reworded errors (the `show_raw_errors` option)
+    read_swap: bool, // Read swap usage even when not sorting by it, e.g. for a shown swap column
 }
 
 impl ProcessManager {
-    pub fn new() -> Self {
-        let mut system = System::new_all(); 
-        system.refresh_all(); 
-        ProcessManager { 
+    pub fn new(proc_root: &Path) -> Self {
+        // sysinfo always enumerates /proc, so under another root it stays empty and procfs reads
+        // the processes instead
+        let foreign = proc_root != Path::new(DEFAULT_PROC_ROOT);
+        let mut system = if foreign { System::new() } else { System::new_all() };
+        if !foreign {
+            system.refresh_all(); 
+        }
+        let mut manager = ProcessManager { 
             system,
+            proc_root: proc_root.to_path_buf(),
+            hidden_processes: Vec::new(),
             processes: Vec::new(),
+            previous_processes: Vec::new(),
             filtered_processes: Vec::new(),
             sort_mode: None,
             sort_ascending: true,
             filter_mode: None,
             filter_value: None,
+            total_memory: read_total_memory(proc_root),
+            procfs_handles: HashMap::new(),
+            details: HashMap::new(),
+            cpu_source: CpuSource::Proc,
+            cpu_samples: HashMap::new(),
+            sampled_cpu_ticks: 0,
+            kernel_threads: 0,
+            own_usage: None,
+            show_raw_errors: false,
+            read_swap: true,
+        };
+        manager.update_processes(); // Takes the first CPU sample, so the next refresh has a baseline
+        manager
+    }
+
+    pub fn set_cpu_source(&mut self, source: CpuSource) {
+        self.cpu_source = source;
+    }
+
+    pub fn set_show_raw_errors(&mut self, show: bool) {
+        self.show_raw_errors = show;
+    }
+
+    /// Whether to read each process's swap usage, which costs a /proc/<pid>/status read per
+    /// process and refresh. It's read regardless while sorting by swap; otherwise it stays 0.
+    pub fn set_read_swap(&mut self, read: bool) {
+        self.read_swap = read;
+    }
+
+    /// The procfs mount processes are read from.
+    pub fn proc_root(&self) -> &Path {
+        &self.proc_root
+    }
+
+    // Whether processes come from a procfs other than our own, which can't be signalled
+    fn foreign_root(&self) -> bool {
+        self.proc_root != Path::new(DEFAULT_PROC_ROOT)
+    }
+
+    // Refuse to change processes read from another procfs: their PIDs don't name our processes
+    fn ensure_local(&self) -> std::io::Result<()> {
+        if self.foreign_root() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                format!("read-only: processes come from {}", self.proc_root.display()),
+            ));
         }
+        Ok(())
     }
 
     pub fn refresh(&mut self) {
-        self.system.refresh_all();
+        if !self.foreign_root() {
+            self.system.refresh_all();
+        }
+        // Keep the old list (moved, not cloned) so callers can look up processes that just exited
+        self.previous_processes = std::mem::take(&mut self.processes);
         self.update_processes();
         // Re-apply sort if there is an active sort mode
         if let Some(mode) = self.sort_mode.clone() {
@@ -52,6 +364,87 @@ impl ProcessManager {
         }
     }
 
+    /// The active filter as (mode, value), if any.
+    pub fn get_filter(&self) -> Option<(&str, &str)> {
+        Some((self.filter_mode.as_deref()?, self.filter_value.as_deref()?))
+    }
+
+    /// Processes of the current (filtered) list grouped by name, heaviest CPU users first and
+    /// then by memory. Kernel threads are left out unless `include_kernel_threads` is set.
+    pub fn groups(&self, by: GroupBy, sort: GroupSort, include_kernel_threads: bool) -> Vec<ProcessGroup> {
+        let mut groups: HashMap<String, ProcessGroup> = HashMap::new();
+        for process in self.processes.iter().filter(|p| include_kernel_threads || !p.kernel_thread) {
+            let key = match by {
+                GroupBy::Name => process.name.clone(),
+                GroupBy::User => process.user_label(),
+            };
+            let group = groups.entry(key).or_insert_with_key(|key| ProcessGroup {
+                name: key.clone(),
+                count: 0,
+                cpu_usage: 0.0,
+                memory_usage: 0,
+            });
+            group.count += 1;
+            group.cpu_usage += process.cpu_usage;
+            group.memory_usage += process.memory_usage;
+        }
+        let mut groups: Vec<ProcessGroup> = groups.into_values().collect();
+        // Largest first on the chosen column; the others break ties, then the name
+        groups.sort_by(|a, b| {
+            let cpu = b.cpu_usage.total_cmp(&a.cpu_usage);
+            let memory = b.memory_usage.cmp(&a.memory_usage);
+            let count = b.count.cmp(&a.count);
+            match sort {
+                GroupSort::Cpu => cpu.then(memory).then(count),
+                GroupSort::Memory => memory.then(cpu).then(count),
+                GroupSort::Count => count.then(cpu).then(memory),
+            }
+            .then_with(|| a.name.cmp(&b.name))
+        });
+        groups
+    }
+
+    /// Whether `pid` was running at the last refresh, whether or not the filter hides it.
+    pub fn process_exists(&self, pid: u32) -> bool {
+        self.find_process(pid).is_some()
+    }
+
+    /// `pid` as of the last refresh, whether or not the filter hides it.
+    pub fn find_process(&self, pid: u32) -> Option<&ProcessInfo> {
+        if let Ok(index) = self.hidden_processes.binary_search_by_key(&pid, |p| p.pid) {
+            return Some(&self.hidden_processes[index]);
+        }
+        self.processes.iter().find(|p| p.pid == pid)
+    }
+
+    /// Start time and state of `pid` as of the last refresh, even if the filter hides it. The start
+    /// time tells a process apart from a later one that reused its PID.
+    pub fn process_identity(&self, pid: u32) -> Option<(u64, ProcessState)> {
+        self.find_process(pid).map(|process| (process.start_time, process.state))
+    }
+
+    /// Every process at the last refresh, before any filter: the listed ones in sort order, then
+    /// the ones the menu filter hides.
+    pub fn all_processes(&self) -> impl Iterator<Item = &ProcessInfo> {
+        self.processes.iter().chain(&self.hidden_processes)
+    }
+
+    /// Number of processes on the system, before any filter.
+    pub fn total_process_count(&self) -> usize {
+        self.processes.len() + self.hidden_processes.len()
+    }
+
+    /// CPU% and resident memory in bytes of this program itself at the last refresh, whatever
+    /// the filter, or None before it has been seen.
+    pub fn own_usage(&self) -> Option<(f32, u64)> {
+        self.own_usage
+    }
+
+    /// Number of kernel threads as of the last refresh, before any filter.
+    pub fn kernel_thread_count(&self) -> usize {
+        self.kernel_threads
+    }
+
     pub fn set_filter(&mut self, mode: Option<String>, value: Option<String>) {
         self.filter_mode = mode;
         self.filter_value = value;
@@ -59,122 +452,217 @@ impl ProcessManager {
     }
 
     fn update_processes(&mut self) {
-        let mut processes = Vec::new();
-        
-        for (pid, process) in self.system.processes() {
-            // Convert pid to i32 for ProcfsProcess::new()
-            let pid_i32: i32 = pid.as_u32().try_into().unwrap_or(0); // Safe conversion
-
-            // Retrieve nice value using procfs
-            let nice_value = ProcfsProcess::new(pid_i32)
-                .and_then(|p| p.stat().map(|stat| stat.nice))
-                .unwrap_or(0); // Default to 0 if retrieval fails
+        // Take a new CPU sample only once enough CPU time has passed since the last one. The
+        // totals come from the same procfs as the per-process times they are compared against.
+        let cpu_source = self.cpu_source;
+        let stat = crate::diagnostics::read_proc(&self.proc_root, "stat", "CPU usage disabled")
+            .map(|text| crate::procfs_parse::parse_stat(&text));
+        let mut elapsed_cpu_ticks = None;
+        if (cpu_source == CpuSource::Proc || self.foreign_root())
+            && let Some(stat) = &stat
+        {
+            let total = stat.cpu.total();
+            let per_cpu = total.saturating_sub(self.sampled_cpu_ticks) / stat.cpus.len().max(1) as u64;
+            if per_cpu >= MIN_CPU_SAMPLE_TICKS {
+                elapsed_cpu_ticks = Some(per_cpu);
+                self.sampled_cpu_ticks = total;
+            }
+        }
+
+        let collected = if self.foreign_root() {
+            let boot_time = stat.map_or(0, |stat| stat.btime);
+            collect_under_root(&self.proc_root, &self.cpu_samples, elapsed_cpu_ticks, self.total_memory, boot_time)
+        } else {
+            self.collect_local(elapsed_cpu_ticks)
+        };
+
+        // Rebuilt from scratch so exited PIDs drop out
+        let mut cpu_samples = HashMap::with_capacity(collected.len());
+        self.kernel_threads = collected.iter().filter(|(proc_info, _, _, _)| proc_info.kernel_thread).count();
+        let mut all_processes = Vec::with_capacity(collected.len());
+        let own_pid = std::process::id();
+        for (proc_info, new_handle, sample, new_details) in collected {
+            if proc_info.pid == own_pid && !self.foreign_root() {
+                self.own_usage = Some((proc_info.cpu_usage, proc_info.memory_usage));
+            }
+            if let Some(sample) = sample {
+                cpu_samples.insert(proc_info.pid, sample);
+            }
+            if let Some(handle) = new_handle
+                && (self.procfs_handles.contains_key(&proc_info.pid) || self.procfs_handles.len() < MAX_CACHED_HANDLES)
+            {
+                self.procfs_handles.insert(proc_info.pid, handle);
+            }
+            if let Some(details) = new_details {
+                self.details.insert(proc_info.pid, details);
+            }
+            all_processes.push(proc_info);
+        }
+        // Forget handles and details of processes that are gone so the maps don't grow without bound
+        self.procfs_handles.retain(|pid, _| cpu_samples.contains_key(pid));
+        let current: HashSet<u32> = all_processes.iter().map(|p| p.pid).collect();
+        self.details.retain(|pid, _| current.contains(pid));
+
+        // The process table is a HashMap, so give the unsorted list a stable PID order
+        // (this also makes serial and parallel collection produce the same list)
+        all_processes.sort_by_key(|p| p.pid);
+        // Split rather than copy: the filtered-out processes are kept aside for lookups by PID
+        let (processes, hidden): (Vec<_>, Vec<_>) = all_processes.into_iter().partition(|p| self.passes_filter(p));
+        self.processes = processes;
+        self.hidden_processes = hidden;
+        self.cpu_samples = cpu_samples;
+
+        // Re-apply sort if there is an active sort mode
+        if let Some(mode) = self.sort_mode.clone() {
+            self.sort_processes(&mode);
+        }
+    }
+
+    // Whether a process passes the menu filter, if one is set
+    fn passes_filter(&self, proc_info: &ProcessInfo) -> bool {
+        let (Some(mode), Some(value)) = (&self.filter_mode, &self.filter_value) else { return true };
+        match mode.as_str() {
+            "user" => {
+                proc_info.user.as_ref().is_some_and(|u| u.contains(value))
+                    || proc_info.uid.is_some_and(|uid| uid.to_string() == *value)
+            }
+            "name" => proc_info.name.to_lowercase().contains(&value.to_lowercase()),
+            "pid" => proc_info.pid.to_string().contains(value),
+            "ppid" => proc_info.parent_pid.is_some_and(|p| p.to_string().contains(value)),
+            "container" => proc_info.container.as_ref().is_some_and(|c| c.contains(&value.to_lowercase())),
+            _ => true,
+        }
+    }
+
+    // Read our own /proc: the table comes from sysinfo, the rest from each process's files
+    fn collect_local(&self, elapsed_cpu_ticks: Option<u64>) -> Collected {
+        let system = &self.system;
+        let root = self.proc_root.as_path();
+        let handles = &self.procfs_handles;
+        let cached_details = &self.details;
+        let samples = &self.cpu_samples;
+        let read_swap = self.read_swap || self.sort_mode.as_deref() == Some("swap");
+        let cpu_source = self.cpu_source;
+        let total_memory = self.total_memory;
+        let ticks_per_second = procfs::ticks_per_second().max(1) as f64;
+        let build = |(pid, process): (&sysinfo::Pid, &sysinfo::Process)| {
+            // Retrieve nice value and thread count from a single procfs stat read
+            let (stat, new_handle) = read_stat(root, handles, pid.as_u32());
+            let sample = stat.as_ref().map(|stat| sample_cpu(samples.get(&pid.as_u32()), stat, elapsed_cpu_ticks));
+            let cpu_usage = match cpu_source {
+                CpuSource::Proc => sample.map_or(0.0, |sample| sample.percent),
+                CpuSource::Sysinfo => process.cpu_usage(),
+            };
+            let nice_value = stat.as_ref().map(|stat| stat.nice).unwrap_or(0); // Default to 0 if retrieval fails
+            // Processes that vanish mid-read (or kernel threads we can't read) count as a single thread
+            let thread_count = stat.as_ref().map(|stat| stat.num_threads.max(1) as usize).unwrap_or(1);
+            let (details, new_details) = match cached_details.get(&pid.as_u32()) {
+                Some(details) if details.is_current(process.start_time(), process.name()) => (details.clone(), None),
+                _ => {
+                    let details = CachedDetails::read(root, pid.as_u32(), process.start_time(), process.name());
+                    (details.clone(), Some(details))
+                }
+            };
             // Format the start time
             let formatted_time = format_timestamp(process.start_time());
             let proc_info = ProcessInfo {
                 pid: pid.as_u32(),
                 name: process.name().to_string(),
-                cpu_usage: process.cpu_usage(),
+                cpu_usage,
                 memory_usage: process.memory(),
+                swap_usage: if read_swap { read_swap_usage(root, pid.as_u32()) } else { 0 },
+                virtual_memory: process.virtual_memory(),
                 parent_pid: process.parent().map(|p| p.as_u32()),
                 status: process.status().to_string(),
+                state: process.status().into(),
                 user: process.user_id()
-                    .and_then(|id| self.system.get_user_by_id(id)
+                    .and_then(|id| system.get_user_by_id(id)
                     .map(|user| user.name().to_string())),
+                uid: process.user_id().map(|id| **id),
+                gid: process.group_id().map(|id| *id),
                 nice: nice_value as i32,
                 start_time_str: formatted_time,
+                start_time: process.start_time(),
+                uptime_secs: crate::util::seconds_since(process.start_time()),
+                thread_count,
+                cpu_time_secs: stat.as_ref().map_or(0.0, |stat| (stat.utime + stat.stime) as f64 / ticks_per_second),
+                mem_percent: if total_memory > 0 {
+                    (process.memory() as f64 / total_memory as f64 * 100.0) as f32
+                } else {
+                    0.0
+                },
+                cmdline: process.cmd().join(" "),
+                // PF_KTHREAD is exact; without a stat read, fall back to no command line and no address
+                // space (zombies also lose their command line, but keep their status)
+                kernel_thread: stat.as_ref().map_or_else(
+                    || process.cmd().is_empty() && process.virtual_memory() == 0 && process.status() != sysinfo::ProcessStatus::Zombie,
+                    |stat| stat.flags & PF_KTHREAD != 0,
+                ),
+                cgroup: details.cgroup,
+                container: details.container,
+                cwd: details.cwd,
+                exe: details.exe,
             };
+            (proc_info, new_handle, sample, new_details)
+        };
 
-            // Apply filter if set
-            if let (Some(mode), Some(value)) = (&self.filter_mode, &self.filter_value) {
-                let should_include = match mode.as_str() {
-                    "user" => proc_info.user.as_ref().map_or(false, |u| u.contains(value)),
-                    "name" => proc_info.name.to_lowercase().contains(&value.to_lowercase()),
-                    "pid" => proc_info.pid.to_string().contains(value),
-                    "ppid" => proc_info.parent_pid.map_or(false, |p| p.to_string().contains(value)),
-                    _ => true,
-                };
-                if !should_include {
-                    continue;
-                }
-            }
-
-            processes.push(proc_info);
-        }
-        
-        self.processes = processes;
-
-        // Re-apply sort if there is an active sort mode
-        if let Some(mode) = self.sort_mode.clone() {
-            self.sort_processes(&mode);
+        #[cfg(feature = "parallel")]
+        {
+            use rayon::prelude::*;
+            collection_pool().install(|| system.processes().par_iter().map(build).collect())
         }
+        #[cfg(not(feature = "parallel"))]
+        system.processes().iter().map(build).collect()
     }
 
     pub fn get_processes(&self) -> &Vec<ProcessInfo> {
         &self.processes
     }
 
-    pub fn get_filtered_processes(&self) -> &Vec<ProcessInfo> {
+    pub fn get_previous_processes(&self) -> &[ProcessInfo] {
+        &self.previous_processes
+    }
+
+    pub fn get_filtered_processes(&self) -> &[ProcessInfo] {
         &self.filtered_processes
     }
 
+    /// Processes passing both the menu filter and the active script rule, in sort order. The
+    /// menu filter is applied while collecting; the rule result is the one from the last
+    /// `apply_rules`, so call that after each refresh and rule change.
+    pub fn visible_processes(&self, rule_engine: &RuleEngine) -> &[ProcessInfo] {
+        if rule_engine.active_rule.is_some() {
+            self.get_filtered_processes()
+        } else {
+            &self.processes
+        }
+    }
+
     pub fn set_sort(&mut self, mode: &str, ascending: bool) {
         self.sort_mode = Some(mode.to_string());
         self.sort_ascending = ascending;
         self.sort_processes(mode);
     }
 
+    /// Drop the sort, going back to PID order.
+    pub fn clear_sort(&mut self) {
+        self.sort_mode = None;
+        self.sort_ascending = false;
+        self.processes.sort_by_key(|p| p.pid);
+        self.filtered_processes.sort_by_key(|p| p.pid);
+    }
+
+    // Sort the full list and the rule-filtered one alike, so a sort change between refreshes shows
+    // up in the rule view too
     fn sort_processes(&mut self, mode: &str) {
-        match mode {
-            "pid" => {
-                if self.sort_ascending {
-                    self.processes.sort_by_key(|p| p.pid);
-                } else {
-                    self.processes.sort_by_key(|p| std::cmp::Reverse(p.pid));
-                }
-            }
-            "mem" => {
-                if self.sort_ascending {
-                    self.processes.sort_by_key(|p| p.memory_usage);
-                } else {
-                    self.processes.sort_by_key(|p| std::cmp::Reverse(p.memory_usage));
-                }
-            }
-            "ppid" => {
-                if self.sort_ascending {
-                    self.processes.sort_by_key(|p| p.parent_pid.unwrap_or(0));
-                } else {
-                    self.processes.sort_by_key(|p| std::cmp::Reverse(p.parent_pid.unwrap_or(0)));
-                }
-            }
-            "start" => {
-                if self.sort_ascending {
-                    self.processes.sort_by(|a, b| a.start_time_str.cmp(&b.start_time_str));
-                } else {
-                    self.processes.sort_by(|a, b| b.start_time_str.cmp(&a.start_time_str));
-                }
-            }
-            "nice" => {
-                if self.sort_ascending {
-                    self.processes.sort_by_key(|p| p.nice);
-                } else {
-                    self.processes.sort_by_key(|p| std::cmp::Reverse(p.nice));
-                }
-            }
-            "cpu" => {
-                if self.sort_ascending {
-                    self.processes.sort_by(|a, b| a.cpu_usage.partial_cmp(&b.cpu_usage).unwrap_or(std::cmp::Ordering::Equal));
-                } else {
-                    self.processes.sort_by(|a, b| b.cpu_usage.partial_cmp(&a.cpu_usage).unwrap_or(std::cmp::Ordering::Equal));
-                }
-            }
-            _ => {}
-        }
+        sort_list(&mut self.processes, mode, self.sort_ascending);
+        sort_list(&mut self.filtered_processes, mode, self.sort_ascending);
     }
 
     pub fn set_niceness(&self, pid: u32, nice: i32) -> std::io::Result<()> {
+        self.ensure_local()?;
         // Validate niceness range
-        if nice < -20 || nice > 19 {
+        if !(-20..=19).contains(&nice) {
             return Err(std::io::Error::new(
                 std::io::ErrorKind::InvalidInput,
                 "Nice value must be between -20 and 19"
@@ -194,14 +682,35 @@ impl ProcessManager {
         let result = unsafe { libc::setpriority(libc::PRIO_PROCESS, temp_pid, nice as c_int) };
         
         if result != 0 {
-            return Err(std::io::Error::last_os_error());
+            return Err(last_os_error(self.show_raw_errors));
         }
 
         Ok(())
     }
 
+    /// Set how eagerly the OOM killer picks a process: -1000 exempts it, 1000 makes it the first
+    /// victim. Anyone may raise their own processes' value; lowering it needs root.
+    pub fn set_oom_score_adj(&self, pid: u32, adj: i32) -> std::io::Result<()> {
+        self.ensure_local()?;
+        if !(-1000..=1000).contains(&adj) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "oom_score_adj must be between -1000 and 1000"
+            ));
+        }
+        std::fs::write(pid_path(&self.proc_root, pid, "oom_score_adj"), adj.to_string()).map_err(|e| match e.kind() {
+            std::io::ErrorKind::PermissionDenied => std::io::Error::new(
+                e.kind(),
+                "Permission denied - lowering oom_score_adj or changing another user's process needs root (use sudo)"
+            ),
+            std::io::ErrorKind::NotFound => std::io::Error::new(e.kind(), "No such process - it may have already exited"),
+            _ => e,
+        })
+    }
+
     pub fn stop_process(&self, pid: u32) -> std::io::Result<()> {
         use libc::{kill, pid_t, SIGSTOP};
+        self.ensure_local()?;
         
         let temp_pid: pid_t = pid as pid_t;
         
@@ -209,7 +718,7 @@ impl ProcessManager {
         let result = unsafe { kill(temp_pid, SIGSTOP) };
         
         if result != 0 {
-            return Err(std::io::Error::last_os_error());
+            return Err(last_os_error(self.show_raw_errors));
         }
         
         Ok(())
@@ -218,6 +727,7 @@ impl ProcessManager {
 
     pub fn kill_process(&self, pid: u32) -> std::io::Result<()> {
         use libc::{kill, pid_t, SIGKILL};
+        self.ensure_local()?;
         
         let temp_pid: pid_t = pid as pid_t;
         
@@ -225,7 +735,7 @@ impl ProcessManager {
         let result = unsafe { kill(temp_pid, SIGKILL) };
         
         if result != 0 {
-            return Err(std::io::Error::last_os_error());
+            return Err(last_os_error(self.show_raw_errors));
         }
         
         Ok(())
@@ -233,6 +743,7 @@ impl ProcessManager {
 
     pub fn continue_process(&self, pid: u32) -> std::io::Result<()> {
         use libc::{kill, pid_t, SIGCONT};
+        self.ensure_local()?;
         
         let temp_pid: pid_t = pid as pid_t;
         
@@ -240,7 +751,7 @@ impl ProcessManager {
         let result = unsafe { kill(temp_pid, SIGCONT) };
         
         if result != 0 {
-            return Err(std::io::Error::last_os_error());
+            return Err(last_os_error(self.show_raw_errors));
         }
         
         Ok(())
@@ -248,6 +759,7 @@ impl ProcessManager {
 
     pub fn terminate_process(&self, pid: u32) -> std::io::Result<()> {
         use libc::{kill, pid_t, SIGTERM};
+        self.ensure_local()?;
         
         let temp_pid: pid_t = pid as pid_t;
         
@@ -255,23 +767,416 @@ impl ProcessManager {
         let result = unsafe { kill(temp_pid, SIGTERM) };
         
         if result != 0 {
-            return Err(std::io::Error::last_os_error());
+            return Err(last_os_error(self.show_raw_errors));
         }
         
         Ok(())
     }
+
+    /// Send SIGCHLD to `pid`, reminding a parent that a child exited so it reaps the zombie.
+    /// Parents that ignore the signal keep their zombies until they exit themselves.
+    pub fn remind_parent(&self, pid: u32) -> std::io::Result<()> {
+        self.ensure_local()?;
+        send_signal(pid, libc::SIGCHLD, self.show_raw_errors)
+    }
     
-    pub fn apply_rules(&mut self, rule_engine: &mut RuleEngine) {
-        self.filtered_processes = self.processes
-            .iter()
-            .cloned()
-            .filter(|p| rule_engine.evaluate_for(p))
+    /// PIDs of every descendant of `pid`, parents before their children. Built from the full
+    /// process table, so processes hidden by the current filter are included.
+    pub fn descendants(&self, pid: u32) -> Vec<u32> {
+        let mut children: HashMap<u32, Vec<u32>> = HashMap::new();
+        for process in self.all_processes() {
+            if let Some(parent) = process.parent_pid {
+                children.entry(parent).or_default().push(process.pid);
+            }
+        }
+        // The listed processes are in sort order; visit siblings by PID regardless
+        for siblings in children.values_mut() {
+            siblings.sort_unstable();
+        }
+        let mut result = Vec::new();
+        let mut queue = std::collections::VecDeque::from([pid]);
+        while let Some(current) = queue.pop_front() {
+            for &child in children.get(&current).into_iter().flatten() {
+                result.push(child);
+                queue.push_back(child);
+            }
+        }
+        result
+    }
+
+    /// Send `signal` to `pid` and all of its descendants, children first. If the target leads its
+    /// own process group, the group is signalled too, catching members that were re-parented.
+    /// Refuses PIDs 0-2 (the kernel and init) and any tree containing this program itself.
+    pub fn kill_tree(&self, pid: u32, signal: c_int) -> std::io::Result<OperationSummary> {
+        let tree = self.signallable_tree(pid)?;
+        let mut summary = OperationSummary::default();
+        for &target in tree.iter().rev() {
+            summary.record(target, send_signal(target, signal, self.show_raw_errors));
+        }
+        signal_own_group(pid, signal);
+        Ok(summary)
+    }
+
+    /// Suspend `pid` and all of its descendants with SIGSTOP, parents first so none of them can
+    /// react to a child stopping by spawning a replacement. Same refusals as `kill_tree`.
+    pub fn stop_tree(&self, pid: u32) -> std::io::Result<OperationSummary> {
+        let tree = self.signallable_tree(pid)?;
+        let mut summary = OperationSummary::default();
+        for &target in &tree {
+            summary.record(target, send_signal(target, libc::SIGSTOP, self.show_raw_errors));
+        }
+        signal_own_group(pid, libc::SIGSTOP);
+        Ok(summary)
+    }
+
+    /// Resume a tree suspended by `stop_tree`, in the reverse order: the deepest children wake up
+    /// first and the parents waiting on them last, rather than everything at once.
+    pub fn continue_tree(&self, pid: u32) -> std::io::Result<OperationSummary> {
+        let tree = self.signallable_tree(pid)?;
+        let mut summary = OperationSummary::default();
+        for &target in tree.iter().rev() {
+            summary.record(target, send_signal(target, libc::SIGCONT, self.show_raw_errors));
+        }
+        signal_own_group(pid, libc::SIGCONT);
+        Ok(summary)
+    }
+
+    // `pid` followed by its descendants, parents before children. Refuses PIDs 0-2 (the kernel
+    // and init) and any tree containing this program itself.
+    fn signallable_tree(&self, pid: u32) -> std::io::Result<Vec<u32>> {
+        let refuse = |reason: &str| Err(std::io::Error::new(std::io::ErrorKind::PermissionDenied, reason.to_string()));
+        self.ensure_local()?;
+        if pid <= 2 {
+            return refuse("PIDs 0-2 belong to the kernel and init");
+        }
+        let mut tree = self.descendants(pid);
+        let own_pid = std::process::id();
+        if pid == own_pid || tree.contains(&own_pid) {
+            return refuse("the tree contains this process manager");
+        }
+        tree.insert(0, pid);
+        Ok(tree)
+    }
+
+    /// PIDs of every process named `name` (ignoring case), from the full process table.
+    /// PIDs 0-2 and this program itself are never included.
+    pub fn pids_named(&self, name: &str) -> Vec<u32> {
+        let name = name.to_lowercase();
+        let own_pid = std::process::id();
+        self.all_processes()
+            .filter(|process| process.name.to_lowercase() == name)
+            .map(|process| process.pid)
+            .filter(|&pid| pid > 2 && pid != own_pid)
+            .collect()
+    }
+
+    /// Send `signal` to every process named `name`.
+    pub fn kill_by_name(&self, name: &str, signal: c_int) -> OperationSummary {
+        let mut summary = OperationSummary::default();
+        for pid in self.pids_named(name) {
+            summary.record(pid, self.ensure_local().and_then(|()| send_signal(pid, signal, self.show_raw_errors)));
+        }
+        summary
+    }
+
+    /// Set the nice value of every process named `name`.
+    pub fn set_niceness_by_name(&self, name: &str, nice: i32) -> OperationSummary {
+        let mut summary = OperationSummary::default();
+        for pid in self.pids_named(name) {
+            summary.record(pid, self.set_niceness(pid, nice));
+        }
+        summary
+    }
+
+    /// Send SIGTERM and return the deadline after which the caller should send SIGKILL if the
+    /// process is still around. Checking the deadline is left to the caller's refresh loop.
+    pub fn terminate_then_kill(&self, pid: u32, grace_period: std::time::Duration) -> std::io::Result<std::time::Instant> {
+        self.terminate_process(pid)?;
+        Ok(std::time::Instant::now() + grace_period)
+    }
+
+    /// First half of a restart: read how `pid` was started, then send it SIGTERM. Once it has
+    /// exited, `RestartCommand::spawn` starts it again; waiting for that is left to the caller's
+    /// refresh loop, like the deadline of `terminate_then_kill`. Nothing is signalled if the
+    /// process can't be read.
+    pub fn begin_restart(&self, pid: u32) -> std::io::Result<RestartCommand> {
+        use std::os::unix::ffi::OsStrExt;
+        self.ensure_local()?;
+        if pid <= 2 || pid == std::process::id() {
+            return Err(std::io::Error::new(std::io::ErrorKind::PermissionDenied, "refusing to restart the kernel, init or this program"));
+        }
+        // Everything needed to relaunch is read before the process goes away
+        let argv: Vec<std::ffi::OsString> = std::fs::read(pid_path(&self.proc_root, pid, "cmdline"))?
+            .split(|&b| b == 0)
+            .filter(|arg| !arg.is_empty())
+            .map(|arg| std::ffi::OsStr::from_bytes(arg).to_os_string())
             .collect();
+        let Some(arg0) = argv.first() else {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, "the process has no command line (kernel thread?)"));
+        };
+        // Prefer the resolved executable; argv[0] may be relative to a directory we aren't in.
+        // A binary replaced by an upgrade reads as "<path> (deleted)", so fall back to argv[0].
+        let program = match std::fs::read_link(pid_path(&self.proc_root, pid, "exe")) {
+            Ok(exe) if !exe.to_string_lossy().ends_with(" (deleted)") => exe.into_os_string(),
+            _ => arg0.clone(),
+        };
+        let cwd = std::fs::read_link(pid_path(&self.proc_root, pid, "cwd"))?;
+        let environment = read_environ(&self.proc_root, pid)?;
+
+        self.terminate_process(pid)?;
+        Ok(RestartCommand { program, argv, cwd, environment })
+    }
+
+    /// Re-evaluate the script rule against the current list into `get_filtered_processes`.
+    /// Run once per refresh (and when the rule changes), not per frame: rules can be slow.
+    /// The result keeps the current sort order, and later sort changes re-sort it too.
+    pub fn apply_rules(&mut self, rule_engine: &RuleEngine) {
+        self.filtered_processes = rule_engine.select_active(&self.processes).into_iter().cloned().collect();
     }
     
     
     
 }
+/// Environment of a process from /proc/<pid>/environ, as (name, value) pairs. This is the
+/// environment the process was started with; later setenv calls inside it don't show up.
+/// Reading another user's process needs root.
+pub fn read_environ(proc_root: &Path, pid: u32) -> std::io::Result<Vec<(String, String)>> {
+    let bytes = std::fs::read(pid_path(proc_root, pid, "environ"))?;
+    Ok(bytes
+        .split(|&b| b == 0)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let entry = String::from_utf8_lossy(entry);
+            match entry.split_once('=') {
+                Some((name, value)) => (name.to_string(), value.to_string()),
+                None => (entry.to_string(), String::new()),
+            }
+        })
+        .collect())
+}
+
+/// The kernel's OOM badness score of a process (0 to 1000, higher is killed first) and the
+/// oom_score_adj it was given. None where the files can't be read.
+pub fn read_oom_score(proc_root: &Path, pid: u32) -> (Option<i32>, Option<i32>) {
+    let read = |file: &str| std::fs::read_to_string(pid_path(proc_root, pid, file)).ok()?.trim().parse().ok();
+    (read("oom_score"), read("oom_score_adj"))
+}
+
+/// Memory map totals of a process (RSS, PSS, ...) in KB, from smaps_rollup or, on kernels
+/// without it, summed over smaps. Reading another user's process needs root.
+pub fn read_smaps(proc_root: &Path, pid: u32) -> std::io::Result<crate::procfs_parse::SmapsSummary> {
+    let text = match std::fs::read_to_string(pid_path(proc_root, pid, "smaps_rollup")) {
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => std::fs::read_to_string(pid_path(proc_root, pid, "smaps"))?,
+        result => result?,
+    };
+    Ok(crate::procfs_parse::parse_smaps(&text))
+}
+
+// Ties on the sort column are broken by ascending PID, so rows with equal values (e.g. the many
+// processes at 0% CPU) keep their order from one refresh to the next instead of jittering
+fn sort_list(list: &mut [ProcessInfo], mode: &str, ascending: bool) {
+    use std::cmp::Ordering;
+    let primary: fn(&ProcessInfo, &ProcessInfo) -> Ordering = match mode {
+        "pid" => |a, b| a.pid.cmp(&b.pid),
+        "mem" => |a, b| a.memory_usage.cmp(&b.memory_usage),
+        "ppid" => |a, b| a.parent_pid.unwrap_or(0).cmp(&b.parent_pid.unwrap_or(0)),
+        "start" => |a, b| a.start_time.cmp(&b.start_time),
+        "uptime" => |a, b| a.uptime_secs.cmp(&b.uptime_secs),
+        "nice" => |a, b| a.nice.cmp(&b.nice),
+        "threads" => |a, b| a.thread_count.cmp(&b.thread_count),
+        "mem_percent" => |a, b| a.mem_percent.partial_cmp(&b.mem_percent).unwrap_or(Ordering::Equal),
+        "swap" => |a, b| a.swap_usage.cmp(&b.swap_usage),
+        "cpu_time" => |a, b| a.cpu_time_secs.partial_cmp(&b.cpu_time_secs).unwrap_or(Ordering::Equal),
+        "cpu" => |a, b| a.cpu_usage.partial_cmp(&b.cpu_usage).unwrap_or(Ordering::Equal),
+        _ => return,
+    };
+    list.sort_by(|a, b| {
+        let ordering = if ascending { primary(a, b) } else { primary(b, a) };
+        ordering.then(a.pid.cmp(&b.pid))
+    });
+}
+
+/// How a process was started, read by `ProcessManager::begin_restart` before terminating it.
+pub struct RestartCommand {
+    program: std::ffi::OsString,
+    argv: Vec<std::ffi::OsString>, // Starting with the original argv[0]
+    cwd: PathBuf,
+    environment: Vec<(String, String)>,
+}
+
+impl RestartCommand {
+    /// Start the process again with the same executable, arguments, working directory and
+    /// environment, and return the new PID. The copy runs as this user in its own process group,
+    /// detached from the terminal, so it may not behave like the original if that ran with other
+    /// privileges or a since-changed environment.
+    pub fn spawn(self) -> std::io::Result<u32> {
+        use std::os::unix::process::CommandExt;
+        let mut child = std::process::Command::new(self.program)
+            .arg0(&self.argv[0])
+            .args(&self.argv[1..])
+            .current_dir(self.cwd)
+            .env_clear()
+            .envs(self.environment)
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .process_group(0)
+            .spawn()?;
+        let new_pid = child.id();
+        // The relaunched process is our child; reap it whenever it exits so it can't linger as a zombie
+        std::thread::spawn(move || child.wait());
+        Ok(new_pid)
+    }
+}
+
+/// Whether a process is gone, or a zombie waiting for its parent to reap it.
+pub fn has_exited(proc_root: &Path, pid: u32) -> bool {
+    match ProcfsProcess::new_with_root(proc_root.join(pid.to_string())).and_then(|process| process.stat()) {
+        Ok(stat) => stat.state == 'Z',
+        Err(_) => true,
+    }
+}
+
+/// Whether an environment variable name suggests its value is a credential.
+pub fn is_secret_name(name: &str) -> bool {
+    let name = name.to_uppercase();
+    ["TOKEN", "KEY", "PASSWORD", "PASSWD", "SECRET", "CREDENTIAL"].iter().any(|word| name.contains(word))
+}
+
+// Advance a process's CPU sample. `elapsed_ticks` is the CPU time per CPU since the last
+// sample, or None if it's too early to take a new one.
+fn sample_cpu(previous: Option<&CpuSample>, stat: &procfs::process::Stat, elapsed_ticks: Option<u64>) -> CpuSample {
+    let ticks = stat.utime + stat.stime;
+    match (previous.filter(|previous| previous.start_time == stat.starttime), elapsed_ticks) {
+        (Some(previous), None) => *previous,
+        (Some(previous), Some(elapsed)) => CpuSample {
+            start_time: stat.starttime,
+            ticks,
+            percent: (ticks.saturating_sub(previous.ticks) as f64 / elapsed as f64 * 100.0) as f32,
+        },
+        // First sight of this process: nothing to compare against yet
+        (None, _) => CpuSample { start_time: stat.starttime, ticks, percent: 0.0 },
+    }
+}
+
+// VmSwap from /proc/<pid>/status in bytes. Kernel threads and kernels before 2.6.34 don't report
+// it, which counts as nothing swapped out.
+fn read_swap_usage(proc_root: &Path, pid: u32) -> u64 {
+    std::fs::read_to_string(pid_path(proc_root, pid, "status"))
+        .ok()
+        .and_then(|status| {
+            let value = status.lines().find_map(|line| line.strip_prefix("VmSwap:"))?;
+            value.split_whitespace().next()?.parse::<u64>().ok()
+        })
+        .map_or(0, |kb| kb * 1024)
+}
+
+// Total system RAM in bytes from /proc/meminfo (0 if unreadable)
+fn read_total_memory(proc_root: &Path) -> u64 {
+    crate::diagnostics::read_proc(proc_root, "meminfo", "memory stats disabled")
+        .map(|meminfo| crate::procfs_parse::parse_meminfo(&meminfo).total * 1024)
+        .unwrap_or(0)
+}
+
+// Each cached handle holds a file descriptor. sysinfo keeps its own per-process files open too,
+// so stay well under the usual 1024 descriptor limit.
+const MAX_CACHED_HANDLES: usize = 256;
+
+// Read /proc/<pid>/stat through a cached directory handle, so a refresh costs one open per
+// process instead of two. A stale handle (the PID exited and was reused) fails to read, and a
+// fresh handle is opened and returned for the caller to cache.
+fn read_stat(proc_root: &Path, handles: &HashMap<u32, ProcfsProcess>, pid: u32) -> (Option<procfs::process::Stat>, Option<ProcfsProcess>) {
+    if let Some(stat) = handles.get(&pid).and_then(|handle| handle.stat().ok()) {
+        return (Some(stat), None);
+    }
+    let Ok(handle) = ProcfsProcess::new_with_root(proc_root.join(pid.to_string())) else {
+        return (None, None);
+    };
+    match handle.stat() {
+        Ok(stat) => (Some(stat), Some(handle)),
+        Err(_) => (None, None),
+    }
+}
+
+// /proc/<pid>/<file> under `proc_root`
+fn pid_path(proc_root: &Path, pid: u32, file: &str) -> PathBuf {
+    proc_root.join(pid.to_string()).join(file)
+}
+
+// Read every process of a procfs other than ours (a container's, a captured copy) with procfs
+// alone. sysinfo can't be pointed there, so user names aren't resolved (the UID is shown) and CPU%
+// always comes from the stat deltas. Start times are rebuilt from that system's boot time.
+fn collect_under_root(proc_root: &Path, samples: &HashMap<u32, CpuSample>, elapsed_cpu_ticks: Option<u64>, total_memory: u64, boot_time: u64) -> Collected {
+    let Ok(processes) = procfs::process::all_processes_with_root(proc_root) else {
+        crate::diagnostics::record_failure(proc_root.to_path_buf(), "process list disabled");
+        return Vec::new();
+    };
+    let ticks_per_second = procfs::ticks_per_second().max(1);
+    let page_size = procfs::page_size();
+    processes
+        .flatten()
+        .filter_map(|process| {
+            // Processes that exit between listing and reading are skipped
+            let stat = process.stat().ok()?;
+            let status = process.status().ok();
+            let pid = stat.pid as u32;
+            let sample = sample_cpu(samples.get(&pid), &stat, elapsed_cpu_ticks);
+            let start_time = boot_time + stat.starttime / ticks_per_second;
+            let memory_usage = stat.rss * page_size;
+            let cmdline = process.cmdline().unwrap_or_default().join(" ");
+            let cgroup = std::fs::read_to_string(pid_path(proc_root, pid, "cgroup"))
+                .ok()
+                .and_then(|text| crate::procfs_parse::parse_cgroup(&text));
+            let state = sysinfo::ProcessStatus::from(stat.state);
+            let proc_info = ProcessInfo {
+                pid,
+                name: stat.comm.clone(),
+                cpu_usage: sample.percent,
+                memory_usage,
+                swap_usage: status.as_ref().and_then(|status| status.vmswap).map_or(0, |kb| kb * 1024),
+                virtual_memory: stat.vsize,
+                parent_pid: (stat.ppid > 0).then_some(stat.ppid as u32),
+                status: state.to_string(),
+                state: state.into(),
+                user: None,
+                uid: status.as_ref().map(|status| status.ruid),
+                gid: status.as_ref().map(|status| status.rgid),
+                nice: stat.nice as i32,
+                start_time_str: format_timestamp(start_time),
+                start_time,
+                uptime_secs: crate::util::seconds_since(start_time),
+                thread_count: stat.num_threads.max(1) as usize,
+                cpu_time_secs: (stat.utime + stat.stime) as f64 / ticks_per_second as f64,
+                mem_percent: if total_memory > 0 {
+                    (memory_usage as f64 / total_memory as f64 * 100.0) as f32
+                } else {
+                    0.0
+                },
+                kernel_thread: stat.flags & PF_KTHREAD != 0,
+                cmdline,
+                cgroup: cgroup.clone(),
+                container: cgroup.as_deref().and_then(crate::procfs_parse::container_from_cgroup),
+                cwd: process.cwd().ok(),
+                exe: process.exe().ok(),
+            };
+            Some((proc_info, None, Some(sample), None))
+        })
+        .collect()
+}
+
+// Worker pool for parallel collection, capped so a refresh doesn't compete with the whole machine
+#[cfg(feature = "parallel")]
+fn collection_pool() -> &'static rayon::ThreadPool {
+    static POOL: std::sync::OnceLock<rayon::ThreadPool> = std::sync::OnceLock::new();
+    POOL.get_or_init(|| {
+        let threads = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1).min(4);
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .expect("failed to build process collection thread pool")
+    })
+}
+
 // Function to format the timestamp
 fn format_timestamp(timestamp: u64) -> String {
     // The timestamp from sysinfo is usually in seconds since boot
@@ -281,3 +1186,67 @@ fn format_timestamp(timestamp: u64) -> String {
         _ => "00:00:00".to_string() // Fallback if conversion fails
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process(pid: u32, name: &str, cpu_usage: f32, memory_mb: u64) -> ProcessInfo {
+        ProcessInfo {
+            pid,
+            name: name.to_string(),
+            cpu_usage,
+            memory_usage: memory_mb * 1024 * 1024,
+            swap_usage: 0,
+            virtual_memory: 0,
+            parent_pid: Some(1),
+            status: "Runnable".to_string(),
+            state: ProcessState::Running,
+            user: None,
+            uid: None,
+            gid: None,
+            nice: 0,
+            start_time_str: String::new(),
+            start_time: 0,
+            uptime_secs: 0,
+            thread_count: 1,
+            cpu_time_secs: 0.0,
+            mem_percent: 0.0,
+            cmdline: String::new(),
+            kernel_thread: false,
+            cgroup: None,
+            container: None,
+            cwd: None,
+            exe: None,
+        }
+    }
+
+    #[test]
+    fn rule_filters_and_keeps_sort_order() {
+        // A captured procfs without process directories, so the list is only what the test sets
+        let mut manager = ProcessManager::new(Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/proc/ubuntu-22.04")));
+        assert!(manager.get_processes().is_empty());
+        manager.processes = vec![
+            process(10, "idle", 0.5, 20),
+            process(11, "build", 95.0, 900),
+            process(12, "db", 40.0, 2000),
+            process(13, "editor", 12.0, 300),
+        ];
+        let mut rules = RuleEngine::new();
+        rules.active_rule = Some("cpu > 10.0 && mem > 100.0".to_string());
+
+        manager.set_sort("cpu", false);
+        manager.apply_rules(&rules);
+        let pids: Vec<u32> = manager.visible_processes(&rules).iter().map(|p| p.pid).collect();
+        assert_eq!(pids, [11, 12, 13]);
+
+        // A sort change before the next refresh reorders the filtered set as well
+        manager.set_sort("mem", false);
+        let pids: Vec<u32> = manager.get_filtered_processes().iter().map(|p| p.pid).collect();
+        assert_eq!(pids, [12, 11, 13]);
+
+        // Without a rule every process is visible
+        rules.active_rule = None;
+        assert_eq!(manager.visible_processes(&rules).len(), 4);
+    }
+}
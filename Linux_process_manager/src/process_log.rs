@@ -3,6 +3,8 @@
 
 use ratatui::{Frame, layout::Rect};
 use chrono::{DateTime, Local};
+use crate::ui::{NAME_COLUMN_WIDTH, USER_COLUMN_WIDTH};
+use crate::util::MemoryUnit;
 
 /// Struct to store exited process info for the log.
 #[derive(Clone)]
@@ -13,30 +15,88 @@ pub struct ProcessExitLogEntry {
     pub start_time: String,
     pub exit_time: DateTime<Local>,
     pub uptime_secs: u64,
+    pub peak_cpu: f32, // Highest CPU% seen over the process's recorded history
+    pub peak_mem: u64, // Highest memory (bytes) seen over the process's recorded history
 }
 
-/// Render the process log tab.
-pub fn render_process_log_tab(frame: &mut Frame, area: Rect, log: &[ProcessExitLogEntry]) {
+/// Column the process log is sorted by.
+#[derive(PartialEq, Clone, Copy)]
+pub enum LogSortMode {
+    Pid,
+    Name,
+    Uptime,
+    ExitTime,
+}
+
+impl LogSortMode {
+    /// Cycle to the next sort column (PID -> Name -> Uptime -> Exit Time -> PID).
+    pub fn next(self) -> Self {
+        match self {
+            LogSortMode::Pid => LogSortMode::Name,
+            LogSortMode::Name => LogSortMode::Uptime,
+            LogSortMode::Uptime => LogSortMode::ExitTime,
+            LogSortMode::ExitTime => LogSortMode::Pid,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            LogSortMode::Pid => "PID",
+            LogSortMode::Name => "Name",
+            LogSortMode::Uptime => "Uptime",
+            LogSortMode::ExitTime => "Exit Time",
+        }
+    }
+}
+
+/// Sort log entries in place by the given column.
+pub fn sort_exit_log(log: &mut [ProcessExitLogEntry], mode: LogSortMode, ascending: bool) {
+    log.sort_by(|a, b| {
+        let ordering = match mode {
+            LogSortMode::Pid => a.pid.cmp(&b.pid),
+            LogSortMode::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+            LogSortMode::Uptime => a.uptime_secs.cmp(&b.uptime_secs),
+            LogSortMode::ExitTime => a.exit_time.cmp(&b.exit_time),
+        };
+        if ascending { ordering } else { ordering.reverse() }
+    });
+}
+
+/// Render the process log tab. `log` is expected to already be sorted by `sort_mode`.
+pub fn render_process_log_tab(frame: &mut Frame, area: Rect, log: &[ProcessExitLogEntry], sort_mode: LogSortMode, sort_ascending: bool, memory_unit: MemoryUnit) {
     use ratatui::widgets::{Table, Row, Cell, Block, Borders};
     use ratatui::style::{Style, Color};
     use ratatui::layout::Constraint;
 
+    // Sort indicator for the active column
+    let title = |label: &str, column: LogSortMode| -> String {
+        if column == sort_mode {
+            format!("{}{}", label, if sort_ascending { " ↑" } else { " ↓" })
+        } else {
+            label.to_string()
+        }
+    };
+
     let header = Row::new(vec![
-        Cell::from("PID").style(Style::default().fg(Color::Yellow)),
-        Cell::from("Name").style(Style::default().fg(Color::Green)),
+        Cell::from(title("PID", LogSortMode::Pid)).style(Style::default().fg(Color::Yellow)),
+        Cell::from(title("Name", LogSortMode::Name)).style(Style::default().fg(Color::Green)),
         Cell::from("User").style(Style::default().fg(Color::Magenta)),
         Cell::from("Start Time").style(Style::default().fg(Color::Cyan)),
-        Cell::from("Exit Time").style(Style::default().fg(Color::Blue)),
-        Cell::from("Uptime").style(Style::default().fg(Color::White)),
+        Cell::from(title("Exit Time", LogSortMode::ExitTime)).style(Style::default().fg(Color::Blue)),
+        Cell::from(title("Uptime", LogSortMode::Uptime)).style(Style::default().fg(Color::White)),
+        Cell::from("Peak CPU").style(Style::default().fg(Color::Red)),
+        Cell::from("Peak Mem").style(Style::default().fg(Color::Green)),
     ]);
-    let rows: Vec<Row> = log.iter().rev().map(|entry| {
+    let rows: Vec<Row> = log.iter().map(|entry| {
         Row::new(vec![
             Cell::from(entry.pid.to_string()),
-            Cell::from(entry.name.clone()),
-            Cell::from(entry.user.clone().unwrap_or_default()),
+            Cell::from(crate::util::ellipsize(&entry.name, NAME_COLUMN_WIDTH as usize)),
+            Cell::from(crate::util::ellipsize(entry.user.as_deref().unwrap_or_default(), USER_COLUMN_WIDTH as usize)),
             Cell::from(entry.start_time.clone()),
             Cell::from(entry.exit_time.format("%Y-%m-%d %H:%M:%S").to_string()),
-            Cell::from(format!("{}s", entry.uptime_secs)),
+            Cell::from(crate::util::format_duration(entry.uptime_secs)),
+            Cell::from(format!("{:.1}%", entry.peak_cpu)),
+            Cell::from(crate::util::format_bytes(entry.peak_mem, memory_unit)),
         ])
     }).collect();
     let table = Table::new(rows)
@@ -44,11 +104,13 @@ pub fn render_process_log_tab(frame: &mut Frame, area: Rect, log: &[ProcessExitL
         .block(Block::default().borders(Borders::ALL).title("Exited Processes Log"))
         .widths(&[
             Constraint::Length(8),
-            Constraint::Length(20),
-            Constraint::Length(12),
+            Constraint::Length(NAME_COLUMN_WIDTH),
+            Constraint::Length(USER_COLUMN_WIDTH),
             Constraint::Length(19),
-            Constraint::Length(19),
-            Constraint::Length(8),
+            Constraint::Length(21),
+            Constraint::Length(10),
+            Constraint::Length(9),
+            Constraint::Length(10),
         ]);
     frame.render_widget(table, area);
 } 
\ No newline at end of file
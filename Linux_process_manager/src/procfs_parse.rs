@@ -0,0 +1,465 @@
+//! /proc parsing module
+// Pure parsers for the system-wide /proc files read by the statistics views, and for the
+// per-process files whose format is involved enough to need tests. They take the file contents as
+// text so they can be tested against captured samples; the callers do the reading.
+// Fields missing from a file (older kernels, containers) come back as 0 or None here rather than
+// being defaulted at every call site.
+
+use std::net::{IpAddr, SocketAddr};
+
+/// Values from /proc/meminfo, in KB.
+#[derive(Debug, Default, PartialEq)]
+pub struct MemInfo {
+    pub total: u64,
+    pub free: u64,
+    pub available: Option<u64>, // MemAvailable only exists since Linux 3.14
+    pub buffers: u64,
+    pub cached: u64,
+    pub swap_total: u64,
+    pub swap_free: u64,
+}
+
+impl MemInfo {
+    /// Memory in use, preferring the kernel's own estimate of what is available.
+    pub fn used(&self) -> u64 {
+        match self.available {
+            Some(available) => self.total.saturating_sub(available),
+            None => self.total.saturating_sub(self.free + self.cached + self.buffers),
+        }
+    }
+
+    /// Swap in use, summed over every swap device.
+    pub fn swap_used(&self) -> u64 {
+        self.swap_total.saturating_sub(self.swap_free)
+    }
+}
+
+pub fn parse_meminfo(text: &str) -> MemInfo {
+    let mut info = MemInfo::default();
+    for line in text.lines() {
+        let mut parts = line.split_whitespace();
+        let (Some(key), Some(value)) = (parts.next(), parts.next().and_then(|v| v.parse::<u64>().ok())) else {
+            continue;
+        };
+        match key {
+            "MemTotal:" => info.total = value,
+            "MemFree:" => info.free = value,
+            "MemAvailable:" => info.available = Some(value),
+            "Buffers:" => info.buffers = value,
+            "Cached:" => info.cached = value,
+            "SwapTotal:" => info.swap_total = value,
+            "SwapFree:" => info.swap_free = value,
+            _ => {}
+        }
+    }
+    info
+}
+
+/// One cpu line of /proc/stat, in clock ticks: user, nice, system, idle, iowait, irq, softirq, ...
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct CpuTimes(pub Vec<u64>);
+
+impl CpuTimes {
+    pub fn idle(&self) -> u64 {
+        self.0.get(3).copied().unwrap_or(0)
+    }
+
+    pub fn iowait(&self) -> u64 {
+        self.0.get(4).copied().unwrap_or(0)
+    }
+
+    pub fn total(&self) -> u64 {
+        self.0.iter().sum()
+    }
+}
+
+/// Values from /proc/stat.
+#[derive(Debug, Default, PartialEq)]
+pub struct Stat {
+    pub cpu: CpuTimes, // Aggregate of all CPUs
+    pub cpus: Vec<CpuTimes>, // Per CPU, in order
+    pub ctxt: u64,
+    pub processes: u64, // Forks since boot
+    pub procs_running: u64,
+    pub procs_blocked: u64,
+    pub interrupts: u64,
+    pub btime: u64, // Boot time, seconds since the epoch
+}
+
+pub fn parse_stat(text: &str) -> Stat {
+    let mut stat = Stat::default();
+    for line in text.lines() {
+        let mut parts = line.split_whitespace();
+        let Some(key) = parts.next() else { continue };
+        if key == "cpu" {
+            stat.cpu = CpuTimes(parts.filter_map(|v| v.parse().ok()).collect());
+        } else if key.starts_with("cpu") {
+            stat.cpus.push(CpuTimes(parts.filter_map(|v| v.parse().ok()).collect()));
+        } else {
+            // Every other line of interest has the value as its first field (intr then lists
+            // per-interrupt counts after the total)
+            let value = parts.next().and_then(|v| v.parse().ok()).unwrap_or(0);
+            match key {
+                "ctxt" => stat.ctxt = value,
+                "processes" => stat.processes = value,
+                "procs_running" => stat.procs_running = value,
+                "procs_blocked" => stat.procs_blocked = value,
+                "intr" => stat.interrupts = value,
+                "btime" => stat.btime = value,
+                _ => {}
+            }
+        }
+    }
+    stat
+}
+
+/// Counters from /proc/vmstat.
+#[derive(Debug, Default, PartialEq)]
+pub struct VmStat {
+    pub pgfault: u64,
+    pub pswpin: u64,
+    pub pswpout: u64,
+}
+
+pub fn parse_vmstat(text: &str) -> VmStat {
+    let mut vmstat = VmStat::default();
+    for line in text.lines() {
+        let mut parts = line.split_whitespace();
+        let (Some(key), Some(value)) = (parts.next(), parts.next().and_then(|v| v.parse::<u64>().ok())) else {
+            continue;
+        };
+        match key {
+            "pgfault" => vmstat.pgfault = value,
+            "pswpin" => vmstat.pswpin = value,
+            "pswpout" => vmstat.pswpout = value,
+            _ => {}
+        }
+    }
+    vmstat
+}
+
+/// Memory of one process from /proc/<pid>/smaps_rollup, or summed over every mapping in
+/// /proc/<pid>/smaps on kernels before 4.14, in KB.
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+pub struct SmapsSummary {
+    pub rss: u64,
+    pub pss: u64, // Resident memory with each shared page split between the processes mapping it
+    pub private_dirty: u64,
+    pub shared: u64, // Shared_Clean + Shared_Dirty
+    pub swap: u64,
+}
+
+pub fn parse_smaps(text: &str) -> SmapsSummary {
+    let mut summary = SmapsSummary::default();
+    for line in text.lines() {
+        let mut parts = line.split_whitespace();
+        // Mapping header lines ("7f12...-7f34... r-xp ...") don't parse as "Key: value"
+        let (Some(key), Some(value)) = (parts.next(), parts.next().and_then(|v| v.parse::<u64>().ok())) else {
+            continue;
+        };
+        match key {
+            "Rss:" => summary.rss += value,
+            "Pss:" => summary.pss += value,
+            "Private_Dirty:" => summary.private_dirty += value,
+            "Shared_Clean:" | "Shared_Dirty:" => summary.shared += value,
+            "Swap:" => summary.swap += value,
+            _ => {}
+        }
+    }
+    summary
+}
+
+/// The 1, 5 and 15 minute load averages from /proc/loadavg.
+pub fn parse_loadavg(text: &str) -> Option<(f64, f64, f64)> {
+    let values: Vec<f64> = text.split_whitespace().take(3).filter_map(|s| s.parse().ok()).collect();
+    match values[..] {
+        [one, five, fifteen] => Some((one, five, fifteen)),
+        _ => None,
+    }
+}
+
+/// Seconds since boot, the first field of /proc/uptime.
+pub fn parse_uptime(text: &str) -> Option<f64> {
+    text.split_whitespace().next()?.parse().ok()
+}
+
+/// Summary of /proc/cpuinfo. The fields describe the first processor listed.
+#[derive(Debug, Default, PartialEq)]
+pub struct CpuModel {
+    pub processors: usize,
+    pub model: String,
+    pub mhz: Option<f64>, // Not reported on most ARM boards
+    pub cache: String,
+}
+
+pub fn parse_cpuinfo(text: &str) -> CpuModel {
+    let mut info = CpuModel::default();
+    for line in text.lines() {
+        let Some((key, value)) = line.split_once(':') else { continue };
+        let value = value.trim();
+        match key.trim() {
+            "processor" => info.processors += 1,
+            "model name" if info.model.is_empty() => info.model = value.to_string(),
+            "cpu MHz" if info.mhz.is_none() => info.mhz = value.parse().ok(),
+            "cache size" if info.cache.is_empty() => info.cache = value.to_string(),
+            _ => {}
+        }
+    }
+    info
+}
+
+/// The cgroup path from /proc/<pid>/cgroup: the unified (v2) hierarchy if there is one, else the
+/// first v1 hierarchy listed.
+pub fn parse_cgroup(text: &str) -> Option<String> {
+    let mut first = None;
+    for line in text.lines() {
+        // hierarchy-id:controllers:path, where the path may itself contain ':'
+        let mut parts = line.splitn(3, ':');
+        let (Some(id), Some(_), Some(path)) = (parts.next(), parts.next(), parts.next()) else { continue };
+        if id == "0" {
+            return Some(path.to_string());
+        }
+        first.get_or_insert_with(|| path.to_string());
+    }
+    first
+}
+
+// Unit name prefixes systemd-managed runtimes give a container's scope, e.g. docker-<id>.scope
+const CONTAINER_SCOPES: &[(&str, &str)] = &[
+    ("docker-", "docker"),
+    ("cri-containerd-", "containerd"),
+    ("crio-", "crio"),
+    ("libpod-", "podman"),
+];
+
+/// Short container label such as "docker:3f4e2a1b9c0d" for a cgroup path created by docker,
+/// containerd, CRI-O or podman, or None for processes outside a container.
+pub fn container_from_cgroup(path: &str) -> Option<String> {
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    for (i, segment) in segments.iter().enumerate().rev() {
+        let unit = segment.strip_suffix(".scope").unwrap_or(segment);
+        // conmon is the runtime's monitor process, not the container itself
+        if unit.contains("conmon") {
+            return None;
+        }
+        for (prefix, runtime) in CONTAINER_SCOPES {
+            if let Some(id) = unit.strip_prefix(prefix).filter(|id| is_container_id(id)) {
+                return Some(format!("{}:{}", runtime, &id[..12]));
+            }
+        }
+        // cgroupfs driver: /docker/<id> or /kubepods/<qos>/pod<uid>/<id>
+        if is_container_id(unit) {
+            let runtime = match segments[..i].first() {
+                Some(&"docker") => "docker",
+                Some(parent) if parent.starts_with("kubepods") => "kubepods",
+                _ => "container",
+            };
+            return Some(format!("{}:{}", runtime, &unit[..12]));
+        }
+    }
+    None
+}
+
+// Container IDs are 64 hex digits
+fn is_container_id(id: &str) -> bool {
+    id.len() == 64 && id.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+/// One socket from /proc/net/tcp, tcp6, udp or udp6.
+#[derive(Debug, PartialEq, Clone)]
+pub struct NetSocket {
+    pub local: SocketAddr,
+    pub remote: SocketAddr,
+    pub state: u8, // Kernel TCP state, e.g. 0x01 established, 0x0A listening; UDP uses 0x07 when unconnected
+    pub inode: u64,
+}
+
+/// Parse one of the /proc/net socket tables, IPv4 or IPv6. The header line is skipped.
+pub fn parse_net_sockets(text: &str) -> Vec<NetSocket> {
+    text.lines()
+        .skip(1)
+        .filter_map(|line| {
+            // sl local_address rem_address st tx_queue:rx_queue tr:tm->when retrnsmt uid timeout inode ...
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 10 {
+                return None;
+            }
+            Some(NetSocket {
+                local: parse_socket_addr(fields[1])?,
+                remote: parse_socket_addr(fields[2])?,
+                state: u8::from_str_radix(fields[3], 16).ok()?,
+                inode: fields[9].parse().ok()?,
+            })
+        })
+        .collect()
+}
+
+// "0100007F:1F90" is 127.0.0.1:8080: the address bytes are printed as 32-bit words in host byte
+// order, the port as plain hex. IPv6 addresses are four such words.
+fn parse_socket_addr(text: &str) -> Option<SocketAddr> {
+    let (address, port) = text.split_once(':')?;
+    let port = u16::from_str_radix(port, 16).ok()?;
+    let mut bytes = Vec::with_capacity(16);
+    for i in (0..address.len()).step_by(8) {
+        let word = u32::from_str_radix(address.get(i..i + 8)?, 16).ok()?;
+        bytes.extend_from_slice(&word.to_ne_bytes());
+    }
+    let ip = match bytes.len() {
+        4 => IpAddr::from(<[u8; 4]>::try_from(bytes).ok()?),
+        16 => IpAddr::from(<[u8; 16]>::try_from(bytes).ok()?),
+        _ => return None,
+    };
+    Some(SocketAddr::new(ip, port))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Captured /proc files, one directory per system
+    macro_rules! fixture {
+        ($system:literal, $file:literal) => {
+            include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/proc/", $system, "/", $file))
+        };
+    }
+
+    #[test]
+    fn meminfo_with_mem_available() {
+        let info = parse_meminfo(fixture!("ubuntu-22.04", "meminfo"));
+        assert_eq!(info.total, 16303428);
+        assert_eq!(info.available, Some(11245508));
+        assert_eq!(info.used(), 16303428 - 11245508);
+        assert_eq!((info.swap_total, info.swap_free), (2097148, 1835004));
+    }
+
+    #[test]
+    fn meminfo_without_mem_available_subtracts_cache() {
+        let info = parse_meminfo(fixture!("centos-5", "meminfo"));
+        assert_eq!(info.available, None);
+        assert_eq!(info.used(), 2061260 - 402112 - 981244 - 136872);
+    }
+
+    #[test]
+    fn swap_is_summed_over_devices() {
+        // Two 2 GiB swap devices, 1.5 GiB of them in use
+        let info = parse_meminfo("SwapTotal:       4194304 kB\nSwapFree:        2621440 kB\n");
+        assert_eq!(info.swap_used() / 1024, 1536);
+        assert_eq!(info.swap_total / 1024, 4096);
+        assert_eq!(parse_meminfo("").swap_used(), 0);
+    }
+
+    #[test]
+    fn stat_cpu_lines_and_counters() {
+        let stat = parse_stat(fixture!("ubuntu-22.04", "stat"));
+        assert_eq!(stat.cpus.len(), 4);
+        assert_eq!(stat.cpu.idle(), 41962617);
+        assert_eq!(stat.cpu.iowait(), 51372);
+        assert_eq!(stat.cpus[0].total(), 462383 + 1205 + 131077 + 10487133 + 13205 + 5822);
+        assert_eq!(stat.ctxt, 611929443);
+        assert_eq!(stat.processes, 1073592);
+        assert_eq!((stat.procs_running, stat.procs_blocked), (2, 0));
+        assert_eq!(stat.interrupts, 297361822);
+        assert_eq!(stat.btime, 1696402215);
+    }
+
+    #[test]
+    fn smaps_rollup_and_summed_smaps_agree() {
+        let rollup = "55d4c5a0e000-7ffd8a3f1000 ---p 00000000 00:00 0                          [rollup]\n\
+            Rss:                9216 kB\nPss:                5120 kB\nPss_Anon:           2048 kB\n\
+            Shared_Clean:       4096 kB\nShared_Dirty:          0 kB\nPrivate_Clean:      3072 kB\n\
+            Private_Dirty:      2048 kB\nSwap:                512 kB\nSwapPss:             512 kB\n";
+        let smaps = "55d4c5a0e000-55d4c5a10000 r-xp 00000000 08:01 1311 /usr/bin/app\n\
+            Rss:                7168 kB\nPss:                3072 kB\nShared_Clean:       4096 kB\n\
+            Shared_Dirty:          0 kB\nPrivate_Clean:      3072 kB\nPrivate_Dirty:         0 kB\nSwap:                  0 kB\n\
+            7ffd8a3d0000-7ffd8a3f1000 rw-p 00000000 00:00 0 [stack]\n\
+            Rss:                2048 kB\nPss:                2048 kB\nShared_Clean:          0 kB\n\
+            Shared_Dirty:          0 kB\nPrivate_Clean:         0 kB\nPrivate_Dirty:      2048 kB\nSwap:                512 kB\n";
+        let expected = SmapsSummary { rss: 9216, pss: 5120, private_dirty: 2048, shared: 4096, swap: 512 };
+        assert_eq!(parse_smaps(rollup), expected);
+        assert_eq!(parse_smaps(smaps), expected);
+    }
+
+    #[test]
+    fn stat_from_older_kernel_has_fewer_cpu_fields() {
+        let stat = parse_stat(fixture!("centos-5", "stat"));
+        assert_eq!(stat.cpus.len(), 2);
+        assert_eq!(stat.cpu.0.len(), 8);
+        assert_eq!(stat.cpu.idle(), 9214556);
+    }
+
+    #[test]
+    fn vmstat_counters() {
+        let vmstat = parse_vmstat(fixture!("ubuntu-22.04", "vmstat"));
+        assert_eq!(vmstat, VmStat { pgfault: 1384250197, pswpin: 10342, pswpout: 76210 });
+        assert_eq!(parse_vmstat(fixture!("raspbian-11", "vmstat")).pswpout, 0);
+    }
+
+    #[test]
+    fn loadavg_and_uptime() {
+        assert_eq!(parse_loadavg(fixture!("ubuntu-22.04", "loadavg")), Some((0.52, 0.61, 0.58)));
+        assert_eq!(parse_loadavg(""), None);
+        assert_eq!(parse_uptime(fixture!("centos-5", "uptime")), Some(1163470.78));
+        assert_eq!(parse_uptime("garbage"), None);
+    }
+
+    #[test]
+    fn cpuinfo_x86() {
+        let info = parse_cpuinfo(fixture!("ubuntu-22.04", "cpuinfo"));
+        assert_eq!(info.processors, 4);
+        assert_eq!(info.model, "Intel(R) Core(TM) i5-7200U CPU @ 2.50GHz");
+        assert_eq!(info.mhz, Some(2700.012));
+        assert_eq!(info.cache, "3072 KB");
+    }
+
+    #[test]
+    fn cgroup_prefers_unified_hierarchy() {
+        let v1_and_v2 = "12:pids:/user.slice\n1:name=systemd:/user.slice/session-2.scope\n0::/user.slice/user-1000.slice/session-2.scope\n";
+        assert_eq!(parse_cgroup(v1_and_v2).as_deref(), Some("/user.slice/user-1000.slice/session-2.scope"));
+        assert_eq!(parse_cgroup("11:memory:/docker/abc\n4:cpu,cpuacct:/docker/abc\n").as_deref(), Some("/docker/abc"));
+        assert_eq!(parse_cgroup(""), None);
+    }
+
+    #[test]
+    fn container_ids_from_cgroup_paths() {
+        let id = "3f4e2a1b9c0d5e6f7a8b9c0d1e2f3a4b5c6d7e8f9a0b1c2d3e4f5a6b7c8d9e0f";
+        assert_eq!(container_from_cgroup(&format!("/system.slice/docker-{}.scope", id)).as_deref(), Some("docker:3f4e2a1b9c0d"));
+        assert_eq!(container_from_cgroup(&format!("/docker/{}", id)).as_deref(), Some("docker:3f4e2a1b9c0d"));
+        assert_eq!(
+            container_from_cgroup(&format!("/kubepods.slice/kubepods-burstable.slice/kubepods-burstable-pod1234.slice/cri-containerd-{}.scope", id)).as_deref(),
+            Some("containerd:3f4e2a1b9c0d")
+        );
+        assert_eq!(container_from_cgroup(&format!("/kubepods/besteffort/pod5678/{}", id)).as_deref(), Some("kubepods:3f4e2a1b9c0d"));
+        assert_eq!(container_from_cgroup(&format!("/machine.slice/libpod-{}.scope/container", id)).as_deref(), Some("podman:3f4e2a1b9c0d"));
+        assert_eq!(container_from_cgroup(&format!("/machine.slice/libpod-conmon-{}.scope", id)), None);
+        assert_eq!(container_from_cgroup("/user.slice/user-1000.slice/session-2.scope"), None);
+        assert_eq!(container_from_cgroup("/"), None);
+    }
+
+    #[test]
+    fn cpuinfo_arm_lacks_frequency_and_cache() {
+        let info = parse_cpuinfo(fixture!("raspbian-11", "cpuinfo"));
+        assert_eq!(info.processors, 4);
+        assert_eq!(info.model, "ARMv7 Processor rev 4 (v7l)");
+        assert_eq!(info.mhz, None);
+        assert_eq!(info.cache, "");
+    }
+
+    #[test]
+    fn net_sockets_ipv4_and_ipv6() {
+        let tcp = "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode\n   \
+            0: 0100007F:1F90 00000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 123456 1 0000000000000000 100 0 0 10 0\n   \
+            1: 0100007F:1F90 0100007F:D431 01 00000000:00000000 00:00000000 00000000  1000        0 123457 1 0000000000000000 20 4 30 10 -1\n";
+        let sockets = parse_net_sockets(tcp);
+        assert_eq!(sockets.len(), 2);
+        assert_eq!(sockets[0].local, "127.0.0.1:8080".parse().unwrap());
+        assert_eq!((sockets[0].state, sockets[0].inode), (0x0A, 123456));
+        assert_eq!(sockets[1].remote, "127.0.0.1:54321".parse().unwrap());
+
+        let tcp6 = "  sl  local_address                         remote_address                        st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode\n   \
+            0: 00000000000000000000000001000000:0277 00000000000000000000000000000000:0000 0A 00000000:00000000 00:00000000 00000000     0        0 23456 1 0000000000000000 100 0 0 10 0\n";
+        let sockets = parse_net_sockets(tcp6);
+        assert_eq!(sockets[0].local, "[::1]:631".parse().unwrap());
+        assert_eq!(sockets[0].inode, 23456);
+        assert!(parse_net_sockets("header only\n").is_empty());
+    }
+}
@@ -1,4 +1,4 @@
-use rhai::{Engine, Scope};
+use rhai::{Engine, Scope, AST};
 use crate::process::ProcessInfo;
 
 /// A lightweight snapshot of a process used for rule testing.
@@ -30,31 +30,38 @@ impl RuleEngine {
     }
 
     pub fn set_rule(&mut self, rule: String) {
-        self.active_rule = Some(rule.clone());
-        println!("Setting rule: {}", rule);
+        self.active_rule = Some(rule);
+    }
 
+    /// The processes matching `rule`, which is compiled once for the whole list. An empty rule
+    /// matches everything; a rule that doesn't parse is an error.
+    pub fn select<'a>(&self, rule: &str, processes: &'a [ProcessInfo]) -> Result<Vec<&'a ProcessInfo>, String> {
+        if rule.trim().is_empty() {
+            return Ok(processes.iter().collect());
+        }
+        let ast = self.engine.compile(rule).map_err(|e| e.to_string())?;
+        Ok(processes.iter().filter(|process| self.matches(&ast, process)).collect())
     }
 
-    // Evaluate and return a boolean result for testing
-    pub fn evaluate_for(&mut self, process: &ProcessInfo) -> bool {
+    /// Processes matching the active rule, or all of them without one. A rule that doesn't
+    /// parse matches nothing.
+    pub fn select_active<'a>(&self, processes: &'a [ProcessInfo]) -> Vec<&'a ProcessInfo> {
         match &self.active_rule {
-            Some(rule) if !rule.trim().is_empty() => {
-                let mut scope = Scope::new();
-                scope.push("cpu", process.cpu_usage as f64);
-                scope.push("mem", process.memory_usage as f64 / 1024.0 / 1024.0);
-                scope.push("pid", process.pid as i64);
-                scope.push("name", process.name.clone() as String);
-    
-                let result = self.engine.eval_with_scope::<bool>(&mut scope, rule);
-    
-                match result {
-                    Ok(val) => val,
-                    Err(_) => false, // ignore errors
-                }
-            }
-            _ => true, // No rule or empty string = allow all
+            Some(rule) => self.select(rule, processes).unwrap_or_default(),
+            None => processes.iter().collect(),
         }
     }
-    
-    
+
+    // Evaluate a compiled rule for one process
+    fn matches(&self, ast: &AST, process: &ProcessInfo) -> bool {
+        let mut scope = Scope::new();
+        scope.push("cpu", process.cpu_usage as f64);
+        scope.push("mem", process.memory_usage as f64 / 1024.0 / 1024.0);
+        scope.push("pid", process.pid as i64);
+        scope.push("name", process.name.clone() as String);
+
+        let result = self.engine.eval_ast_with_scope::<bool>(&mut scope, ast);
+
+        result.unwrap_or_default() // ignore errors, e.g. a rule that isn't a boolean
+    }
 }
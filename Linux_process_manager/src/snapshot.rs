@@ -0,0 +1,82 @@
+//! Process snapshot module
+// Captures the process list at a point in time and compares two captures: which processes
+// appeared, which went away and which changed CPU or memory noticeably in between.
+
+use crate::process::ProcessInfo;
+use chrono::{DateTime, Local};
+use std::collections::HashMap;
+
+/// Snapshots kept at once; taking another drops the oldest.
+pub const MAX_SNAPSHOTS: usize = 2;
+
+// A process counts as changed if its CPU% moved by at least this many points...
+const CPU_CHANGE_THRESHOLD: f32 = 5.0;
+// ...or its memory grew or shrank by this fraction, and by at least MEM_CHANGE_MIN_BYTES
+const MEM_CHANGE_RATIO: f64 = 0.2;
+const MEM_CHANGE_MIN_BYTES: u64 = 10 * 1024 * 1024;
+
+/// The whole process table as it was at `taken_at`, before any filter, so changing a filter
+/// between two snapshots doesn't show the hidden processes as gone.
+pub struct Snapshot {
+    pub name: String, // "#3", numbered in the order snapshots were taken
+    pub taken_at: DateTime<Local>,
+    pub processes: Vec<ProcessInfo>,
+}
+
+/// A process present in both snapshots whose usage changed noticeably.
+pub struct ChangedProcess {
+    pub before: ProcessInfo,
+    pub after: ProcessInfo,
+}
+
+/// Differences between an older and a newer snapshot.
+pub struct SnapshotDiff {
+    pub appeared: Vec<ProcessInfo>,
+    pub disappeared: Vec<ProcessInfo>,
+    pub changed: Vec<ChangedProcess>,
+}
+
+/// Compare two snapshots. Processes are matched by PID and start time, so a PID reused by a new
+/// process shows up as one process disappearing and another appearing.
+pub fn diff(older: &Snapshot, newer: &Snapshot) -> SnapshotDiff {
+    let before: HashMap<(u32, u64), &ProcessInfo> = older.processes.iter().map(|p| ((p.pid, p.start_time), p)).collect();
+    let after: HashMap<(u32, u64), &ProcessInfo> = newer.processes.iter().map(|p| ((p.pid, p.start_time), p)).collect();
+
+    let mut appeared: Vec<ProcessInfo> = newer
+        .processes
+        .iter()
+        .filter(|p| !before.contains_key(&(p.pid, p.start_time)))
+        .cloned()
+        .collect();
+    let mut disappeared: Vec<ProcessInfo> = older
+        .processes
+        .iter()
+        .filter(|p| !after.contains_key(&(p.pid, p.start_time)))
+        .cloned()
+        .collect();
+    let mut changed: Vec<ChangedProcess> = newer
+        .processes
+        .iter()
+        .filter_map(|p| {
+            let previous = before.get(&(p.pid, p.start_time))?;
+            changed_noticeably(previous, p).then(|| ChangedProcess { before: (*previous).clone(), after: p.clone() })
+        })
+        .collect();
+
+    appeared.sort_by_key(|p| p.pid);
+    disappeared.sort_by_key(|p| p.pid);
+    // Biggest CPU swings first
+    changed.sort_by(|a, b| {
+        let delta = |c: &ChangedProcess| (c.after.cpu_usage - c.before.cpu_usage).abs();
+        delta(b).total_cmp(&delta(a))
+    });
+    SnapshotDiff { appeared, disappeared, changed }
+}
+
+fn changed_noticeably(before: &ProcessInfo, after: &ProcessInfo) -> bool {
+    if (after.cpu_usage - before.cpu_usage).abs() >= CPU_CHANGE_THRESHOLD {
+        return true;
+    }
+    let delta = after.memory_usage.abs_diff(before.memory_usage);
+    delta >= MEM_CHANGE_MIN_BYTES && delta as f64 >= before.memory_usage as f64 * MEM_CHANGE_RATIO
+}
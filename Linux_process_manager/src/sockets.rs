@@ -0,0 +1,138 @@
+//! Socket module
+// Maps sockets to the processes holding them: /proc/net/{tcp,tcp6,udp,udp6} list every socket
+// with its inode, and each /proc/<pid>/fd entry for a socket links to "socket:[<inode>]".
+// Other users' fd directories are only readable as root, so lookups may come back incomplete.
+
+use crate::procfs_parse::{self, NetSocket};
+use std::collections::HashSet;
+use std::path::Path;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Protocol {
+    Tcp,
+    Udp,
+}
+
+impl Protocol {
+    pub fn label(self) -> &'static str {
+        match self {
+            Protocol::Tcp => "tcp",
+            Protocol::Udp => "udp",
+        }
+    }
+}
+
+// Kernel TCP state for a listening socket
+const TCP_LISTEN: u8 = 0x0A;
+
+/// A socket from the /proc/net tables.
+pub struct Socket {
+    pub protocol: Protocol,
+    pub info: NetSocket,
+}
+
+impl Socket {
+    /// A listening TCP socket, or a UDP socket not connected to a peer, i.e. one that receives
+    /// whatever is sent to its port.
+    pub fn is_listening(&self) -> bool {
+        match self.protocol {
+            Protocol::Tcp => self.info.state == TCP_LISTEN,
+            Protocol::Udp => self.info.remote.port() == 0,
+        }
+    }
+
+    /// State as `ss` names it; UDP sockets are just unconnected or connected.
+    pub fn state_label(&self) -> &'static str {
+        if self.protocol == Protocol::Udp {
+            return if self.is_listening() { "UNCONN" } else { "ESTAB" };
+        }
+        match self.info.state {
+            0x01 => "ESTAB",
+            0x02 => "SYN-SENT",
+            0x03 => "SYN-RECV",
+            0x04 => "FIN-WAIT-1",
+            0x05 => "FIN-WAIT-2",
+            0x06 => "TIME-WAIT",
+            0x07 => "CLOSE",
+            0x08 => "CLOSE-WAIT",
+            0x09 => "LAST-ACK",
+            TCP_LISTEN => "LISTEN",
+            0x0B => "CLOSING",
+            _ => "UNKNOWN",
+        }
+    }
+}
+
+/// Every TCP and UDP socket on the system, IPv4 and IPv6. Tables that can't be read (no IPv6)
+/// are skipped.
+pub fn read_sockets(proc_root: &Path) -> Vec<Socket> {
+    let tables = [("tcp", Protocol::Tcp), ("tcp6", Protocol::Tcp), ("udp", Protocol::Udp), ("udp6", Protocol::Udp)];
+    tables
+        .iter()
+        .flat_map(|(file, protocol)| {
+            let text = std::fs::read_to_string(proc_root.join("net").join(file)).unwrap_or_default();
+            procfs_parse::parse_net_sockets(&text).into_iter().map(|info| Socket { protocol: *protocol, info })
+        })
+        .collect()
+}
+
+/// Inodes of the sockets a process has open.
+pub fn socket_inodes(proc_root: &Path, pid: u32) -> std::io::Result<HashSet<u64>> {
+    let mut inodes = HashSet::new();
+    for entry in std::fs::read_dir(proc_root.join(pid.to_string()).join("fd"))? {
+        // The fd may close between listing and reading the link; skip it
+        let Ok(target) = entry.and_then(|entry| std::fs::read_link(entry.path())) else { continue };
+        if let Some(inode) = target
+            .to_str()
+            .and_then(|target| target.strip_prefix("socket:["))
+            .and_then(|target| target.strip_suffix(']'))
+            .and_then(|inode| inode.parse().ok())
+        {
+            inodes.insert(inode);
+        }
+    }
+    Ok(inodes)
+}
+
+/// The TCP and UDP sockets a process has open, listening ones first, then by local port.
+pub fn process_sockets(proc_root: &Path, pid: u32) -> std::io::Result<Vec<Socket>> {
+    let inodes = socket_inodes(proc_root, pid)?;
+    let mut sockets: Vec<Socket> = read_sockets(proc_root).into_iter().filter(|socket| inodes.contains(&socket.info.inode)).collect();
+    sockets.sort_by_key(|socket| (!socket.is_listening(), socket.info.local.port()));
+    Ok(sockets)
+}
+
+/// Who holds a port, from `processes_on_port`.
+pub struct PortOwners {
+    pub protocol: Option<Protocol>, // Of the first listening socket found; None if nothing listens
+    pub pids: Vec<u32>, // Lowest first, which for forked servers is usually the parent
+    pub unreadable: usize, // Processes whose open files we weren't allowed to list
+}
+
+/// Processes with a TCP socket listening on `port` or a UDP socket bound to it.
+pub fn processes_on_port(proc_root: &Path, port: u16) -> PortOwners {
+    let listening: Vec<Socket> = read_sockets(proc_root)
+        .into_iter()
+        .filter(|socket| socket.info.local.port() == port && socket.is_listening())
+        .collect();
+    let mut owners = PortOwners { protocol: listening.first().map(|socket| socket.protocol), pids: Vec::new(), unreadable: 0 };
+    if listening.is_empty() {
+        return owners;
+    }
+    let inodes: HashSet<u64> = listening.iter().map(|socket| socket.info.inode).collect();
+    let pids = std::fs::read_dir(proc_root)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| entry.file_name().to_str()?.parse::<u32>().ok());
+    for pid in pids {
+        match socket_inodes(proc_root, pid) {
+            Ok(held) if !held.is_disjoint(&inodes) => owners.pids.push(pid),
+            Ok(_) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied => owners.unreadable += 1,
+            Err(_) => {} // Exited meanwhile
+        }
+    }
+    owners.pids.sort_unstable();
+    owners
+}
@@ -0,0 +1,224 @@
+//! Color theme module
+// Palette for the process tables, menus and messages, picked by name with the `theme` config
+// option. "dark" is the original look, "light" suits light terminal backgrounds and "mono" uses
+// no colors at all (bold/reverse only) for limited terminals and screen readers.
+
+use crate::process::ProcessState;
+use crate::util::{usage_level, UsageLevel};
+use ratatui::buffer::Buffer;
+use ratatui::style::{Color, Modifier, Style};
+
+/// Names accepted by the `theme` config option.
+pub const THEME_NAMES: &[&str] = &["dark", "light", "mono"];
+
+#[derive(Clone)]
+pub struct Theme {
+    pub header: Style, // Table header rows
+    pub row_even: Style,
+    pub row_odd: Style,
+    pub selected: Style, // Selected row of the process list
+    pub highlight: Style, // Cursor row in the menus
+    pub search_match: Style, // Process rows matching the highlight search
+    pub new_process: Style, // Process rows that appeared in the last few refreshes
+    pub name: Style,
+    pub user: Style,
+    pub nice: Style,
+    pub cpu_low: Style,
+    pub cpu_mid: Style,
+    pub cpu_high: Style,
+    pub status_running: Style,
+    pub status_sleeping: Style,
+    pub status_stopped: Style,
+    pub status_zombie: Style,
+    pub status_other: Style,
+    pub message_ok: Style,
+    pub message_error: Style,
+    pub heading: Style, // Section titles in the statistics tabs
+    pub label: Style, // "Name: " labels in the statistics tabs
+    pub value: Style,
+    pub dim: Style, // Placeholders such as "measuring..."
+    pub accent: Style, // Current tab, frequencies, rates and histogram bars
+    pub accent_alt: Style,
+    pub key_hint: Style, // Tab shortcuts and key hints in the statistics header
+    pub series: [Style; 4], // Chart lines, picked by index in the order the graphs use them
+    pub usage_warn: f32, // Usage percentages above this get the warning style (`[thresholds]`)...
+    pub usage_crit: f32, // ...and above this the critical one
+    pub colored: bool, // False strips any remaining color from each frame (charts, graphs)
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+impl Theme {
+    pub fn named(name: &str) -> Option<Theme> {
+        match name {
+            "dark" => Some(Self::dark()),
+            "light" => Some(Self::light()),
+            "mono" => Some(Self::mono()),
+            _ => None,
+        }
+    }
+
+    fn dark() -> Self {
+        Self {
+            header: Style::default().fg(Color::White).bg(Color::Blue).add_modifier(Modifier::BOLD),
+            row_even: Style::default().fg(Color::Cyan),
+            row_odd: Style::default().fg(Color::Blue),
+            selected: Style::default().add_modifier(Modifier::REVERSED),
+            highlight: Style::default().fg(Color::Black).bg(Color::Yellow).add_modifier(Modifier::BOLD),
+            search_match: Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD),
+            new_process: Style::default().fg(Color::LightGreen).add_modifier(Modifier::BOLD),
+            name: Style::default().fg(Color::Green),
+            user: Style::default().fg(Color::Magenta),
+            nice: Style::default().fg(Color::Yellow),
+            cpu_low: Style::default().fg(Color::Green),
+            cpu_mid: Style::default().fg(Color::Yellow),
+            cpu_high: Style::default().fg(Color::Red),
+            status_running: Style::default().fg(Color::Green),
+            status_sleeping: Style::default().fg(Color::Blue),
+            status_stopped: Style::default().fg(Color::Yellow),
+            status_zombie: Style::default().fg(Color::Red),
+            status_other: Style::default().fg(Color::White),
+            message_ok: Style::default().fg(Color::Green),
+            message_error: Style::default().fg(Color::Red),
+            heading: Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
+            label: Style::default().fg(Color::Gray),
+            value: Style::default().fg(Color::White),
+            dim: Style::default().fg(Color::DarkGray),
+            accent: Style::default().fg(Color::Cyan),
+            accent_alt: Style::default().fg(Color::Magenta),
+            key_hint: Style::default().fg(Color::Yellow),
+            series: [Color::Cyan, Color::Green, Color::Yellow, Color::Red].map(|c| Style::default().fg(c)),
+            usage_warn: 50.0,
+            usage_crit: 90.0,
+            colored: true,
+        }
+    }
+
+    // Yellow, cyan and white are hard to read on a light background
+    fn light() -> Self {
+        Self {
+            header: Style::default().fg(Color::White).bg(Color::Blue).add_modifier(Modifier::BOLD),
+            row_even: Style::default().fg(Color::Black),
+            row_odd: Style::default().fg(Color::Blue),
+            selected: Style::default().add_modifier(Modifier::REVERSED),
+            highlight: Style::default().fg(Color::White).bg(Color::DarkGray).add_modifier(Modifier::BOLD),
+            search_match: Style::default().bg(Color::LightYellow).add_modifier(Modifier::BOLD),
+            new_process: Style::default().fg(Color::Green).add_modifier(Modifier::BOLD),
+            name: Style::default().fg(Color::Green),
+            user: Style::default().fg(Color::Magenta),
+            nice: Style::default().fg(Color::Blue),
+            cpu_low: Style::default().fg(Color::Green),
+            cpu_mid: Style::default().fg(Color::Magenta),
+            cpu_high: Style::default().fg(Color::Red),
+            status_running: Style::default().fg(Color::Green),
+            status_sleeping: Style::default().fg(Color::Blue),
+            status_stopped: Style::default().fg(Color::Magenta),
+            status_zombie: Style::default().fg(Color::Red),
+            status_other: Style::default().fg(Color::Black),
+            message_ok: Style::default().fg(Color::Green),
+            message_error: Style::default().fg(Color::Red),
+            heading: Style::default().fg(Color::Black).add_modifier(Modifier::BOLD),
+            label: Style::default().fg(Color::DarkGray),
+            value: Style::default().fg(Color::Black),
+            dim: Style::default().fg(Color::Gray),
+            accent: Style::default().fg(Color::Blue),
+            accent_alt: Style::default().fg(Color::Magenta),
+            key_hint: Style::default().fg(Color::Magenta),
+            series: [Color::Blue, Color::Green, Color::Magenta, Color::Red].map(|c| Style::default().fg(c)),
+            usage_warn: 50.0,
+            usage_crit: 90.0,
+            colored: true,
+        }
+    }
+
+    fn mono() -> Self {
+        let plain = Style::default();
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+        Self {
+            header: Style::default().add_modifier(Modifier::BOLD | Modifier::REVERSED),
+            row_even: plain,
+            row_odd: plain,
+            selected: Style::default().add_modifier(Modifier::REVERSED),
+            highlight: Style::default().add_modifier(Modifier::BOLD | Modifier::REVERSED),
+            search_match: Style::default().add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+            new_process: Style::default().add_modifier(Modifier::BOLD | Modifier::ITALIC),
+            name: plain,
+            user: plain,
+            nice: plain,
+            cpu_low: plain,
+            cpu_mid: plain,
+            cpu_high: bold,
+            status_running: plain,
+            status_sleeping: plain,
+            status_stopped: bold,
+            status_zombie: bold,
+            status_other: plain,
+            message_ok: plain,
+            message_error: bold,
+            heading: bold,
+            label: plain,
+            value: plain,
+            dim: plain,
+            accent: plain,
+            accent_alt: plain,
+            key_hint: plain,
+            series: [plain; 4],
+            usage_warn: 50.0,
+            usage_crit: 90.0,
+            colored: false,
+        }
+    }
+
+    /// The same palette coloring usage by the given warning and critical thresholds.
+    pub fn with_thresholds(self, usage_warn: f32, usage_crit: f32) -> Self {
+        Self { usage_warn, usage_crit, ..self }
+    }
+
+    /// Style for alternating table rows.
+    pub fn row(&self, index: usize) -> Style {
+        if index.is_multiple_of(2) { self.row_even } else { self.row_odd }
+    }
+
+    pub fn cpu(&self, usage: f32) -> Style {
+        self.usage(usage as f64)
+    }
+
+    /// Style for any usage percentage (memory, disk, GPU), by the same thresholds as CPU.
+    pub fn usage(&self, percent: f64) -> Style {
+        match usage_level(percent, self.usage_warn, self.usage_crit) {
+            UsageLevel::Critical => self.cpu_high,
+            UsageLevel::Warning => self.cpu_mid,
+            UsageLevel::Normal => self.cpu_low,
+        }
+    }
+
+    pub fn status(&self, state: ProcessState) -> Style {
+        match state {
+            ProcessState::Running => self.status_running,
+            ProcessState::Sleeping | ProcessState::Idle => self.status_sleeping,
+            ProcessState::Stopped => self.status_stopped,
+            ProcessState::Zombie => self.status_zombie,
+            ProcessState::DiskSleep | ProcessState::Other => self.status_other,
+        }
+    }
+
+    pub fn message(&self, is_error: bool) -> Style {
+        if is_error { self.message_error } else { self.message_ok }
+    }
+
+    /// For themes without color, clear every foreground and background color left in a drawn
+    /// frame by views that don't use the palette, keeping bold/reverse modifiers.
+    pub fn finish_frame(&self, buffer: &mut Buffer) {
+        if self.colored {
+            return;
+        }
+        for cell in buffer.content.iter_mut() {
+            cell.fg = Color::Reset;
+            cell.bg = Color::Reset;
+        }
+    }
+}
@@ -1,13 +1,20 @@
 use crate::process;
 use crate::scripting_rules::RuleEngine;
 use crate::graph;
+use crate::config::{self, Config};
+use crate::keymap::{self, Action, KeyMap};
+use crate::columns::{Column, ColumnConfig};
+use crate::theme::Theme;
+use crate::util::{ellipsize, format_bytes, format_megabytes, MemoryUnit};
+use crate::procfs_parse::SmapsSummary;
+use crate::snapshot::{self, Snapshot};
+use crate::alerts::{AlertEngine, AlertRule};
 use std::io::stdout;
-use std::thread::sleep;
 use std::time::Duration;
-use process::ProcessManager;
+use process::{GroupBy, GroupSort, ProcessManager, ProcessState};
 use std::error::Error;
 use crossterm::{
-    event::{self, Event, KeyCode, KeyEvent},
+    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKind},
     terminal::{ disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
     execute,
 };
@@ -16,7 +23,7 @@ use ratatui::{
     prelude::*,
     widgets::{
         Block, Borders, List, ListItem, Paragraph, Table, Row, Cell,
-        Dataset, GraphType, Chart, BorderType,
+        BorderType,
     },
     layout::{Layout, Constraint, Direction, Alignment},
     style::{Style, Modifier, Color},
@@ -24,13 +31,13 @@ use ratatui::{
     Frame,
 };
 
-use crate::process_log::{ProcessExitLogEntry, render_process_log_tab};
+use crate::per_process_graph::{self, GraphView, GRAPH_WINDOWS_SECS, MAX_GRAPH_SERIES, render_per_process_graph_tab};
+use crate::process_log::{ProcessExitLogEntry, LogSortMode, render_process_log_tab, sort_exit_log};
 use chrono::{Local};
-use chrono::TimeZone;
-use std::collections::{HashSet, VecDeque};
+use std::collections::{HashMap, HashSet, VecDeque};
 
 // ViewMode enum to track current view
-#[derive(PartialEq)]
+#[derive(PartialEq, Clone, Copy)]
 enum ViewMode {
     ProcessList,
     Statistics,  // Renamed from GraphView
@@ -44,51 +51,56 @@ enum ViewMode {
     ProcessLog,      // Added for new feature
     Help,            // Added for new feature
     RuleInput,
+    AlertInput, // Adding an alert rule from the Filter/Sort menu
+    Columns,
+    Environment,
+    Sockets, // TCP/UDP sockets of one process
+    Aggregated, // Live processes grouped by name or user
+    SnapshotDiff, // Differences between the two latest snapshots
 }
 
 // Input state for various operations
+#[derive(Default)]
 struct InputState {
     pid_input: String,
     nice_input: String,
     filter_input: String,
     rule_input: String,
+    alert_input: String,
+    name_input: String, // Process name for the by-name operations in the kill and nice menus
+    port_input: String, // Port whose listening process the kill menu looks up
+    jump_input: String, // PID typed after ':' in the process list
+    oom_input: String, // oom_score_adj typed in the Change Nice menu
     message: Option<(String, bool)>, // (message, is_error)
     message_timeout: Option<std::time::Instant>,
 }
 
-impl Default for InputState {
-    fn default() -> Self {
-        Self {
-            pid_input: String::new(),
-            nice_input: String::new(),
-            filter_input: String::new(),
-            rule_input: String::new(),
-            message: None,
-            message_timeout: None,
-        }
-    }
-}
 
 // NiceInputState enum to track the state of nice value input
 #[derive(PartialEq)]
 enum NiceInputState {
     SelectingPid,
+    EnteringName, // Typing a process name to renice every match
     EnteringNice,
+    EnteringOomAdj, // Typing a new oom_score_adj for the selected process
 }
 // KillStopInputState enum to track the state of kill/stop/continue input
 #[derive(PartialEq)]
 enum KillStopInputState {
     SelectingPid,
+    EnteringName, // Typing a process name to signal every match
+    EnteringPort, // Typing a port to select the process listening on it
     EnteringAction,
+    ConfirmingAction, // Waiting for 'y' when confirm_before_kill is set
 }
 
 // StatisticsTab enum to track the current statistics tab
-#[derive(PartialEq)]
+#[derive(PartialEq, Clone, Copy)]
 #[allow(dead_code)]
 pub enum StatisticsTab {
     Graphs,
     Overview,
-    CPU,
+    Cpu,
     Memory,
     PerProcessGraph, // New tab for per-process graphing
     ProcessLog,      // New tab for process logging
@@ -96,6 +108,8 @@ pub enum StatisticsTab {
     Processes,
     Advanced,
     Help,            // New tab for help
+    Gpu,
+    Battery,
 }
 
 // LogGroupMode enum to track process log grouping
@@ -103,10 +117,56 @@ pub enum StatisticsTab {
 enum LogGroupMode {
     None,
     Name,
-    PPID,
+    Ppid,
     User,
 }
 
+// Environment of one process, read once when the viewer opens
+struct EnvironView {
+    pid: u32,
+    name: String,
+    vars: Result<Vec<(String, String)>, String>, // Error text if /proc/<pid>/environ couldn't be read
+    scroll: usize,
+    reveal_secrets: bool, // Show values of TOKEN/KEY/PASSWORD-like variables instead of masking them
+}
+
+// Sockets of one process, read when the viewer opens and on 'r'
+struct SocketsView {
+    pid: u32,
+    name: String,
+    sockets: Result<Vec<crate::sockets::Socket>, String>, // Error text if /proc/<pid>/fd couldn't be listed
+    scroll: usize,
+}
+
+impl SocketsView {
+    fn load(proc_root: &std::path::Path, pid: u32, name: String) -> Self {
+        let sockets = crate::sockets::process_sockets(proc_root, pid).map_err(|e| match e.kind() {
+            std::io::ErrorKind::PermissionDenied => {
+                "Permission denied (only root or the process owner can list its open files)".to_string()
+            }
+            std::io::ErrorKind::NotFound => "The process has exited".to_string(),
+            _ => e.to_string(),
+        });
+        Self { pid, name, sockets, scroll: 0 }
+    }
+}
+
+// What a confirmed kill menu action applies to
+enum KillTarget {
+    Pid(u32),
+    Name(String),
+    ZombieParent(u32), // Parent of a zombie the user tried to kill, sent SIGCHLD instead
+}
+
+// A process sent SIGTERM by terminate-then-kill or restart, identified by PID and start time so
+// a reused PID is never killed by mistake
+struct PendingTermination {
+    pid: u32,
+    start_time: u64,
+    deadline: std::time::Instant,
+    restart: Option<process::RestartCommand>, // Relaunched once it exits; never force-killed
+}
+
 // App state
 struct App {
     process_manager: ProcessManager,
@@ -117,127 +177,495 @@ struct App {
     input_state: InputState,
     sort_ascending: bool,
     sort_mode: Option<String>,
+    default_sort: (Option<String>, bool), // Sort mode and direction from the config, restored by reset_view
     filter_mode: Option<String>,
     stats_scroll_offset: usize,  // New field for statistics scrolling
     nice_input_state: NiceInputState,  // Track which input we're currently handling
     current_stats_tab: StatisticsTab,  // New field for tracking current statistics tab
+    maximized_graph: Option<graph::GraphKind>, // Chart filling the Graphs tab, toggled with c/m
     change_nice_scroll_offset: usize,
     selected_process_index: usize,
     per_process_graph_scroll_offset: usize,  // Add this
     selected_process_for_graph: Option<u32>,  // Add this
+    graph_compare_pids: Vec<u32>, // Processes overlaid on the per-process graph alongside the selected one
+    graph_window_index: usize, // Index into GRAPH_WINDOWS_SECS for the per-process graph
+    graph_virtual_memory: bool, // Chart virtual size instead of resident memory in the per-process graph
+    graph_overlay: bool, // Draw CPU and memory on one dual-axis chart instead of two stacked charts
+    graph_smaps: Option<Result<SmapsSummary, String>>, // Memory map panel of the graphed process; None while hidden
+    graph_filter_input: String, // Name/PID search in the per-process graph selection list
+    graph_filter_active: bool, // True while typing into that search
     kill_stop_input_state: KillStopInputState,
     process_exit_log: VecDeque<ProcessExitLogEntry>, // Add this
+    exit_log_capacity: usize, // Max exits kept in the log (treated as at least 1)
+    action_log: VecDeque<(chrono::DateTime<Local>, String, bool)>, // Recent actions and their outcome (text, is_error), newest last
     prev_pids: HashSet<u32>, // For tracking exited processes
+    first_seen: HashMap<u32, std::time::Instant>, // When each process that started while we were running was first listed
+    previous_refresh: Option<i64>, // Unix time of the previous refresh, to tell which processes started since
     log_filter_input: String, // For process log search/filter
     log_filter_active: bool,  // True if in filter input mode
     log_scroll_offset: usize, // For scrolling the process log
     log_group_mode: LogGroupMode, // For grouping process log
+    log_sort_mode: LogSortMode, // Column the process log is sorted by
+    log_sort_ascending: bool,
+    help_scroll_offset: usize, // Scroll position in the Help view
+    quick_filter: String, // Type-to-filter query for the process list (name/cmdline)
+    quick_filter_active: bool, // True while typing into the quick filter
+    highlight_query: String, // Search that highlights matching rows (name/cmdline) without hiding the rest
+    highlight_active: bool, // True while typing the highlight search
+    jump_active: bool, // True while typing a PID to jump to
+    jump_clear_offered: bool, // The typed PID is hidden by filters; Enter again clears them
+    refresh_interval: Duration, // Delay between refreshes of the main loop
+    confirm_before_kill: bool, // Require 'y' before kill/terminate in the kill menu
+    kill_grace_period: Duration, // Wait between SIGTERM and SIGKILL for terminate-then-kill
+    pending_terminations: Vec<PendingTermination>, // Processes sent SIGTERM that get SIGKILL at their deadline
+    pending_kill_action: Option<(Action, KillTarget)>, // Action and target awaiting confirmation
+    bulk_name: Option<String>, // Set while a kill/nice menu action applies to every process with this name
+    nice_history: Vec<(u32, i32)>, // (PID, nice before we changed it), newest last, for undo in the nice menu
+    columns: ColumnConfig, // Order and visibility of the process list columns
+    column_menu_index: usize, // Cursor in the column editor
+    columns_on_open: Vec<String>, // Visible column keys when the editor opened, to save only real changes
+    process_table_area: Rect, // Where the process table was last drawn, for mouse hit-testing
+    process_list_len: usize, // Rows in the process list after filtering, as last drawn
+    compact: bool, // Process list without borders or the key menu, so the table gets those rows
+    pinned_pid: Option<u32>, // Process the list selection follows across refreshes
+    sudo_offered: bool, // A privileged action was refused; restart_with_sudo works while the message shows
+    restart_with_sudo: bool, // Set by that key so the main loop restores the terminal and re-execs
+    watched_pid: Option<u32>, // Process to announce when it exits ('w' or --wait-for)
+    watch_exit: Option<String>, // Summary of the watched process once it exited, shown until dismissed
+    show_kernel_threads: bool, // Include kernel threads (kthreadd and its children) in the process list
+    running_only: bool, // Hide everything not in the R (running/runnable) state
+    environ_view: Option<EnvironView>, // Process shown by the environment viewer
+    sockets_view: Option<SocketsView>, // Process shown by the sockets viewer
+    aggregated_scroll: usize, // Scroll position in the grouped view
+    group_by: GroupBy,
+    group_sort: GroupSort,
+    snapshots: VecDeque<Snapshot>, // Latest captures of the process list, oldest first
+    snapshots_taken: usize, // Numbers the snapshots
+    snapshot_diff_scroll: usize,
+    keymap: KeyMap,
+    theme: Theme,
+    memory_unit: MemoryUnit, // Unit for memory sizes, from the `memory_unit` option
+    cpu_of_total: bool, // CPU% column as a share of the whole machine instead of one core
     pub rule_engine: RuleEngine, //for scripting
+    alerts: AlertEngine,
+    rule_preview: Result<usize, String>, // Matches of the rule being typed, or why it doesn't parse
+    redraw: bool, // Repaint everything on the next frame, after an external program used the screen
 }
 
 impl App {
-    fn new() -> Self {
+    fn new(args: &crate::cli::Args) -> Self {
+        let (mut config, warnings) = config::load();
+        if let Some(points) = args.history_points {
+            config.graph_history_points = points.max(2);
+        }
+        if let Some(points) = args.process_history_points {
+            config.process_history_points = points.max(2);
+        }
+        let mut app = Self::with_config(&config, args.proc_root());
+        app.watched_pid = args.wait_for;
+        if !warnings.is_empty() {
+            app.input_state.message = Some((format!("Config: {}", warnings.join("; ")), true));
+            app.input_state.message_timeout = Some(std::time::Instant::now() + Duration::from_secs(5));
+        }
+        app
+    }
+
+    fn with_config(config: &Config, proc_root: &std::path::Path) -> Self {
+        let mut process_manager = ProcessManager::new(proc_root);
+        process_manager.set_cpu_source(config.cpu_source);
+        process_manager.set_show_raw_errors(config.show_raw_errors);
+        if let Some(mode) = &config.sort_mode {
+            process_manager.set_sort(mode, config.sort_ascending);
+        }
         Self {
-            process_manager: ProcessManager::new(),
-            graph_data: graph::GraphData::new(60, 500),
+            process_manager,
+            graph_data: graph::GraphData::new(
+                proc_root,
+                config.graph_history_points,
+                config.process_history_points,
+                config.refresh_interval_ms.max(graph::MIN_SAMPLE_INTERVAL_MS),
+            ),
             rule_engine: RuleEngine::new(),
+            alerts: AlertEngine::new(config.alerts.clone(), config.alert_bell),
+            rule_preview: Ok(0),
+            redraw: false,
             view_mode: ViewMode::ProcessList,
             scroll_offset: 0,
             display_limit: 20,
             input_state: InputState::default(),
-            sort_ascending: true,
-            sort_mode: None,
+            sort_ascending: config.sort_ascending,
+            sort_mode: config.sort_mode.clone(),
+            default_sort: (config.sort_mode.clone(), config.sort_ascending),
             filter_mode: None,
             stats_scroll_offset: 0,  // Initialize stats scroll offset
             nice_input_state: NiceInputState::SelectingPid,
             current_stats_tab: StatisticsTab::Graphs,  // Default to Graphs tab
+            maximized_graph: None,
             change_nice_scroll_offset: 0,
             selected_process_index: 0,
             per_process_graph_scroll_offset: 0,  // Add this
             selected_process_for_graph: None,    // Add this
+            graph_compare_pids: Vec::new(),
+            graph_window_index: 1, // 30s, the whole default history
+            graph_virtual_memory: false,
+            graph_overlay: false,
+            graph_smaps: None,
+            graph_filter_input: String::new(),
+            graph_filter_active: false,
             kill_stop_input_state: KillStopInputState::SelectingPid,
-            process_exit_log: VecDeque::with_capacity(100), // Keep last 100 exits
+            process_exit_log: VecDeque::with_capacity(config.exit_log_capacity.max(1)),
+            exit_log_capacity: config.exit_log_capacity,
+            action_log: VecDeque::with_capacity(ACTION_LOG_LIMIT),
             prev_pids: HashSet::new(),
+            first_seen: HashMap::new(),
+            previous_refresh: None,
             log_filter_input: String::new(),
             log_filter_active: false,
             log_scroll_offset: 0,
             log_group_mode: LogGroupMode::None,
+            log_sort_mode: LogSortMode::ExitTime, // Newest exits first by default
+            log_sort_ascending: false,
+            help_scroll_offset: 0,
+            quick_filter: String::new(),
+            quick_filter_active: false,
+            highlight_query: String::new(),
+            highlight_active: false,
+            jump_active: false,
+            jump_clear_offered: false,
+            refresh_interval: Duration::from_millis(config.refresh_interval_ms),
+            confirm_before_kill: config.confirm_before_kill,
+            kill_grace_period: Duration::from_millis(config.kill_grace_period_ms),
+            pending_terminations: Vec::new(),
+            pending_kill_action: None,
+            bulk_name: None,
+            nice_history: Vec::new(),
+            columns: config
+                .columns
+                .as_deref()
+                .map(ColumnConfig::from_keys)
+                .unwrap_or_default(),
+            column_menu_index: 0,
+            columns_on_open: Vec::new(),
+            process_table_area: Rect::default(),
+            compact: false,
+            process_list_len: 0,
+            pinned_pid: None,
+            sudo_offered: false,
+            restart_with_sudo: false,
+            watched_pid: None,
+            watch_exit: None,
+            show_kernel_threads: false,
+            running_only: false,
+            environ_view: None,
+            sockets_view: None,
+            aggregated_scroll: 0,
+            group_by: GroupBy::Name,
+            group_sort: GroupSort::Cpu,
+            snapshots: VecDeque::new(),
+            snapshots_taken: 0,
+            snapshot_diff_scroll: 0,
+            keymap: config.keymap.clone(),
+            theme: config.theme.clone().with_thresholds(config.usage_warn, config.usage_crit),
+            memory_unit: config.memory_unit,
+            cpu_of_total: false,
         }
     }
 
     fn refresh(&mut self) {
-        let prev_map: std::collections::HashMap<u32, process::ProcessInfo> = self.process_manager.get_processes().iter().map(|p| (p.pid, p.clone())).collect();
-        let prev_pids = self.prev_pids.clone();
+        // Expire transient messages
+        if let Some(timeout) = self.input_state.message_timeout
+            && std::time::Instant::now() >= timeout
+        {
+            self.input_state.message = None;
+            self.input_state.message_timeout = None;
+            self.sudo_offered = false;
+        }
+        // Swap usage costs a file read per process, so only read it while it's shown
+        self.process_manager.set_read_swap(self.columns.visible().any(|column| column == Column::Swap));
         self.process_manager.refresh();
-        self.graph_data.update(&self.process_manager);
-        let current: Vec<_> = self.process_manager.get_processes().iter().map(|p| p.pid).collect();
-        let current_set: HashSet<u32> = current.iter().copied().collect();
-        // Find exited PIDs
-        for pid in prev_pids.difference(&current_set) {
-            if let Some(proc) = prev_map.get(pid) {
+        // Rules are evaluated once per refresh; every view then reads the same filtered list
+        self.process_manager.apply_rules(&self.rule_engine);
+        if self.view_mode == ViewMode::RuleInput {
+            update_rule_preview(self);
+        }
+        if self.view_mode == ViewMode::PerProcessGraph && self.graph_smaps.is_some() {
+            load_graph_smaps(self);
+        }
+        let current_set: HashSet<u32> = self.process_manager.get_processes().iter().map(|p| p.pid).collect();
+        // Find exited PIDs, then look up their last-known info in the pre-refresh list
+        let exited: HashSet<u32> = self.prev_pids.difference(&current_set).copied().collect();
+        let mut entries = Vec::new();
+        if !exited.is_empty() {
+            for proc in self.process_manager.get_previous_processes().iter().filter(|p| exited.contains(&p.pid)) {
                 let exit_time = Local::now();
-                // Try to parse start_time_str as chrono::NaiveDateTime
-                let uptime_secs = chrono::NaiveDateTime::parse_from_str(&proc.start_time_str, "%Y-%m-%d %H:%M:%S")
-                    .ok()
-                    .and_then(|start| {
-                        let start = Local.from_local_datetime(&start).single()?;
-                        Some((exit_time - start).num_seconds().max(0) as u64)
-                    })
-                    .unwrap_or(0);
-                let entry = ProcessExitLogEntry {
+                let uptime_secs = crate::util::seconds_since(proc.start_time);
+                // Peaks come from the history, which still holds the exited PID until the graph update below.
+                // Fall back to the last-known values if it never got a sample.
+                let (peak_cpu, peak_mem) = match self.graph_data.get_process_history(proc.pid) {
+                    Some((cpu_history, mem_history)) => (
+                        cpu_history.iter().copied().fold(proc.cpu_usage, f32::max),
+                        mem_history.iter().copied().max().unwrap_or(0).max(proc.memory_usage),
+                    ),
+                    None => (proc.cpu_usage, proc.memory_usage),
+                };
+                entries.push(ProcessExitLogEntry {
                     pid: proc.pid,
                     name: proc.name.clone(),
-                    user: proc.user.clone(),
+                    user: Some(proc.user_label()).filter(|user| !user.is_empty()),
                     start_time: proc.start_time_str.clone(),
                     exit_time,
                     uptime_secs,
-                };
-                if self.process_exit_log.len() >= 100 {
-                    self.process_exit_log.pop_front();
-                }
-                self.process_exit_log.push_back(entry);
+                    peak_cpu,
+                    peak_mem,
+                });
+            }
+        }
+        for entry in entries {
+            self.push_exit_log_entry(entry);
+        }
+        // Processes are new by their start time, not by appearing in the list, so the ones a
+        // filter change reveals aren't marked. Nothing is new on the first refresh.
+        let refreshed_at = Local::now().timestamp();
+        if let Some(previous) = self.previous_refresh {
+            let now = std::time::Instant::now();
+            for process in self.process_manager.get_processes().iter().filter(|p| p.start_time as i64 >= previous) {
+                self.first_seen.entry(process.pid).or_insert(now);
             }
         }
+        self.previous_refresh = Some(refreshed_at);
+        let process_manager = &self.process_manager;
+        self.first_seen.retain(|pid, _| process_manager.process_exists(*pid));
+        // Like the watched process below, a pin only goes once the process is really gone
+        if let Some(pid) = self.pinned_pid
+            && !current_set.contains(&pid)
+            && process::has_exited(self.process_manager.proc_root(), pid)
+        {
+            self.pinned_pid = None;
+            self.input_state.message = Some((format!("Pinned process {} exited", pid), true));
+            self.input_state.message_timeout = Some(std::time::Instant::now() + Duration::from_secs(3));
+        }
+        // A watched process only counts as exited once it's really gone, not just filtered out
+        if let Some(pid) = self.watched_pid
+            && !current_set.contains(&pid)
+            && process::has_exited(self.process_manager.proc_root(), pid)
+        {
+            self.watched_pid = None;
+            let entry = self.process_exit_log.iter().rev().find(|entry| entry.pid == pid);
+            self.watch_exit = Some(watch_summary(pid, entry, self.memory_unit));
+        }
+        let process_manager = &self.process_manager;
+        self.graph_compare_pids.retain(|pid| process_manager.process_exists(*pid));
+        // Undo entries outlive a filter that hides their process; only exiting drops them
+        let proc_root = self.process_manager.proc_root();
+        self.nice_history.retain(|&(pid, _)| !process::has_exited(proc_root, pid));
+        self.check_pending_terminations();
         self.prev_pids = current_set;
+        self.graph_data.update(&self.process_manager);
+        self.check_alerts();
+        self.report_proc_failures();
+    }
+
+    // Tell the user once about each /proc source that couldn't be read, so zeros aren't mistaken for data
+    fn report_proc_failures(&mut self) {
+        let failures = crate::diagnostics::take_unreported();
+        if failures.is_empty() {
+            return;
+        }
+        self.input_state.message = Some((failures.join("; "), true));
+        self.input_state.message_timeout = Some(std::time::Instant::now() + Duration::from_secs(10));
+    }
+
+    // Re-evaluate the alert rules and announce processes that just crossed one
+    fn check_alerts(&mut self) {
+        if self.alerts.rules.is_empty() {
+            return;
+        }
+        let started = self.alerts.update(self.graph_data.process_histories(), self.graph_data.sample_interval());
+        let Some((pid, rule)) = started.first() else { return };
+        let name = self.process_manager.get_processes().iter().find(|p| p.pid == *pid).map_or("?", |p| p.name.as_str());
+        let mut text = format!("Alert: {} ({}) crossed '{}'", name, pid, rule);
+        if started.len() > 1 {
+            text.push_str(&format!(" and {} more", started.len() - 1));
+        }
+        self.input_state.message = Some((text, true));
+        self.input_state.message_timeout = Some(std::time::Instant::now() + Duration::from_secs(5));
+        if self.alerts.bell {
+            use std::io::Write;
+            let mut out = stdout();
+            let _ = out.write_all(b"\x07");
+            let _ = out.flush();
+        }
+    }
+
+    // Finish terminate-then-kill and restart requests: report processes that exited (or became
+    // zombies) after SIGTERM, relaunching the restarted ones, and SIGKILL the others still running
+    // at their deadline. A restart that times out is given up instead.
+    fn check_pending_terminations(&mut self) {
+        let now = std::time::Instant::now();
+        let grace_period = self.kill_grace_period;
+        let mut outcomes = Vec::new();
+        self.pending_terminations.retain_mut(|pending| {
+            // Checked against the whole process table: a filter hiding the process doesn't mean it exited
+            let running = self
+                .process_manager
+                .process_identity(pending.pid)
+                .filter(|(start_time, _)| *start_time == pending.start_time);
+            let exited = match running {
+                None => true,
+                Some((_, state)) => state == ProcessState::Zombie,
+            };
+            match (exited, pending.restart.take()) {
+                (true, Some(restart)) => outcomes.push(match restart.spawn() {
+                    Ok(new_pid) => (format!("Relaunched as PID {} after terminating process {}", new_pid, pending.pid), false),
+                    Err(e) => (format!("Could not restart ({}) process {}", e, pending.pid), true),
+                }),
+                (true, None) => outcomes.push((format!("Process {} terminated gracefully", pending.pid), false)),
+                (false, Some(_)) if now >= pending.deadline => outcomes.push((
+                    format!(
+                        "Process {} still running {:.1}s after SIGTERM, not relaunched",
+                        pending.pid,
+                        grace_period.as_secs_f64()
+                    ),
+                    true,
+                )),
+                (false, Some(restart)) => {
+                    pending.restart = Some(restart);
+                    return true;
+                }
+                (false, None) if now >= pending.deadline => {
+                    outcomes.push(match self.process_manager.kill_process(pending.pid) {
+                        Ok(()) => (format!("Process {} force-killed after timeout", pending.pid), false),
+                        Err(e) => (format!("Error force-killing process {}: {}", pending.pid, e), true),
+                    })
+                }
+                (false, None) => return true,
+            }
+            false
+        });
+        for (msg, is_error) in &outcomes {
+            self.log_action(msg, *is_error);
+        }
+        if let Some((msg, is_error)) = outcomes.pop() {
+            self.input_state.message = Some((msg, is_error));
+            self.input_state.message_timeout = Some(now + Duration::from_secs(3));
+        }
+    }
+
+    // Record an action (signal, renice, filter...) with its outcome in the action log shown in Help
+    fn log_action(&mut self, text: &str, is_error: bool) {
+        if self.action_log.len() >= ACTION_LOG_LIMIT {
+            self.action_log.pop_front();
+        }
+        self.action_log.push_back((Local::now(), text.to_string(), is_error));
+    }
+
+    // Log the outcome an action just put on the message line
+    fn log_message(&mut self) {
+        if let Some((text, is_error)) = self.input_state.message.clone() {
+            self.log_action(&text, is_error);
+        }
+    }
+
+    // Append to the exit log, dropping the oldest entries beyond the configured capacity
+    fn push_exit_log_entry(&mut self, entry: ProcessExitLogEntry) {
+        let capacity = self.exit_log_capacity.max(1);
+        while self.process_exit_log.len() >= capacity {
+            self.process_exit_log.pop_front();
+        }
+        self.process_exit_log.push_back(entry);
     }
 }
 
 
 //ui_renderer
-pub fn ui_renderer() -> Result<(), Box<dyn Error>> {
+pub fn ui_renderer(args: &crate::cli::Args) -> Result<(), Box<dyn Error>> {
+    if let Some(pid) = args.wait_for
+        && process::has_exited(args.proc_root(), pid)
+    {
+        return Err(format!("no running process with PID {}", pid).into());
+    }
+
+    // The default hook prints the panic message; restore the terminal first so the message lands
+    // on the normal screen instead of being wiped along with the alternate one
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        restore_terminal();
+        default_hook(info);
+    }));
+
     // Terminal initialization
     enable_raw_mode()?;
+    let guard = TerminalGuard;
     let mut stdout = stdout();
-    execute!(stdout, EnterAlternateScreen)?;
+    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    let mut app = App::new();
+    let mut app = App::new(args);
 
+    // Refresh on a fixed schedule, and redraw as soon as a key or mouse event comes in rather
+    // than after the next refresh
+    let mut next_refresh = std::time::Instant::now();
     loop {
-        app.refresh();
+        if std::time::Instant::now() >= next_refresh {
+            app.refresh();
+            next_refresh = std::time::Instant::now() + app.refresh_interval;
+        }
 
+        if app.redraw {
+            terminal.clear()?;
+            app.redraw = false;
+        }
         terminal.draw(|f| {
             match app.view_mode {
+                _ if too_small(f.size(), app.view_mode) => draw_too_small(f, app.view_mode),
                 ViewMode::ProcessList => draw_process_list(f, &mut app),
-                ViewMode::Statistics => graph::render_graph_dashboard(
-                    f,
-                    &app.graph_data,
-                    &app.current_stats_tab,
-                    app.process_manager.get_processes(),
-                ),
+                ViewMode::Statistics => {
+                    let view = graph::DashboardView {
+                        graph_data: &app.graph_data,
+                        theme: &app.theme,
+                        memory_unit: app.memory_unit,
+                        current_tab: &app.current_stats_tab,
+                        process_list: app.process_manager.get_processes(),
+                        scroll: app.stats_scroll_offset,
+                        maximized: app.maximized_graph,
+                    };
+                    app.stats_scroll_offset = graph::render_graph_dashboard(f, &view);
+                    draw_message_overlay(f, &app);
+                }
                 ViewMode::FilterSort => draw_filter_sort_menu(f),
                 ViewMode::Sort => draw_sort_menu(f, &app),
                 ViewMode::Filter => draw_filter_menu(f),
                 ViewMode::FilterInput => draw_filter_input_menu(f, &app),
                 ViewMode::KillStop => draw_kill_stop_menu(f, &mut app),
                 ViewMode::ChangeNice => draw_change_nice_menu(f, &mut app),
-                ViewMode::PerProcessGraph => render_per_process_graph_tab(f, f.size(), &app),
+                ViewMode::PerProcessGraph => {
+                    let view = GraphView {
+                        process_manager: &app.process_manager,
+                        graph_data: &app.graph_data,
+                        theme: &app.theme,
+                        keymap: &app.keymap,
+                        memory_unit: app.memory_unit,
+                        message: app.input_state.message.as_ref(),
+                        selected_pid: app.selected_process_for_graph,
+                        selected_index: app.selected_process_index,
+                        scroll_offset: app.per_process_graph_scroll_offset,
+                        compare_pids: &app.graph_compare_pids,
+                        window_secs: GRAPH_WINDOWS_SECS[app.graph_window_index],
+                        overlay: app.graph_overlay,
+                        smaps: app.graph_smaps.as_ref(),
+                        virtual_memory: app.graph_virtual_memory,
+                        filter_input: &app.graph_filter_input,
+                        filter_active: app.graph_filter_active,
+                    };
+                    render_per_process_graph_tab(f, f.size(), &view);
+                }
                 ViewMode::RuleInput => draw_rule_input(f, &app), //for scripting                
+                ViewMode::AlertInput => draw_alert_input(f, &app),
                 ViewMode::ProcessLog => {
                     let size = f.size();
                     // Filter log if needed
-                    let log: Vec<_> = if app.log_filter_input.is_empty() {
+                    let mut log: Vec<_> = if app.log_filter_input.is_empty() {
                         app.process_exit_log.make_contiguous().to_vec()
                     } else {
                         let query = app.log_filter_input.to_lowercase();
@@ -251,19 +679,42 @@ pub fn ui_renderer() -> Result<(), Box<dyn Error>> {
                             .cloned()
                             .collect()
                     };
+                    sort_exit_log(&mut log, app.log_sort_mode, app.log_sort_ascending);
                     // Draw filter input at top (make it 3 lines tall)
+                    let key = |action| app.keymap.label(action);
+                    let (group, ungroup) = (key(Action::GroupLog), key(Action::UngroupLog));
                     let group_status = match app.log_group_mode {
-                        LogGroupMode::None => "Ungrouped (press 'g' to group)",
-                        LogGroupMode::Name => "Grouped by Name (press 'g' to group by PPID, 'u' to ungroup)",
-                        LogGroupMode::PPID => "Grouped by PPID (press 'g' to group by User, 'u' to ungroup)",
-                        LogGroupMode::User => "Grouped by User (press 'g' to ungroup, 'u' to ungroup)",
+                        LogGroupMode::None => format!("Ungrouped (press '{}' to group)", group),
+                        LogGroupMode::Name => format!("Grouped by Name (press '{}' to group by PPID, '{}' to ungroup)", group, ungroup),
+                        LogGroupMode::Ppid => format!("Grouped by PPID (press '{}' to group by User, '{}' to ungroup)", group, ungroup),
+                        LogGroupMode::User => format!("Grouped by User (press '{}' or '{}' to ungroup)", group, ungroup),
                     };
+                    let sort_status = format!(
+                        "Sorted by {} {} ({}: next column, {}: toggle order)",
+                        app.log_sort_mode.label(),
+                        if app.log_sort_ascending { "↑" } else { "↓" },
+                        key(Action::CycleLogSort),
+                        key(Action::ToggleSortOrder),
+                    );
                     let filter_line = if app.log_filter_active {
                         format!("/{}", app.log_filter_input)
                     } else if !app.log_filter_input.is_empty() {
-                        format!("Filter: {} | {}", app.log_filter_input, group_status)
+                        format!("Filter: {} | {}\n{}", app.log_filter_input, group_status, sort_status)
                     } else {
-                        format!("{}\nPress / to search/filter, ↑/↓/PgUp/PgDn to scroll, g: group, u: ungroup, Esc/q: back", group_status)
+                        format!(
+                            "{} | {}\nPress {} to search/filter, {}/{}/{}/{} to scroll, {}: group, {}: ungroup, {}: clear log, Esc/{}: back",
+                            group_status,
+                            sort_status,
+                            key(Action::QuickFilter),
+                            key(Action::Up),
+                            key(Action::Down),
+                            key(Action::PageUp),
+                            key(Action::PageDown),
+                            group,
+                            ungroup,
+                            key(Action::ClearLog),
+                            key(Action::Quit),
+                        )
                     };
                     let chunks = Layout::default()
                         .direction(Direction::Vertical)
@@ -284,29 +735,33 @@ pub fn ui_renderer() -> Result<(), Box<dyn Error>> {
                             let offset = app.log_scroll_offset.min(max_scroll);
                             (&log[offset..(offset + log_height).min(total)], false)
                         }
-                        LogGroupMode::Name | LogGroupMode::PPID | LogGroupMode::User => {
+                        LogGroupMode::Name | LogGroupMode::Ppid | LogGroupMode::User => {
                             use std::collections::BTreeMap;
-                            let mut grouped: BTreeMap<String, Vec<&ProcessExitLogEntry>> = BTreeMap::new();
-                            for entry in &log {
+                            // Each group also keeps where its first entry sits in the sorted log
+                            let mut grouped: BTreeMap<String, (usize, Vec<&ProcessExitLogEntry>)> = BTreeMap::new();
+                            for (position, entry) in log.iter().enumerate() {
                                 let key = match app.log_group_mode {
                                     LogGroupMode::Name => entry.name.clone(),
-                                    LogGroupMode::PPID => entry.user.clone().unwrap_or_else(|| "Unknown".to_string()), // Use user for now, will fix below
+                                    LogGroupMode::Ppid => entry.user.clone().unwrap_or_else(|| "Unknown".to_string()), // Use user for now, will fix below
                                     LogGroupMode::User => entry.user.clone().unwrap_or_else(|| "Unknown".to_string()),
                                     LogGroupMode::None => unreachable!(),
                                 };
-                                grouped.entry(key).or_default().push(entry);
+                                grouped.entry(key).or_insert_with(|| (position, Vec::new())).1.push(entry);
                             }
                             // If grouping by PPID, fix key
-                            if app.log_group_mode == LogGroupMode::PPID {
+                            if app.log_group_mode == LogGroupMode::Ppid {
                                 grouped.clear();
-                                for entry in &log {
+                                for (position, entry) in log.iter().enumerate() {
                                     let key = format!("{}", entry.pid); // Actually, we want PPID, but ProcessExitLogEntry doesn't have it. For now, use PID.
-                                    grouped.entry(key).or_default().push(entry);
+                                    grouped.entry(key).or_insert_with(|| (position, Vec::new())).1.push(entry);
                                 }
                             }
-                            // Build summary rows
+                            // Build summary rows, ordered like the log: a group comes where its
+                            // first entry would under the selected sort column and direction
+                            let mut groups: Vec<_> = grouped.iter().collect();
+                            groups.sort_by_key(|(_, (position, _))| *position);
                             let mut summary: Vec<(String, usize, u64, u64, u64, String)> = Vec::new();
-                            for (key, entries) in grouped.iter() {
+                            for (key, (_, entries)) in groups {
                                 let count = entries.len();
                                 let min_uptime = entries.iter().map(|e| e.uptime_secs).min().unwrap_or(0);
                                 let max_uptime = entries.iter().map(|e| e.uptime_secs).max().unwrap_or(0);
@@ -314,8 +769,6 @@ pub fn ui_renderer() -> Result<(), Box<dyn Error>> {
                                 let most_recent = entries.iter().map(|e| e.exit_time).max().map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string()).unwrap_or_default();
                                 summary.push((key.clone(), count, min_uptime, max_uptime, avg_uptime, most_recent));
                             }
-                            // Sort by count descending
-                            summary.sort_by(|a, b| b.1.cmp(&a.1));
                             let total = summary.len();
                             let max_scroll = total.saturating_sub(log_height);
                             let offset = app.log_scroll_offset.min(max_scroll);
@@ -324,7 +777,7 @@ pub fn ui_renderer() -> Result<(), Box<dyn Error>> {
                             let header = Row::new(vec![
                                 Cell::from(match app.log_group_mode {
                                     LogGroupMode::Name => "Name",
-                                    LogGroupMode::PPID => "PPID",
+                                    LogGroupMode::Ppid => "PPID",
                                     LogGroupMode::User => "User",
                                     LogGroupMode::None => unreachable!(),
                                 }).style(Style::default().fg(Color::Yellow)),
@@ -338,9 +791,9 @@ pub fn ui_renderer() -> Result<(), Box<dyn Error>> {
                                 Row::new(vec![
                                     Cell::from(key.clone()),
                                     Cell::from(count.to_string()),
-                                    Cell::from(format!("{}s", min)),
-                                    Cell::from(format!("{}s", max)),
-                                    Cell::from(format!("{}s", avg)),
+                                    Cell::from(crate::util::format_duration(*min)),
+                                    Cell::from(crate::util::format_duration(*max)),
+                                    Cell::from(crate::util::format_duration(*avg)),
                                     Cell::from(recent.clone()),
                                 ])
                             }).collect();
@@ -360,46 +813,123 @@ pub fn ui_renderer() -> Result<(), Box<dyn Error>> {
                         }
                     };
                     if !is_grouped {
-                        render_process_log_tab(f, chunks[1], visible);
+                        render_process_log_tab(f, chunks[1], visible, app.log_sort_mode, app.log_sort_ascending, app.memory_unit);
                     }
                 },
-                ViewMode::Help => {
-                    let size = f.size();
-                    let para = Paragraph::new("Help View (to be implemented)")
-                        .block(Block::default().borders(Borders::ALL).title("Help"));
-                    f.render_widget(para, size);
-                },
+                ViewMode::Help => draw_help(f, &app),
+                ViewMode::Columns => draw_columns_menu(f, &app),
+                ViewMode::Environment => draw_environment(f, &app),
+                ViewMode::Sockets => draw_sockets(f, &app),
+                ViewMode::Aggregated => draw_aggregated(f, &app),
+                ViewMode::SnapshotDiff => draw_snapshot_diff(f, &app),
             }
+            app.theme.finish_frame(f.buffer_mut());
         })?;
 
-        if handle_events(&mut app)? {
+        if handle_events(&mut app, next_refresh.saturating_duration_since(std::time::Instant::now()))? {
             break;
         }
-
-        sleep(Duration::from_millis(100));
     }
 
     // Cleanup and restore terminal
-    disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
-    terminal.show_cursor()?;
+    drop(guard);
+
+    if app.restart_with_sudo {
+        return Err(Box::new(crate::util::reexec_with_sudo()));
+    }
+    if let Some(pid) = args.wait_for {
+        match &app.watch_exit {
+            Some(summary) => println!("{}", summary),
+            None => println!("Process {} is still running", pid),
+        }
+    }
     
     Ok(())
 }
 
-const PROCESS_TABLE_HEIGHT: usize = 12;
+// Gives the terminal back however ui_renderer ends: normally, through an early `?` return, or
+// by a panic unwinding past it
+struct TerminalGuard;
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        restore_terminal();
+    }
+}
+
+// Leave raw mode and the alternate screen. Safe to call twice (the panic hook and the guard both
+// run on a panic), and errors are ignored since there is nothing left to report them on.
+fn restore_terminal() {
+    let _ = disable_raw_mode();
+    let _ = execute!(stdout(), LeaveAlternateScreen, DisableMouseCapture, crossterm::cursor::Show);
+}
+
+pub const PROCESS_TABLE_HEIGHT: usize = 12;
+
+// Widths of the name and user columns in the process pickers and the process log; long values
+// are cut to these with an ellipsis
+pub const NAME_COLUMN_WIDTH: u16 = 20;
+pub const USER_COLUMN_WIDTH: u16 = 12;
+
+// Width of the name or user column in the grouped view
+const GROUP_KEY_WIDTH: u16 = 25;
+
+// Nice changes remembered for undo; older ones are forgotten
+const NICE_HISTORY_LIMIT: usize = 20;
+// Entries kept in the action log shown in Help
+const ACTION_LOG_LIMIT: usize = 50;
+
+// Smallest terminal (columns, rows) each view is drawn in. Below it the layouts squeeze panels
+// to nothing, so a notice is shown instead until the terminal grows.
+fn minimum_size(view_mode: ViewMode) -> (u16, u16) {
+    match view_mode {
+        // Table plus the details/input/instructions panel beside it
+        ViewMode::KillStop | ViewMode::ChangeNice => (60, 24),
+        // Title, info box, two charts and the help line
+        ViewMode::PerProcessGraph => (50, 24),
+        _ => (30, 10),
+    }
+}
+
+fn too_small(size: Rect, view_mode: ViewMode) -> bool {
+    let (width, height) = minimum_size(view_mode);
+    size.width < width || size.height < height
+}
+
+fn draw_too_small(f: &mut Frame, view_mode: ViewMode) {
+    let size = f.size();
+    let (width, height) = minimum_size(view_mode);
+    let text = format!("Terminal too small ({}x{}), need at least {}x{}", size.width, size.height, width, height);
+    f.render_widget(Paragraph::new(text).wrap(ratatui::widgets::Wrap { trim: true }).alignment(Alignment::Center), size);
+}
+
 
 fn draw_process_list(f: &mut Frame, app: &mut App) {
     let size = f.size();
+    let compact = app.compact;
     
     let chunks = Layout::default()
         .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Min(3),     // Header
-            Constraint::Min(size.height.saturating_sub(6)), // Process list
-            Constraint::Length(3),   // Menu
-        ])
+        .constraints(if compact {
+            [
+                Constraint::Length(1), // Header
+                Constraint::Min(size.height.saturating_sub(2)), // Process list
+                Constraint::Length(1), // Status bar
+            ]
+        } else {
+            [
+                Constraint::Min(3),     // Header
+                Constraint::Min(size.height.saturating_sub(6)), // Process list
+                Constraint::Length(3),   // Menu
+            ]
+        })
         .split(size);
+    // Compact mode drops the boxes (and their titles) around the header and the table
+    let boxed = |title: &'static str| if compact { Block::default() } else { Block::default().borders(Borders::ALL).title(title) };
+
+    // Recomputed every frame as well as on resize events, so it follows the terminal size
+    app.display_limit = table_rows(chunks[1].height, compact);
+    app.process_table_area = chunks[1];
 
     // Get sort indicator for each column
     let get_sort_indicator = |column: &str| -> &str {
@@ -418,117 +948,387 @@ fn draw_process_list(f: &mut Frame, app: &mut App) {
         }
     };
 
-    // Header
-    let headers = [
-        format!("PID{}", get_sort_indicator("pid")),
-        format!("NAME{}", get_sort_indicator("name")),
-        format!("CPU%{}", get_sort_indicator("cpu")),
-        format!("MEM(MB){}", get_sort_indicator("mem")),
-        format!("PPID{}", get_sort_indicator("ppid")),
-        format!("START{}", get_sort_indicator("start")),
-        format!("NICE{}", get_sort_indicator("nice")),
-        format!("USER{}", get_sort_indicator("user")),
-        "STATUS".to_string(),
-    ];
+    let cpu_divisor = if app.cpu_of_total { app.graph_data.cpu_count() } else { 1 };
 
-    let header_cells = headers
-        .iter()
-        .map(|h| Cell::from(h.as_str()).style(Style::default().fg(Color::White).add_modifier(Modifier::BOLD)));
+    // Header
+    let header_cells = app.columns.visible().map(|column| {
+        let indicator = column.sort_mode().map(get_sort_indicator).unwrap_or("");
+        Cell::from(format!("{}{}", column.header(app.cpu_of_total), indicator))
+            .style(app.theme.header)
+    });
     
     let header = Row::new(header_cells)
-        .style(Style::default().bg(Color::Blue))
+        .style(app.theme.header)
         .height(1);
 
     // Process rows
-    // let processes = app.process_manager.get_processes();
-    let processes = if app.rule_engine.active_rule.is_some() {
-        app.process_manager.apply_rules(&mut app.rule_engine);
-        app.process_manager.get_filtered_processes()
-    } else {
-        app.process_manager.get_processes()
-    };
-    
-    
+    let processes = listed_processes(&app.process_manager, &app.rule_engine, &app.quick_filter, app.show_kernel_threads, app.running_only);
+    // Keep the offset valid if the list shrank or the window grew
+    app.scroll_offset = app.scroll_offset.min(processes.len().saturating_sub(app.display_limit));
+    app.process_list_len = processes.len();
+
+    // Follow the pinned process to wherever the latest sort put it
+    if let Some(index) = app.pinned_pid.and_then(|pid| processes.iter().position(|p| p.pid == pid)) {
+        app.selected_process_index = index;
+        scroll_into_view(&mut app.scroll_offset, app.display_limit, index);
+    }
+
+    // Quick filter and jump inputs live in the header area above the table
+    if app.jump_active {
+        let jump_text = if app.jump_clear_offered {
+            format!(":{}  (hidden by the current filters - Enter clears them and jumps, Esc cancels)", app.input_state.jump_input)
+        } else {
+            format!(":{}", app.input_state.jump_input)
+        };
+        let jump_para = Paragraph::new(jump_text)
+            .style(Style::default().fg(Color::Yellow))
+            .block(boxed("Jump to PID"));
+        f.render_widget(jump_para, chunks[0]);
+    } else if app.quick_filter_active || !app.quick_filter.is_empty() {
+        let filter_text = if app.quick_filter_active {
+            format!("/{}", app.quick_filter)
+        } else {
+            format!("Filter: {} ({} matches, / to edit, Esc to clear)", app.quick_filter, processes.len())
+        };
+        let filter_para = Paragraph::new(filter_text)
+            .style(Style::default().fg(Color::Yellow))
+            .block(boxed("Quick Filter"));
+        f.render_widget(filter_para, chunks[0]);
+    } else if app.highlight_active || !app.highlight_query.is_empty() {
+        let search_text = if app.highlight_active {
+            format!("*{}", app.highlight_query)
+        } else {
+            let query = app.highlight_query.to_lowercase();
+            let matches = processes.iter().filter(|p| p.matches_query(&query)).count();
+            format!("Highlight: {} ({} matches, n/N next/previous, * to edit, Esc to clear)", app.highlight_query, matches)
+        };
+        let search_para = Paragraph::new(search_text)
+            .style(Style::default().fg(Color::Yellow))
+            .block(boxed("Search"));
+        f.render_widget(search_para, chunks[0]);
+    } else if let Some(summary) = &app.watch_exit {
+        let banner = Paragraph::new(format!("{}  (w to dismiss)", summary))
+            .style(app.theme.message_ok.add_modifier(Modifier::BOLD | Modifier::REVERSED))
+            .block(boxed("Watched process exited"));
+        f.render_widget(banner, chunks[0]);
+    } else if let Some((msg, is_error)) = &app.input_state.message {
+        let message = Paragraph::new(msg.as_str())
+            .style(app.theme.message(*is_error))
+            .block(boxed(""));
+        f.render_widget(message, chunks[0]);
+    }
+
+    let highlight_query = app.highlight_query.to_lowercase();
     let rows: Vec<Row> = processes
         .iter()
         .skip(app.scroll_offset)
         .take(app.display_limit)
         .enumerate()
         .map(|(i, process)| {
-            let style = if i % 2 == 0 {
-                Style::default().fg(Color::Cyan)
-            } else {
-                Style::default().fg(Color::Blue)
-            };
-
-            let memory_mb = process.memory_usage / (1024 * 1024);
-            let cpu_style = match process.cpu_usage {
-                c if c > 50.0 => Style::default().fg(Color::Red),
-                c if c > 25.0 => Style::default().fg(Color::Yellow),
-                _ => Style::default().fg(Color::Green),
-            };
+            let style = app.theme.row(i);
+            let cpu_style = app.theme.cpu(process.cpu_usage);
+            let alerting = app.alerts.is_alerting(process.pid);
+            // While searching, matches stand out and every other row is dimmed
+            let search_match = (!highlight_query.is_empty()).then(|| process.matches_query(&highlight_query));
+            let is_new = app
+                .first_seen
+                .get(&process.pid)
+                .is_some_and(|seen| seen.elapsed() < app.refresh_interval * NEW_PROCESS_REFRESHES);
 
-            Row::new(vec![
-                Cell::from(process.pid.to_string()).style(style),
-                Cell::from(process.name.clone()).style(Style::default().fg(Color::Green)),
-                Cell::from(format!("{:.2}%", process.cpu_usage)).style(cpu_style),
-                Cell::from(format!("{}MB", memory_mb)).style(style),
-                Cell::from(process.parent_pid.unwrap_or(0).to_string()).style(style),
-                Cell::from(process.start_time_str.clone()).style(Style::default()),
-                Cell::from(process.nice.to_string()).style(Style::default().fg(Color::Yellow)),
-                Cell::from(process.user.clone().unwrap_or_default()).style(Style::default().fg(Color::Magenta)),
-                Cell::from(process.status.trim()).style(get_status_style(&process.status)),
-            ])
+            let cells = app.columns.visible().map(|column| {
+                let cell_style = match column {
+                    _ if alerting => app.theme.message_error.add_modifier(Modifier::BOLD),
+                    Column::Name => app.theme.name,
+                    Column::Cpu | Column::CpuTrend => cpu_style,
+                    Column::Start => Style::default(),
+                    Column::Nice => app.theme.nice,
+                    Column::User => app.theme.user,
+                    Column::Status => app.theme.status(process.state),
+                    _ => style,
+                };
+                let text = if column == Column::Pid && app.pinned_pid == Some(process.pid) {
+                    format!("*{}", process.pid)
+                } else if column == Column::CpuTrend {
+                    app.graph_data
+                        .get_process_history(process.pid)
+                        .map(|(cpu, _)| crate::util::sparkline(cpu, column.width(app.cpu_of_total) as usize))
+                        .unwrap_or_default()
+                } else {
+                    ellipsize(&column.text(process, app.memory_unit, cpu_divisor), column.width(app.cpu_of_total) as usize)
+                };
+                let cell_style = if search_match == Some(false) { cell_style.add_modifier(Modifier::DIM) } else { cell_style };
+                let cell_style = if is_new && !alerting { cell_style.patch(app.theme.new_process) } else { cell_style };
+                Cell::from(text).style(cell_style)
+            });
+            let row = Row::new(cells);
+            if app.scroll_offset + i == app.selected_process_index {
+                row.style(app.theme.selected)
+            } else if search_match == Some(true) {
+                row.style(app.theme.search_match)
+            } else {
+                row
+            }
         })
         .collect();
 
+    // Values cut short in the selected row are spelled out in full on the bottom border
+    let clipped: Vec<String> = processes
+        .get(app.selected_process_index)
+        .map(|process| {
+            app.columns
+                .visible()
+                .filter_map(|column| {
+                    let text = column.text(process, app.memory_unit, cpu_divisor);
+                    (text.chars().count() > column.width(app.cpu_of_total) as usize)
+                        .then(|| format!("{}: {}", column.header(app.cpu_of_total), text))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let mut block = boxed("");
+    // Without a border there is no room for them
+    if !clipped.is_empty() && !compact {
+        block = block.title(
+            ratatui::widgets::block::Title::from(Span::styled(format!(" {} ", clipped.join("  ")), app.theme.name))
+                .position(ratatui::widgets::block::Position::Bottom),
+        );
+    }
+    let widths: Vec<Constraint> = app.columns.visible().map(|column| Constraint::Length(column.width(app.cpu_of_total))).collect();
     let table = Table::new(rows)
         .header(header)
-        .block(Block::default().borders(Borders::ALL))
-        .widths(&[
-            Constraint::Length(8),  // PID
-            Constraint::Length(20), // NAME
-            Constraint::Length(8),  // CPU%
-            Constraint::Length(10), // MEM
-            Constraint::Length(8),  // PPID
-            Constraint::Length(12), // START
-            Constraint::Length(8),  // NICE
-            Constraint::Length(12), // USER
-            Constraint::Length(10), // STATUS
-        ]);
+        .block(block)
+        .widths(&widths);
 
     f.render_widget(table, chunks[1]);
 
-    // Menu
-    let menu_text = vec![
-        Line::from(vec![
-            Span::styled("[↑/↓] Scroll  ", Style::default().fg(Color::Cyan)),
-            Span::raw("| "),
-            Span::styled("[1] Filter/Sort  ", Style::default().fg(Color::Yellow)),
-            Span::raw("| "),
-            Span::styled("[2] Change Nice  ", Style::default().fg(Color::Green)),
-            Span::raw("| "),
-            Span::styled("[3] Kill/Stop  ", Style::default().fg(Color::Red)),
-            Span::raw("| "),
-            Span::styled("[4] Per-Process Graph  ", Style::default().fg(Color::Magenta)),
-            Span::raw("| "),
-            Span::styled("[5] Process Log  ", Style::default().fg(Color::Cyan)),
-            Span::raw("| "),
-            Span::styled("[6] Help  ", Style::default().fg(Color::Yellow)),
-            Span::raw("| "),
-            Span::styled("[S] Statistics  ", Style::default().fg(Color::Blue)),
-            Span::raw("| "),
-            Span::styled("[q] Quit", Style::default().fg(Color::White)),
-        ]),
+    // Menu, labelled with the keys currently bound
+    let key = |action| app.keymap.label(action);
+    let entries = [
+        (format!("{}/{}/{}/{}", key(Action::Up), key(Action::Down), key(Action::PageUp), key(Action::PageDown)), "Scroll", Color::Cyan),
+        (key(Action::QuickFilter), "Filter", Color::Green),
+        (key(Action::FilterSortMenu), "Filter/Sort", Color::Yellow),
+        (key(Action::ChangeNiceMenu), "Change Nice", Color::Green),
+        (key(Action::KillStopMenu), "Kill/Stop", Color::Red),
+        (key(Action::PerProcessGraph), "Per-Process Graph", Color::Magenta),
+        (key(Action::ProcessLog), "Process Log", Color::Cyan),
+        (key(Action::Help), "Help", Color::Yellow),
+        (key(Action::Statistics), "Statistics", Color::Blue),
+        (key(Action::Quit), "Quit", Color::White),
     ];
+    let mut spans = Vec::new();
+    for (i, (keys, label, color)) in entries.into_iter().enumerate() {
+        if i > 0 {
+            spans.push(Span::raw("  | "));
+        }
+        spans.push(Span::styled(format!("[{}] {}", keys, label), Style::default().fg(color)));
+    }
+    let menu_text = vec![Line::from(spans)];
 
+    if compact {
+        f.render_widget(Paragraph::new(status_bar(app)), chunks[2]);
+        return;
+    }
+    // The status bar sits in the menu's top border so it never takes rows from the table
     let menu = Paragraph::new(menu_text)
-        .block(Block::default().borders(Borders::ALL))
+        .block(Block::default().borders(Borders::ALL).title(status_bar(app)))
         .alignment(Alignment::Left);
 
     f.render_widget(menu, chunks[2]);
 }
 
+// One-line system summary for the process list: CPU, memory, load, process count and the
+// active sort/filters
+fn status_bar(app: &App) -> Line<'static> {
+    let (mem_used, mem_total) = app.graph_data.memory_usage();
+    let (load1, load5, load15) = app.graph_data.load_average();
+    let cpu = app.graph_data.cpu_usage();
+    let mut total = app.process_manager.total_process_count();
+    if !app.show_kernel_threads {
+        total = total.saturating_sub(app.process_manager.kernel_thread_count());
+    }
+    let mut text = format!(
+        " CPU {:.1}% | Mem {}/{} | Load {:.2} {:.2} {:.2} | Procs {}/{}",
+        cpu,
+        format_megabytes(mem_used as f64, app.memory_unit),
+        format_megabytes(mem_total as f64, app.memory_unit),
+        load1,
+        load5,
+        load15,
+        app.process_list_len,
+        total,
+    );
+    if app.show_kernel_threads {
+        text.push_str(" (incl. kernel threads)");
+    }
+    if app.running_only {
+        text.push_str(" | Running only");
+    }
+    if let Some((cpu, memory)) = app.process_manager.own_usage() {
+        // Our own footprint, so a too-fast refresh shows up here rather than as a mystery load
+        text.push_str(&format!(" | Self {:.1}% {}", cpu, format_bytes(memory, app.memory_unit)));
+    }
+    if let Some(mode) = &app.sort_mode {
+        text.push_str(&format!(" | Sort: {} {}", mode, if app.sort_ascending { "↑" } else { "↓" }));
+    }
+    if let Some((mode, value)) = app.process_manager.get_filter() {
+        text.push_str(&format!(" | Filter: {}={}", mode, value));
+    }
+    if let Some(rule) = &app.rule_engine.active_rule {
+        text.push_str(&format!(" | Rule: {}", rule));
+    }
+    if app.alerts.alerting_count() > 0 {
+        text.push_str(&format!(" | Alerts: {}", app.alerts.alerting_count()));
+    }
+    if let Some(pid) = app.watched_pid {
+        text.push_str(&format!(" | Watching: {}", pid));
+    }
+    if !app.quick_filter.is_empty() {
+        text.push_str(&format!(" | Search: {}", app.quick_filter));
+    }
+    text.push(' ');
+    if crate::util::is_root() {
+        // Every signal and renice succeeds as root, so make that hard to miss
+        return Line::from(vec![
+            Span::styled(" ROOT ", app.theme.message_error.add_modifier(Modifier::BOLD | Modifier::REVERSED)),
+            Span::raw(text),
+        ]);
+    }
+    Line::from(Span::raw(text))
+}
+
+// Process rows that fit in a table chunk of this height: minus the top/bottom borders (none in
+// compact mode) and the header row
+fn table_rows(chunk_height: u16, compact: bool) -> usize {
+    (chunk_height as usize).saturating_sub(if compact { 1 } else { 3 }).max(1)
+}
+
+// Refit the process list to a resized terminal right away rather than on the next draw, keeping
+// the selected row on screen
+fn handle_resize(app: &mut App, height: u16) {
+    // The table gets everything but the header and menu rows (see draw_process_list)
+    let outside_table = if app.compact { 2 } else { 6 };
+    app.display_limit = table_rows(height.saturating_sub(outside_table), app.compact);
+    if app.process_list_len > 0 {
+        app.selected_process_index = app.selected_process_index.min(app.process_list_len - 1);
+        scroll_into_view(&mut app.scroll_offset, app.display_limit, app.selected_process_index);
+    }
+    app.scroll_offset = app.scroll_offset.min(app.process_list_len.saturating_sub(app.display_limit));
+    app.redraw = true;
+}
+
+// Scroll the process list just enough to bring row `index` into view
+fn scroll_into_view(scroll_offset: &mut usize, display_limit: usize, index: usize) {
+    if index < *scroll_offset {
+        *scroll_offset = index;
+    } else if index >= *scroll_offset + display_limit {
+        *scroll_offset = index + 1 - display_limit;
+    }
+}
+
+// Move a cursor or scroll position for a navigation key: one row, a page of `page` rows, or to
+// either end of a list whose last position is `last`. Other actions leave it alone.
+fn scroll_by_action(action: Action, position: &mut usize, page: usize, last: usize) {
+    let current = (*position).min(last);
+    *position = match action {
+        Action::Up => current.saturating_sub(1),
+        Action::Down => (current + 1).min(last),
+        Action::PageUp => current.saturating_sub(page),
+        Action::PageDown => (current + page).min(last),
+        Action::Top => 0,
+        Action::Bottom => last,
+        _ => return,
+    };
+}
+
+// Select the PID typed after ':'. If a filter hides it, the first Enter offers to clear the
+// filters and the second one does.
+fn jump_to_pid(app: &mut App) {
+    let Ok(pid) = app.input_state.jump_input.parse::<u32>() else {
+        app.jump_active = false;
+        return;
+    };
+    let processes = listed_processes(&app.process_manager, &app.rule_engine, &app.quick_filter, app.show_kernel_threads, app.running_only);
+    if let Some(index) = processes.iter().position(|p| p.pid == pid) {
+        app.jump_active = false;
+        if app.pinned_pid.is_some() {
+            app.pinned_pid = Some(pid);
+        }
+        app.selected_process_index = index;
+        scroll_into_view(&mut app.scroll_offset, app.display_limit, index);
+    } else if !app.process_manager.process_exists(pid) {
+        app.jump_active = false;
+        app.input_state.message = Some((format!("PID {} not found", pid), true));
+        app.input_state.message_timeout = Some(std::time::Instant::now() + Duration::from_secs(3));
+    } else if !app.jump_clear_offered {
+        app.jump_clear_offered = true;
+    } else {
+        app.quick_filter.clear();
+        app.running_only = false;
+        app.show_kernel_threads = true;
+        app.rule_engine.active_rule = None;
+        app.filter_mode = None;
+        app.process_manager.set_filter(None, None);
+        app.jump_clear_offered = false;
+        jump_to_pid(app);
+    }
+}
+
+// Move the selection to the next (or previous) row matching the highlight search, wrapping
+// around the list like `less` does. With `include_current`, a matching selected row stays put.
+fn jump_to_match(app: &mut App, forward: bool, include_current: bool) {
+    let query = app.highlight_query.to_lowercase();
+    if query.is_empty() {
+        return;
+    }
+    let processes = listed_processes(&app.process_manager, &app.rule_engine, &app.quick_filter, app.show_kernel_threads, app.running_only);
+    match find_match(&processes, &query, app.selected_process_index, forward, include_current) {
+        Some(index) => {
+            if app.pinned_pid.is_some() {
+                app.pinned_pid = Some(processes[index].pid);
+            }
+            app.selected_process_index = index;
+            scroll_into_view(&mut app.scroll_offset, app.display_limit, index);
+        }
+        None => {
+            app.input_state.message = Some((format!("No process matches '{}'", app.highlight_query), true));
+            app.input_state.message_timeout = Some(std::time::Instant::now() + Duration::from_secs(3));
+        }
+    }
+}
+
+// Index of the next row matching `query` (lowercase) from `current`, wrapping around the list.
+// `include_current` lets the current row itself count, for a freshly typed query.
+fn find_match(processes: &[&process::ProcessInfo], query: &str, current: usize, forward: bool, include_current: bool) -> Option<usize> {
+    let len = processes.len();
+    if len == 0 {
+        return None;
+    }
+    let current = current.min(len - 1);
+    let first_step = if include_current { 0 } else { 1 };
+    (first_step..=len)
+        .map(|step| if forward { (current + step) % len } else { (current + len - step % len) % len })
+        .find(|&index| processes[index].matches_query(query))
+}
+
+// Rows shown in the process list: the rule-filtered set when a rule is active, narrowed by the quick
+// filter, without kernel threads unless they are shown, and only runnable processes if asked
+fn listed_processes<'a>(
+    manager: &'a ProcessManager,
+    rule_engine: &RuleEngine,
+    quick_filter: &str,
+    show_kernel_threads: bool,
+    running_only: bool,
+) -> Vec<&'a process::ProcessInfo> {
+    let query = quick_filter.to_lowercase();
+    manager
+        .visible_processes(rule_engine)
+        .iter()
+        .filter(|p| show_kernel_threads || !p.kernel_thread)
+        .filter(|p| !running_only || p.state == ProcessState::Running)
+        .filter(|p| query.is_empty() || p.matches_query(&query))
+        .collect()
+}
+
 fn draw_filter_sort_menu(f: &mut Frame) {
     let size = f.size();
     
@@ -553,6 +1353,7 @@ fn draw_filter_sort_menu(f: &mut Frame) {
         ListItem::new(Span::styled("[1] Sort", Style::default().fg(Color::Yellow))),
         ListItem::new(Span::styled("[2] Filter", Style::default().fg(Color::Green))),
         ListItem::new(Span::styled("[X] Script Filtering", Style::default().fg(Color::Magenta))),
+        ListItem::new(Span::styled("[A] Alert Rules", Style::default().fg(Color::Red))),
         ListItem::new(Span::styled("[←] Back", Style::default().fg(Color::Blue))),
     ];
 
@@ -591,6 +1392,11 @@ fn draw_sort_menu(f: &mut Frame, app: &App) {
         ListItem::new(Span::styled("[4] Sort by Start Time", Style::default().fg(Color::Magenta))),
         ListItem::new(Span::styled("[5] Sort by Nice Value", Style::default().fg(Color::Cyan))),
         ListItem::new(Span::styled("[6] Sort by CPU Usage", Style::default().fg(Color::Red))),
+        ListItem::new(Span::styled("[7] Sort by Thread Count", Style::default().fg(Color::Green))),
+        ListItem::new(Span::styled("[8] Sort by Memory %", Style::default().fg(Color::Yellow))),
+        ListItem::new(Span::styled("[9] Sort by Uptime", Style::default().fg(Color::Magenta))),
+        ListItem::new(Span::styled("[0] Sort by Swap", Style::default().fg(Color::Cyan))),
+        ListItem::new(Span::styled("[t] Sort by CPU Time", Style::default().fg(Color::Red))),
         ListItem::new(Span::styled("[a] Toggle Ascending/Descending", Style::default().fg(Color::White))),
         ListItem::new(Span::styled("[←] Back", Style::default().fg(Color::Blue))),
     ];
@@ -632,10 +1438,11 @@ fn draw_filter_menu(f: &mut Frame) {
 
     // Menu items
     let items = vec![
-        ListItem::new(Span::styled("[1] Filter by User", Style::default().fg(Color::Magenta))),
+        ListItem::new(Span::styled("[1] Filter by User (name or UID)", Style::default().fg(Color::Magenta))),
         ListItem::new(Span::styled("[2] Filter by Name", Style::default().fg(Color::Green))),
         ListItem::new(Span::styled("[3] Filter by PID", Style::default().fg(Color::Yellow))),
         ListItem::new(Span::styled("[4] Filter by PPID", Style::default().fg(Color::Cyan))),
+        ListItem::new(Span::styled("[5] Filter by Container", Style::default().fg(Color::Green))),
         ListItem::new(Span::styled("[Esc] Clear Filter", Style::default().fg(Color::Red))),
         ListItem::new(Span::styled("[←] Back", Style::default().fg(Color::Blue))),
     ];
@@ -666,6 +1473,7 @@ fn draw_filter_input_menu(f: &mut Frame, app: &App) {
         Some("name") => "Process Name",
         Some("pid") => "PID",
         Some("ppid") => "Parent PID",
+        Some("container") => "Container",
         _ => "Unknown",
     };
     let title = Paragraph::new(format!("Enter {} Filter", filter_type))
@@ -684,7 +1492,7 @@ fn draw_filter_input_menu(f: &mut Frame, app: &App) {
         ListItem::new(Span::styled("[←] Back", Style::default().fg(Color::Blue))),
     ];
 
-    if app.filter_mode.as_deref().map_or(false, |m| m == "pid" || m == "ppid") {
+    if app.filter_mode.as_deref().is_some_and(|m| m == "pid" || m == "ppid") {
         instructions.insert(1, ListItem::new(Span::styled(
             "(Numbers only)",
             Style::default().fg(Color::Yellow)
@@ -733,8 +1541,8 @@ fn draw_kill_stop_menu(f: &mut Frame, app: &mut App) {
     let size = spacing_chunk[1];
 
     let process_table_width = (size.width as f32 * 0.55) as u16;
-    let right_panel_width = size.width - process_table_width;
-    let process_table_height = size.height - 2;
+    let right_panel_width = size.width.saturating_sub(process_table_width);
+    let process_table_height = size.height.saturating_sub(2);
 
     let chunks = Layout::default()
         .direction(Direction::Horizontal)
@@ -747,45 +1555,33 @@ fn draw_kill_stop_menu(f: &mut Frame, app: &mut App) {
     // --- LEFT: Process Table with highlight ---
     // let processes = app.process_manager.get_processes();
 
-    let processes = if app.rule_engine.active_rule.is_some() {
-        app.process_manager.apply_rules(&mut app.rule_engine);
-        app.process_manager.get_filtered_processes()
-    } else {
-        app.process_manager.get_processes()
-    };
+    let processes = listed_processes(&app.process_manager, &app.rule_engine, &app.quick_filter, app.show_kernel_threads, app.running_only);
     
 
-    let headers = ["PID", "NAME", "STATUS", "CPU%", "MEM(MB)", "USER"];
+    let headers = ["PID", "NAME", "STATUS", "CPU%", "MEM", "USER"];
     let header_cells = headers
         .iter()
-        .map(|h| Cell::from(*h).style(Style::default().fg(Color::White).add_modifier(Modifier::BOLD)));
+        .map(|h| Cell::from(*h).style(app.theme.header));
     let header = Row::new(header_cells)
-        .style(Style::default().bg(Color::Blue))
+        .style(app.theme.header)
         .height(1);
 
     let visible_processes = processes
         .iter()
         .skip(app.scroll_offset)
-        .take(process_table_height as usize - 2)
+        .take((process_table_height as usize).saturating_sub(2))
         .enumerate()
         .map(|(i, process)| {
             let idx = app.scroll_offset + i;
             let highlight = idx == app.selected_process_index;
-            let style = if highlight {
-                Style::default().fg(Color::Black).bg(Color::Yellow).add_modifier(Modifier::BOLD)
-            } else if i % 2 == 0 {
-                Style::default().fg(Color::Cyan)
-            } else {
-                Style::default().fg(Color::Blue)
-            };
-            let memory_mb = process.memory_usage / (1024 * 1024);
+            let style = if highlight { app.theme.highlight } else { app.theme.row(i) };
             Row::new(vec![
                 Cell::from(process.pid.to_string()).style(style),
-                Cell::from(process.name.clone()).style(Style::default().fg(Color::Green)),
-                Cell::from(process.status.trim()).style(get_status_style(&process.status)),
+                Cell::from(ellipsize(&process.name, NAME_COLUMN_WIDTH as usize)).style(app.theme.name),
+                Cell::from(process.status.trim()).style(app.theme.status(process.state)),
                 Cell::from(format!("{:.1}%", process.cpu_usage)).style(style),
-                Cell::from(format!("{}", memory_mb)).style(style),
-                Cell::from(process.user.clone().unwrap_or_default()).style(Style::default().fg(Color::Magenta)),
+                Cell::from(format_bytes(process.memory_usage, app.memory_unit)).style(style),
+                Cell::from(ellipsize(&process.user_label(), USER_COLUMN_WIDTH as usize)).style(app.theme.user),
             ])
         })
         .collect::<Vec<_>>();
@@ -795,11 +1591,11 @@ fn draw_kill_stop_menu(f: &mut Frame, app: &mut App) {
         .block(Block::default().borders(Borders::ALL).title("Processes (↑↓ to move, Enter to select)"))
         .widths(&[
             Constraint::Length(8),   // PID
-            Constraint::Length(20),  // NAME
+            Constraint::Length(NAME_COLUMN_WIDTH),  // NAME
             Constraint::Length(10),  // STATUS
             Constraint::Length(8),   // CPU%
-            Constraint::Length(10),  // MEM(MB)
-            Constraint::Length(12),  // USER
+            Constraint::Length(10),  // MEM
+            Constraint::Length(USER_COLUMN_WIDTH),  // USER
         ]);
     f.render_widget(process_table, chunks[0]);
 
@@ -807,7 +1603,7 @@ fn draw_kill_stop_menu(f: &mut Frame, app: &mut App) {
     let right_chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Length(5), // Process details
+            Constraint::Length(11), // Process details
             Constraint::Length(5), // Input box
             Constraint::Min(3),    // Instructions & status
         ])
@@ -821,8 +1617,21 @@ fn draw_kill_stop_menu(f: &mut Frame, app: &mut App) {
             Line::from(vec![Span::styled("Selected Process:", Style::default().fg(Color::White).add_modifier(Modifier::BOLD))]),
             Line::from(vec![Span::raw(format!("PID: {}", proc.pid))]),
             Line::from(vec![Span::raw(format!("Name: {}", proc.name))]),
-            Line::from(vec![Span::raw(format!("User: {}", proc.user.clone().unwrap_or_default()))]),
-            Line::from(vec![Span::raw(format!("Status: {}", proc.status))]),
+            Line::from(vec![Span::raw(format!(
+                "User: {} (UID {}, GID {})",
+                proc.user_label(),
+                proc.uid.map_or("-".to_string(), |uid| uid.to_string()),
+                proc.gid.map_or("-".to_string(), |gid| gid.to_string()),
+            ))]),
+            Line::from(vec![Span::raw(format!("Status: {}   {}", proc.status, oom_label(app.process_manager.proc_root(), proc.pid)))]),
+            Line::from(vec![Span::raw(format!("Threads: {}", proc.thread_count))]),
+            Line::from(vec![Span::raw(format!(
+                "Memory: {} resident, {} virtual",
+                format_bytes(proc.memory_usage, app.memory_unit),
+                format_bytes(proc.virtual_memory, app.memory_unit),
+            ))]),
+            Line::from(vec![Span::raw(format!("Exe: {}", crate::util::path_or_dash(proc.exe.as_deref())))]),
+            Line::from(vec![Span::raw(format!("Cwd: {}", crate::util::path_or_dash(proc.cwd.as_deref())))]),
         ]
     } else {
         vec![Line::from("No process selected.")]
@@ -832,10 +1641,34 @@ fn draw_kill_stop_menu(f: &mut Frame, app: &mut App) {
     f.render_widget(details_box, right_chunks[0]);
 
     // Input box for action
-    let input_text = if app.kill_stop_input_state == KillStopInputState::EnteringAction {
-        "Enter action: [k] Kill, [s] Stop, [c] Continue, [t] Terminate, [Esc] Cancel".to_string()
-    } else {
-        "Press Enter to select action".to_string()
+    let input_text = match app.kill_stop_input_state {
+        KillStopInputState::EnteringAction if app.bulk_name.is_some() => format!(
+            "Action for every process named '{}': [{}] Kill, [{}] Stop, [{}] Continue, [{}] Terminate, [Esc] Cancel",
+            app.bulk_name.as_deref().unwrap_or_default(),
+            app.keymap.label(Action::Kill),
+            app.keymap.label(Action::Stop),
+            app.keymap.label(Action::Continue),
+            app.keymap.label(Action::Terminate),
+        ),
+        KillStopInputState::EnteringAction => format!(
+            "Enter action: [{}] Kill, [{}] Stop, [{}] Continue, [{}] Terminate, [{}] Terminate then kill, [{}] Kill tree, [{}] Stop tree, [{}] Continue tree, [Esc] Cancel",
+            app.keymap.label(Action::Kill),
+            app.keymap.label(Action::Stop),
+            app.keymap.label(Action::Continue),
+            app.keymap.label(Action::Terminate),
+            app.keymap.label(Action::TerminateThenKill),
+            app.keymap.label(Action::KillTree),
+            app.keymap.label(Action::StopTree),
+            app.keymap.label(Action::ContinueTree),
+        ),
+        KillStopInputState::ConfirmingAction => "Confirm: [y] Yes, any other key to cancel".to_string(),
+        KillStopInputState::EnteringName => format!("Process name: {}", app.input_state.name_input),
+        KillStopInputState::EnteringPort => format!("Kill process on port: {}", app.input_state.port_input),
+        KillStopInputState::SelectingPid => format!(
+            "Press Enter to select action, {} to act on a process name, {} to find the process on a port",
+            app.keymap.label(Action::ByName),
+            app.keymap.label(Action::ByPort),
+        ),
     };
     let input_box = Paragraph::new(input_text)
         .style(Style::default().fg(Color::Yellow))
@@ -849,13 +1682,45 @@ fn draw_kill_stop_menu(f: &mut Frame, app: &mut App) {
         )]),
         Line::from(vec![Span::raw("- Use ↑/↓ to move selection in the process list.")]),
         Line::from(vec![Span::raw("- Press Enter to select a process and input an action.")]),
-        Line::from(vec![Span::raw("- Type k/s/c/t for Kill/Stop/Continue/Terminate, then Esc to cancel or return." )]),
+        Line::from(vec![Span::raw(format!(
+            "- Type {}/{}/{}/{} for Kill/Stop/Continue/Terminate, then Esc to cancel or return.",
+            app.keymap.label(Action::Kill),
+            app.keymap.label(Action::Stop),
+            app.keymap.label(Action::Continue),
+            app.keymap.label(Action::Terminate),
+        ))]),
+        Line::from(vec![Span::raw(format!(
+            "- Type {} to terminate, then kill if still running after {:.1}s.",
+            app.keymap.label(Action::TerminateThenKill),
+            app.kill_grace_period.as_secs_f64(),
+        ))]),
+        Line::from(vec![Span::raw(format!(
+            "- Type {} to kill the process and all of its children.",
+            app.keymap.label(Action::KillTree),
+        ))]),
+        Line::from(vec![Span::raw(format!(
+            "- Type {}/{} to suspend/resume the process and all of its children.",
+            app.keymap.label(Action::StopTree),
+            app.keymap.label(Action::ContinueTree),
+        ))]),
+        Line::from(vec![Span::raw(format!(
+            "- Type {} to terminate and relaunch with the same command line, directory and environment.",
+            app.keymap.label(Action::Restart),
+        ))]),
+        Line::from(vec![Span::raw(format!(
+            "- Press {} to type a process name and act on every process with that name.",
+            app.keymap.label(Action::ByName),
+        ))]),
+        Line::from(vec![Span::raw(format!(
+            "- Press {} to type a port and select the process listening on it.",
+            app.keymap.label(Action::ByPort),
+        ))]),
         Line::from(vec![Span::raw("- Press Esc to cancel and return.")]),
     ];
     if let Some((msg, is_error)) = &app.input_state.message {
         info.push(Line::from(vec![Span::styled(
             msg,
-            if *is_error { Style::default().fg(Color::Red) } else { Style::default().fg(Color::Green) }
+            app.theme.message(*is_error)
         )]));
     }
     let info_box = Paragraph::new(info)
@@ -890,8 +1755,8 @@ fn draw_change_nice_menu(f: &mut Frame, app: &mut App) {
     let size = spacing_chunk[1];
 
     let process_table_width = (size.width as f32 * 0.55) as u16;
-    let right_panel_width = size.width - process_table_width;
-    let process_table_height = size.height - 2;
+    let right_panel_width = size.width.saturating_sub(process_table_width);
+    let process_table_height = size.height.saturating_sub(2);
 
     let chunks = Layout::default()
         .direction(Direction::Horizontal)
@@ -902,40 +1767,30 @@ fn draw_change_nice_menu(f: &mut Frame, app: &mut App) {
         .split(size);
 
     // --- LEFT: Process Table with highlight ---
-    let processes = if app.rule_engine.active_rule.is_some() {
-        app.process_manager.apply_rules(&mut app.rule_engine);
-        app.process_manager.get_filtered_processes()
-    } else {
-        app.process_manager.get_processes()
-    };    let headers = ["PID", "NAME", "NICE", "CPU%", "USER"];
+    let processes = listed_processes(&app.process_manager, &app.rule_engine, &app.quick_filter, app.show_kernel_threads, app.running_only);
+    let headers = ["PID", "NAME", "NICE", "CPU%", "USER"];
     let header_cells = headers
         .iter()
-        .map(|h| Cell::from(*h).style(Style::default().fg(Color::White).add_modifier(Modifier::BOLD)));
+        .map(|h| Cell::from(*h).style(app.theme.header));
     let header = Row::new(header_cells)
-        .style(Style::default().bg(Color::Blue))
+        .style(app.theme.header)
         .height(1);
 
     let visible_processes = processes
         .iter()
         .skip(app.change_nice_scroll_offset)
-        .take(process_table_height as usize - 2)
+        .take((process_table_height as usize).saturating_sub(2))
         .enumerate()
         .map(|(i, process)| {
             let idx = app.change_nice_scroll_offset + i;
             let highlight = idx == app.selected_process_index;
-            let style = if highlight {
-                Style::default().fg(Color::Black).bg(Color::Yellow).add_modifier(Modifier::BOLD)
-            } else if i % 2 == 0 {
-                Style::default().fg(Color::Cyan)
-            } else {
-                Style::default().fg(Color::Blue)
-            };
+            let style = if highlight { app.theme.highlight } else { app.theme.row(i) };
             Row::new(vec![
                 Cell::from(process.pid.to_string()).style(style),
-                Cell::from(process.name.clone()).style(Style::default().fg(Color::Green)),
-                Cell::from(process.nice.to_string()).style(Style::default().fg(Color::Yellow)),
+                Cell::from(ellipsize(&process.name, NAME_COLUMN_WIDTH as usize)).style(app.theme.name),
+                Cell::from(process.nice.to_string()).style(app.theme.nice),
                 Cell::from(format!("{:.1}%", process.cpu_usage)).style(style),
-                Cell::from(process.user.clone().unwrap_or_default()).style(Style::default().fg(Color::Magenta)),
+                Cell::from(ellipsize(&process.user_label(), USER_COLUMN_WIDTH as usize)).style(app.theme.user),
             ])
         })
         .collect::<Vec<_>>();
@@ -945,10 +1800,10 @@ fn draw_change_nice_menu(f: &mut Frame, app: &mut App) {
         .block(Block::default().borders(Borders::ALL).title("Processes (↑↓ to move, Enter to select)"))
         .widths(&[
             Constraint::Length(8),   // PID
-            Constraint::Length(20),  // NAME
+            Constraint::Length(NAME_COLUMN_WIDTH),  // NAME
             Constraint::Length(8),   // NICE
             Constraint::Length(8),   // CPU%
-            Constraint::Length(12),  // USER
+            Constraint::Length(USER_COLUMN_WIDTH),  // USER
         ]);
     f.render_widget(process_table, chunks[0]);
 
@@ -956,7 +1811,7 @@ fn draw_change_nice_menu(f: &mut Frame, app: &mut App) {
     let right_chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Length(5), // Process details
+            Constraint::Length(11), // Process details
             Constraint::Length(5), // Input box
             Constraint::Min(3),    // Instructions & status
         ])
@@ -970,8 +1825,21 @@ fn draw_change_nice_menu(f: &mut Frame, app: &mut App) {
             Line::from(vec![Span::styled("Selected Process:", Style::default().fg(Color::White).add_modifier(Modifier::BOLD))]),
             Line::from(vec![Span::raw(format!("PID: {}", proc.pid))]),
             Line::from(vec![Span::raw(format!("Name: {}", proc.name))]),
-            Line::from(vec![Span::raw(format!("User: {}", proc.user.clone().unwrap_or_default()))]),
-            Line::from(vec![Span::raw(format!("Current Nice: {}", proc.nice))]),
+            Line::from(vec![Span::raw(format!(
+                "User: {} (UID {}, GID {})",
+                proc.user_label(),
+                proc.uid.map_or("-".to_string(), |uid| uid.to_string()),
+                proc.gid.map_or("-".to_string(), |gid| gid.to_string()),
+            ))]),
+            Line::from(vec![Span::raw(format!("Current Nice: {}   {}", proc.nice, oom_label(app.process_manager.proc_root(), proc.pid)))]),
+            Line::from(vec![Span::raw(format!("Threads: {}", proc.thread_count))]),
+            Line::from(vec![Span::raw(format!(
+                "Memory: {} resident, {} virtual",
+                format_bytes(proc.memory_usage, app.memory_unit),
+                format_bytes(proc.virtual_memory, app.memory_unit),
+            ))]),
+            Line::from(vec![Span::raw(format!("Exe: {}", crate::util::path_or_dash(proc.exe.as_deref())))]),
+            Line::from(vec![Span::raw(format!("Cwd: {}", crate::util::path_or_dash(proc.cwd.as_deref())))]),
         ]
     } else {
         vec![Line::from("No process selected.")]
@@ -981,20 +1849,37 @@ fn draw_change_nice_menu(f: &mut Frame, app: &mut App) {
     f.render_widget(details_box, right_chunks[0]);
 
     // Input box for nice value
-    let input_text = if app.nice_input_state == NiceInputState::EnteringNice {
-        format!("New nice value (-20 to 19): {}", app.input_state.nice_input)
-    } else {
-        "Press Enter to change nice value".to_string()
+    let input_text = match (&app.nice_input_state, &app.bulk_name) {
+        (NiceInputState::EnteringNice, Some(name)) => {
+            format!("New nice value for every process named '{}' (-20 to 19): {}", name, app.input_state.nice_input)
+        }
+        (NiceInputState::EnteringNice, None) => {
+            let preview = match (proc, app.input_state.nice_input.parse::<i32>()) {
+                (Some(proc), Ok(nice)) if (-20..=19).contains(&nice) => format!("   ({} → {})", proc.nice, nice),
+                _ => String::new(),
+            };
+            format!("New nice value (-20 to 19): {}{}", app.input_state.nice_input, preview)
+        }
+        (NiceInputState::EnteringOomAdj, _) => {
+            let preview = match (proc.and_then(|proc| process::read_oom_score(app.process_manager.proc_root(), proc.pid).1), app.input_state.oom_input.parse::<i32>()) {
+                (Some(current), Ok(adj)) if (-1000..=1000).contains(&adj) => format!("   ({} → {})", current, adj),
+                _ => String::new(),
+            };
+            format!("New oom_score_adj (-1000 never killed, 1000 killed first): {}{}", app.input_state.oom_input, preview)
+        }
+        (NiceInputState::EnteringName, _) => format!("Process name: {}", app.input_state.name_input),
+        (NiceInputState::SelectingPid, _) => format!(
+            "Press Enter to change nice value, {} to renice by process name, {} to set oom_score_adj, {} to undo",
+            app.keymap.label(Action::ByName),
+            app.keymap.label(Action::OomScoreAdj),
+            app.keymap.label(Action::UndoNice),
+        ),
     };
     // If in selection mode or after a message, use yellow (neutral) for input box
     let input_style = if app.nice_input_state == NiceInputState::SelectingPid {
         Style::default().fg(Color::Yellow)
     } else if let Some((_, is_error)) = &app.input_state.message {
-        if *is_error {
-            Style::default().fg(Color::Red)
-        } else {
-            Style::default().fg(Color::Green)
-        }
+        app.theme.message(*is_error)
     } else {
         Style::default().fg(Color::Yellow)
     };
@@ -1010,13 +1895,18 @@ fn draw_change_nice_menu(f: &mut Frame, app: &mut App) {
         )]),
         Line::from(vec![Span::raw("- Use ↑/↓ to move selection in the process list.")]),
         Line::from(vec![Span::raw("- Press Enter to select a process and input a new nice value.")]),
-        Line::from(vec![Span::raw("- Type the new nice value, then Enter to apply." )]),
+        Line::from(vec![Span::raw("- Type the new nice value, or step it with ←/→ or -/+, then Enter to apply." )]),
+        Line::from(vec![Span::raw(format!("- Press {} to undo the last nice change.", app.keymap.label(Action::UndoNice)))]),
+        Line::from(vec![Span::raw(format!(
+            "- Press {} to set oom_score_adj: -1000 shields a process from the OOM killer, 1000 offers it up first.",
+            app.keymap.label(Action::OomScoreAdj)
+        ))]),
         Line::from(vec![Span::raw("- Press Esc to cancel and return.")]),
     ];
     if let Some((msg, is_error)) = &app.input_state.message {
         info.push(Line::from(vec![Span::styled(
             msg,
-            if *is_error { Style::default().fg(Color::Red) } else { Style::default().fg(Color::Green) }
+            app.theme.message(*is_error)
         )]));
     }
     let info_box = Paragraph::new(info)
@@ -1033,10 +1923,15 @@ fn draw_rule_input(f: &mut Frame, app: &App) {
         .constraints([Constraint::Min(3)].as_ref())
         .split(f.size());
 
+    let preview = match &app.rule_preview {
+        Ok(count) => Span::styled(format!(" matches: {} ", count), app.theme.message_ok),
+        Err(_) => Span::styled(" invalid ", app.theme.message_error),
+    };
     let input = Paragraph::new(app.input_state.rule_input.as_str())
         .block(
             Block::default()
                 .title("Enter Rule (e.g., cpu > 5.0 && mem < 1000)")
+                .title(ratatui::widgets::block::Title::from(preview).alignment(Alignment::Right))
                 .borders(Borders::ALL)
                 .border_type(BorderType::Rounded)
                 .style(Style::default().fg(Color::White)),
@@ -1046,19 +1941,72 @@ fn draw_rule_input(f: &mut Frame, app: &App) {
     f.render_widget(input, chunks[0]);
 }
 
-fn get_status_style(status: &str) -> Style {
-    match status.trim().to_lowercase().as_str() {
-        "running" => Style::default().fg(Color::Green),
-        "sleeping" => Style::default().fg(Color::Blue),
-        "stopped" => Style::default().fg(Color::Yellow),
-        "zombie" => Style::default().fg(Color::Red),
-        _ => Style::default().fg(Color::White),
+fn draw_alert_input(f: &mut Frame, app: &App) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(4)
+        .constraints([Constraint::Length(3), Constraint::Min(3)].as_ref())
+        .split(f.size());
+
+    let input = Paragraph::new(app.input_state.alert_input.as_str())
+        .block(
+            Block::default()
+                .title("Add Alert Rule (e.g., cpu > 90 for 10s, mem > 2000; Enter on empty clears all)")
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .style(Style::default().fg(Color::White)),
+        )
+        .style(Style::default().fg(Color::Yellow));
+    f.render_widget(input, chunks[0]);
+
+    let mut lines: Vec<Line> = if app.alerts.rules.is_empty() {
+        vec![Line::from("No alert rules.")]
+    } else {
+        app.alerts.rules.iter().map(|rule| Line::from(format!("- {}", rule))).collect()
+    };
+    if let Some((msg, is_error)) = &app.input_state.message {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(msg.clone(), app.theme.message(*is_error))));
+    }
+    let rules = Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title("Current Alert Rules"));
+    f.render_widget(rules, chunks[1]);
+}
+
+fn handle_alert_input(key: KeyEvent, app: &mut App) -> Result<bool, Box<dyn Error>> {
+    match key.code {
+        KeyCode::Esc => app.view_mode = ViewMode::ProcessList,
+        KeyCode::Enter if app.input_state.alert_input.trim().is_empty() => {
+            app.alerts.rules.clear();
+            app.view_mode = ViewMode::ProcessList;
+        }
+        KeyCode::Enter => match AlertRule::parse(&app.input_state.alert_input) {
+            Ok(rule) => {
+                app.alerts.rules.push(rule);
+                app.view_mode = ViewMode::ProcessList;
+            }
+            Err(e) => {
+                app.input_state.message = Some((format!("Invalid alert rule: {}", e), true));
+                app.input_state.message_timeout = Some(std::time::Instant::now() + Duration::from_secs(3));
+            }
+        },
+        KeyCode::Char(c) => app.input_state.alert_input.push(c),
+        KeyCode::Backspace => {
+            app.input_state.alert_input.pop();
+        }
+        _ => {}
     }
+    Ok(false)
 }
 
-fn handle_events(app: &mut App) -> Result<bool, Box<dyn Error>> {
-    if event::poll(Duration::from_millis(100))? {
-        if let Event::Key(key) = event::read()? {
+// Wait up to `timeout` for one input event and handle it. Returns true when the app should quit.
+fn handle_events(app: &mut App, timeout: Duration) -> Result<bool, Box<dyn Error>> {
+    if !event::poll(timeout)? {
+        return Ok(false);
+    }
+    match event::read()? {
+        Event::Mouse(mouse) => handle_mouse(mouse, app),
+        Event::Resize(_, height) => handle_resize(app, height),
+        Event::Key(key) => {
             match app.view_mode {
                 ViewMode::ProcessList => {
                     if handle_process_list_input(key, app)? {
@@ -1104,156 +2052,510 @@ fn handle_events(app: &mut App) -> Result<bool, Box<dyn Error>> {
                     if handle_per_process_graph_input(key, app)? {
                         return Ok(true);
                     }
+                    // The graphed process may have changed
+                    if app.graph_smaps.is_some() {
+                        load_graph_smaps(app);
+                    }
                 }
                 ViewMode::RuleInput => {
                     if handle_script_input(key, app)? {
                     return Ok(true);
                     }
                 }
+                ViewMode::AlertInput => {
+                    if handle_alert_input(key, app)? {
+                        return Ok(true);
+                    }
+                }
                 ViewMode::ProcessLog => {
                     if handle_process_log_input(key, app)? {
                         return Ok(true);
                     }
                 }
                 ViewMode::Help => {
-                    // Handle help input
-                    return Ok(false);
+                    if handle_help_input(key, app)? {
+                        return Ok(true);
+                    }
                 }
-            }
-        }
-    }
-    Ok(false)
-}
-
-fn handle_process_list_input(key: KeyEvent, app: &mut App) -> Result<bool, Box<dyn Error>> {
-    match key.code {
-        KeyCode::Char('a') => {
+                ViewMode::Columns => {
+                    if handle_columns_input(key, app)? {
+                        return Ok(true);
+                    }
+                }
+                ViewMode::Environment => {
+                    if handle_environment_input(key, app)? {
+                        return Ok(true);
+                    }
+                }
+                ViewMode::Sockets => {
+                    if handle_sockets_input(key, app)? {
+                        return Ok(true);
+                    }
+                }
+                ViewMode::Aggregated => {
+                    if handle_aggregated_input(key, app)? {
+                        return Ok(true);
+                    }
+                }
+                ViewMode::SnapshotDiff => {
+                    if handle_snapshot_diff_input(key, app)? {
+                        return Ok(true);
+                    }
+                }
+            }
+        }
+        _ => {}
+    }
+    Ok(false)
+}
+
+// Mouse support: click to select a process row or a statistics tab, wheel to scroll the list
+fn handle_mouse(mouse: MouseEvent, app: &mut App) {
+    match app.view_mode {
+        ViewMode::ProcessList => {
+            let area = app.process_table_area;
+            match mouse.kind {
+                MouseEventKind::Down(MouseButton::Left) => {
+                    // Data rows start below the top border (if any) and the header row
+                    let border = if app.compact { 0 } else { 1 };
+                    let first_row = area.y + border + 1;
+                    let last_row = area.y + area.height.saturating_sub(border);
+                    if mouse.column >= area.x + border
+                        && mouse.column < area.x + area.width
+                        && mouse.row >= first_row
+                        && mouse.row < last_row
+                    {
+                        let index = app.scroll_offset + (mouse.row - first_row) as usize;
+                        if index < app.process_list_len {
+                            app.selected_process_index = index;
+                        }
+                    }
+                }
+                MouseEventKind::ScrollUp => {
+                    app.scroll_offset = app.scroll_offset.saturating_sub(MOUSE_SCROLL_LINES);
+                }
+                MouseEventKind::ScrollDown => {
+                    let max_scroll = app.process_list_len.saturating_sub(app.display_limit);
+                    app.scroll_offset = (app.scroll_offset + MOUSE_SCROLL_LINES).min(max_scroll);
+                }
+                _ => {}
+            }
+        }
+        ViewMode::Statistics => {
+            if let MouseEventKind::Down(MouseButton::Left) = mouse.kind
+                && let Some(tab) = graph::tab_at(&app.graph_data, &app.current_stats_tab, mouse.column, mouse.row)
+            {
+                app.current_stats_tab = tab;
+                app.stats_scroll_offset = 0;
+            }
+        }
+        _ => {}
+    }
+}
+
+const MOUSE_SCROLL_LINES: usize = 3;
+
+// Refreshes a newly started process stays marked in the process list
+const NEW_PROCESS_REFRESHES: u32 = 3;
+
+fn handle_process_list_input(key: KeyEvent, app: &mut App) -> Result<bool, Box<dyn Error>> {
+    if app.jump_active {
+        match key.code {
+            KeyCode::Esc => app.jump_active = false,
+            KeyCode::Enter => jump_to_pid(app),
+            KeyCode::Backspace => {
+                app.input_state.jump_input.pop();
+                app.jump_clear_offered = false;
+            }
+            KeyCode::Char(c) if c.is_ascii_digit() => {
+                app.input_state.jump_input.push(c);
+                app.jump_clear_offered = false;
+            }
+            _ => {}
+        }
+        return Ok(false);
+    }
+    if app.quick_filter_active {
+        match key.code {
+            KeyCode::Esc => {
+                app.quick_filter_active = false;
+                app.quick_filter.clear();
+            }
+            KeyCode::Enter => app.quick_filter_active = false,
+            KeyCode::Backspace => {
+                app.quick_filter.pop();
+            }
+            KeyCode::Char(c) => app.quick_filter.push(c),
+            _ => {}
+        }
+        app.scroll_offset = 0;
+        return Ok(false);
+    }
+    if app.highlight_active {
+        match key.code {
+            KeyCode::Esc => {
+                app.highlight_active = false;
+                app.highlight_query.clear();
+            }
+            KeyCode::Enter => {
+                app.highlight_active = false;
+                jump_to_match(app, true, true);
+            }
+            KeyCode::Backspace => {
+                app.highlight_query.pop();
+            }
+            KeyCode::Char(c) => app.highlight_query.push(c),
+            _ => {}
+        }
+        return Ok(false);
+    }
+    if key.code == KeyCode::Esc && !app.quick_filter.is_empty() {
+        app.quick_filter.clear();
+        app.scroll_offset = 0;
+        return Ok(false);
+    }
+    if key.code == KeyCode::Esc && !app.highlight_query.is_empty() {
+        app.highlight_query.clear();
+        return Ok(false);
+    }
+    let Some(action) = app.keymap.action(keymap::PROCESS_LIST_ACTIONS, key.code) else {
+        return Ok(false);
+    };
+    match action {
+        Action::RestartWithSudo if app.sudo_offered => {
+            app.restart_with_sudo = true;
+            return Ok(true);
+        }
+        Action::QuickFilter => {
+            app.quick_filter_active = true;
+            app.scroll_offset = 0;
+        }
+        Action::Highlight => app.highlight_active = true,
+        Action::Compact => {
+            app.compact = !app.compact;
+            app.redraw = true;
+        }
+        Action::CpuScale => {
+            app.cpu_of_total = !app.cpu_of_total;
+            app.input_state.message = Some((
+                if app.cpu_of_total {
+                    "CPU% now shows share of total capacity (0-100%)".to_string()
+                } else {
+                    "CPU% now shows share of one core (can exceed 100%)".to_string()
+                },
+                false,
+            ));
+            app.input_state.message_timeout = Some(std::time::Instant::now() + Duration::from_secs(3));
+        }
+        Action::ResetView => {
+            // Back to the list as it starts: the configured sort, and no filter, rule, search or
+            // running-only view
+            let (mode, ascending) = app.default_sort.clone();
+            match &mode {
+                Some(mode) => app.process_manager.set_sort(mode, ascending),
+                None => app.process_manager.clear_sort(),
+            }
+            app.sort_mode = mode;
+            app.sort_ascending = ascending;
+            app.filter_mode = None;
+            app.process_manager.set_filter(None, None);
+            app.rule_engine.active_rule = None;
+            app.quick_filter.clear();
+            app.highlight_query.clear();
+            app.running_only = false;
+            app.show_kernel_threads = false;
+            app.scroll_offset = 0;
+            app.selected_process_index = 0;
+            app.input_state.message = Some(("View reset".to_string(), false));
+            app.input_state.message_timeout = Some(std::time::Instant::now() + Duration::from_secs(3));
+        }
+        Action::NextMatch => jump_to_match(app, true, false),
+        Action::PreviousMatch => jump_to_match(app, false, false),
+        Action::GroupByName => {
+            app.view_mode = ViewMode::Aggregated;
+            app.aggregated_scroll = 0;
+        }
+        Action::Snapshot => {
+            app.snapshots_taken += 1;
+            app.snapshots.push_back(Snapshot {
+                name: format!("#{}", app.snapshots_taken),
+                taken_at: Local::now(),
+                processes: app.process_manager.all_processes().cloned().collect(),
+            });
+            if app.snapshots.len() > snapshot::MAX_SNAPSHOTS {
+                app.snapshots.pop_front();
+            }
+            if app.snapshots.len() >= 2 {
+                app.view_mode = ViewMode::SnapshotDiff;
+                app.snapshot_diff_scroll = 0;
+            } else {
+                app.input_state.message = Some((
+                    format!("Snapshot #{} taken - press x again later to compare", app.snapshots_taken),
+                    false,
+                ));
+                app.input_state.message_timeout = Some(std::time::Instant::now() + Duration::from_secs(3));
+            }
+        }
+        Action::JumpToPid => {
+            app.jump_active = true;
+            app.jump_clear_offered = false;
+            app.input_state.jump_input.clear();
+        }
+        Action::ToggleSortOrder => {
             app.sort_ascending = !app.sort_ascending;
             if let Some(mode) = &app.sort_mode {
                 app.process_manager.set_sort(mode, app.sort_ascending);
             }
         }        
-        KeyCode::Char('q') => return Ok(true),
-        KeyCode::Char('s') | KeyCode::Char('S') => app.view_mode = ViewMode::Statistics,
-        KeyCode::Up => {
-            if app.scroll_offset > 0 {
-                app.scroll_offset -= 1;
+        Action::Quit => return Ok(true),
+        Action::Statistics => app.view_mode = ViewMode::Statistics,
+        Action::Pin if app.pinned_pid.is_some() => app.pinned_pid = None,
+        Action::Pin => {
+            let processes = listed_processes(&app.process_manager, &app.rule_engine, &app.quick_filter, app.show_kernel_threads, app.running_only);
+            if let Some(process) = processes.get(app.selected_process_index) {
+                app.pinned_pid = Some(process.pid);
+                app.input_state.message = Some((format!("Pinned process {} ({})", process.pid, process.name), false));
+                app.input_state.message_timeout = Some(std::time::Instant::now() + Duration::from_secs(3));
             }
         }
-        KeyCode::Down => {
-            let process_len = app.process_manager.get_processes().len();
-            if app.scroll_offset < process_len.saturating_sub(app.display_limit) {
-                app.scroll_offset += 1;
+        Action::Up | Action::Down | Action::PageUp | Action::PageDown | Action::Top | Action::Bottom => {
+            // The selection moves and the list scrolls to keep it on screen, so every action on
+            // the selected process applies to the row the user sees highlighted
+            let last = app.process_list_len.saturating_sub(1);
+            scroll_by_action(action, &mut app.selected_process_index, app.display_limit, last);
+            scroll_into_view(&mut app.scroll_offset, app.display_limit, app.selected_process_index);
+            // A pinned selection would snap back on the next draw, so the pin moves along
+            if app.pinned_pid.is_some() {
+                let processes = listed_processes(&app.process_manager, &app.rule_engine, &app.quick_filter, app.show_kernel_threads, app.running_only);
+                app.pinned_pid = processes.get(app.selected_process_index).map(|p| p.pid).or(app.pinned_pid);
             }
         }
-        KeyCode::Char('1') => app.view_mode = ViewMode::FilterSort,
-        KeyCode::Char('2') => app.view_mode = ViewMode::ChangeNice,
-        KeyCode::Char('3') => app.view_mode = ViewMode::KillStop,
-        KeyCode::Char('4') => {
+        Action::FilterSortMenu => app.view_mode = ViewMode::FilterSort,
+        Action::ChangeNiceMenu => {
+            scroll_into_view(&mut app.change_nice_scroll_offset, PROCESS_TABLE_HEIGHT - 2, app.selected_process_index);
+            app.view_mode = ViewMode::ChangeNice;
+        }
+        Action::KillStopMenu => {
+            scroll_into_view(&mut app.scroll_offset, app.display_limit, app.selected_process_index);
+            app.view_mode = ViewMode::KillStop;
+        }
+        Action::PerProcessGraph => {
             app.view_mode = ViewMode::PerProcessGraph;
             app.selected_process_index = 0;
             app.per_process_graph_scroll_offset = 0;
             app.selected_process_for_graph = None;
+            app.graph_filter_input.clear();
+            app.graph_filter_active = false;
+        }
+        Action::ProcessLog => app.view_mode = ViewMode::ProcessLog,
+        Action::Help => app.view_mode = ViewMode::Help,
+        Action::ColumnsMenu => {
+            app.view_mode = ViewMode::Columns;
+            app.column_menu_index = 0;
+            app.columns_on_open = app.columns.visible_keys();
+        }
+        Action::SortByCpu | Action::SortByMemory => {
+            // Heaviest first, from the top of the list
+            let mode = if action == Action::SortByCpu { "cpu" } else { "mem" };
+            app.sort_mode = Some(mode.to_string());
+            app.sort_ascending = false;
+            app.process_manager.set_sort(mode, false);
+            app.scroll_offset = 0;
+            app.selected_process_index = 0;
+        }
+        Action::ToggleRunningOnly => {
+            app.running_only = !app.running_only;
+            app.scroll_offset = 0;
+        }
+        Action::ToggleKernelThreads => {
+            app.show_kernel_threads = !app.show_kernel_threads;
+            app.scroll_offset = 0;
+            app.input_state.message = Some((
+                format!("Kernel threads {}", if app.show_kernel_threads { "shown" } else { "hidden" }),
+                false,
+            ));
+            app.input_state.message_timeout = Some(std::time::Instant::now() + Duration::from_secs(3));
+        }
+        Action::Watch if app.watch_exit.is_some() => app.watch_exit = None,
+        Action::Watch => {
+            let processes = listed_processes(&app.process_manager, &app.rule_engine, &app.quick_filter, app.show_kernel_threads, app.running_only);
+            if let Some(pid) = app.watched_pid.take() {
+                app.input_state.message = Some((format!("Stopped watching process {}", pid), false));
+            } else if let Some(process) = processes.get(app.selected_process_index) {
+                app.watched_pid = Some(process.pid);
+                app.input_state.message = Some((format!("Watching process {} ({}) until it exits", process.pid, process.name), false));
+            }
+            app.input_state.message_timeout = Some(std::time::Instant::now() + Duration::from_secs(3));
+        }
+        Action::CopyPid | Action::CopyCommand => {
+            let processes = listed_processes(&app.process_manager, &app.rule_engine, &app.quick_filter, app.show_kernel_threads, app.running_only);
+            if let Some(process) = processes.get(app.selected_process_index) {
+                let (pid, cmdline, name) = (process.pid, process.cmdline.clone(), process.name.clone());
+                copy_process_field(app, action, pid, &cmdline, &name);
+            }
+        }
+        Action::Environment => {
+            let processes = listed_processes(&app.process_manager, &app.rule_engine, &app.quick_filter, app.show_kernel_threads, app.running_only);
+            if let Some(process) = processes.get(app.selected_process_index) {
+                let vars = process::read_environ(app.process_manager.proc_root(), process.pid).map_err(|e| match e.kind() {
+                    std::io::ErrorKind::PermissionDenied => {
+                        "Permission denied (only root or the process owner can read its environment)".to_string()
+                    }
+                    std::io::ErrorKind::NotFound => "The process has exited".to_string(),
+                    _ => e.to_string(),
+                });
+                app.environ_view = Some(EnvironView {
+                    pid: process.pid,
+                    name: process.name.clone(),
+                    vars,
+                    scroll: 0,
+                    reveal_secrets: false,
+                });
+                app.view_mode = ViewMode::Environment;
+            }
+        }
+        Action::Sockets => {
+            let processes = listed_processes(&app.process_manager, &app.rule_engine, &app.quick_filter, app.show_kernel_threads, app.running_only);
+            if let Some(process) = processes.get(app.selected_process_index) {
+                app.sockets_view = Some(SocketsView::load(app.process_manager.proc_root(), process.pid, process.name.clone()));
+                app.view_mode = ViewMode::Sockets;
+            }
         }
-        KeyCode::Char('5') => app.view_mode = ViewMode::ProcessLog,
-        KeyCode::Char('6') => app.view_mode = ViewMode::Help,
         _ => {}
     }
     Ok(false)
 }
 
 fn handle_statistics_input(key: KeyEvent, app: &mut App) -> Result<bool, Box<dyn Error>> {
-    match key.code {
-        KeyCode::Char('q') | KeyCode::Esc | KeyCode::Char('s') | KeyCode::Char('S') => {
+    let action = app.keymap.action(keymap::STATISTICS_ACTIONS, key.code)
+        .or((key.code == KeyCode::Esc).then_some(Action::Statistics));
+    let Some(action) = action else {
+        return Ok(false);
+    };
+    let scrollable = graph::is_scrollable(&app.current_stats_tab);
+    match action {
+        Action::Quit | Action::Statistics => {
             app.view_mode = ViewMode::ProcessList;
             app.stats_scroll_offset = 0;  // Reset scroll when leaving statistics view
             app.current_stats_tab = StatisticsTab::Graphs;  // Reset to default tab
+            app.maximized_graph = None;
         }
-        KeyCode::Char('1') => {
-            app.current_stats_tab = StatisticsTab::Graphs;
-            app.stats_scroll_offset = 0;  // Reset scroll when switching tabs
-        }
-        KeyCode::Char('2') => {
-            app.current_stats_tab = StatisticsTab::Overview;
-            app.stats_scroll_offset = 0;  // Reset scroll when switching tabs
-        }
-        KeyCode::Char('3') => {
-            app.current_stats_tab = StatisticsTab::CPU;
-            app.stats_scroll_offset = 0;  // Reset scroll when switching tabs
-        }
-        KeyCode::Char('4') => {
-            app.current_stats_tab = StatisticsTab::Memory;
-            app.stats_scroll_offset = 0;  // Reset scroll when switching tabs
-        }
-        KeyCode::Char('5') => {
-            app.current_stats_tab = StatisticsTab::Disk;
-            app.stats_scroll_offset = 0;  // Reset scroll when switching tabs
-        }
-        KeyCode::Char('6') => {
-            app.current_stats_tab = StatisticsTab::Processes;
-            app.stats_scroll_offset = 0;  // Reset scroll when switching tabs
-        }
-        KeyCode::Char('7') => {
-            app.current_stats_tab = StatisticsTab::Advanced;
-            app.stats_scroll_offset = 0;  // Reset scroll when switching tabs
-        }
-        KeyCode::Char('8') => {
-            app.current_stats_tab = StatisticsTab::Help;
-            app.stats_scroll_offset = 0;  // Reset scroll when switching tabs
+        Action::Up if scrollable => {
+            // Smooth scrolling - move up by 1/4 of the viewport
+            let scroll_amount = 3;
+            app.stats_scroll_offset = app.stats_scroll_offset.saturating_sub(scroll_amount);
         }
-        KeyCode::Up => {
-            if app.current_stats_tab == StatisticsTab::CPU {
-                // Smooth scrolling - move up by 1/4 of the viewport
-                let scroll_amount = 3;
-                app.stats_scroll_offset = app.stats_scroll_offset.saturating_sub(scroll_amount);
-            }
+        Action::Down if scrollable => {
+            // Smooth scrolling - move down by 1/4 of the viewport
+            let scroll_amount = 3;
+            app.stats_scroll_offset = app.stats_scroll_offset.saturating_add(scroll_amount);
         }
-        KeyCode::Down => {
-            if app.current_stats_tab == StatisticsTab::CPU {
-                // Smooth scrolling - move down by 1/4 of the viewport
-                let scroll_amount = 3;
-                app.stats_scroll_offset = app.stats_scroll_offset.saturating_add(scroll_amount);
-            }
+        Action::PageUp if scrollable => {
+            // Page up - move by half the viewport
+            let scroll_amount = 10;
+            app.stats_scroll_offset = app.stats_scroll_offset.saturating_sub(scroll_amount);
         }
-        KeyCode::PageUp => {
-            if app.current_stats_tab == StatisticsTab::CPU {
-                // Page up - move by half the viewport
-                let scroll_amount = 10;
-                app.stats_scroll_offset = app.stats_scroll_offset.saturating_sub(scroll_amount);
-            }
+        Action::PageDown if scrollable => {
+            // Page down - move by half the viewport
+            let scroll_amount = 10;
+            app.stats_scroll_offset = app.stats_scroll_offset.saturating_add(scroll_amount);
         }
-        KeyCode::PageDown => {
-            if app.current_stats_tab == StatisticsTab::CPU {
-                // Page down - move by half the viewport
-                let scroll_amount = 10;
-                app.stats_scroll_offset = app.stats_scroll_offset.saturating_add(scroll_amount);
+        Action::Top if scrollable => {
+            // Jump to top
+            app.stats_scroll_offset = 0;
         }
+        Action::Bottom if scrollable => {
+            // Jump to bottom (will be bounded by max_scroll in the render function)
+            app.stats_scroll_offset = usize::MAX;
         }
-        KeyCode::Home => {
-            if app.current_stats_tab == StatisticsTab::CPU {
-                // Jump to top
-                app.stats_scroll_offset = 0;
-            }
+        Action::ExportGraphs => export_graph_history(app),
+        Action::MaximizeCpuGraph | Action::MaximizeMemoryGraph
+            if app.current_stats_tab == StatisticsTab::Graphs =>
+        {
+            let kind = if action == Action::MaximizeCpuGraph { graph::GraphKind::Cpu } else { graph::GraphKind::Memory };
+            // The same key again restores the normal layout
+            app.maximized_graph = if app.maximized_graph == Some(kind) { None } else { Some(kind) };
         }
-        KeyCode::End => {
-            if app.current_stats_tab == StatisticsTab::CPU {
-                // Jump to bottom (will be bounded by max_scroll in the render function)
-                app.stats_scroll_offset = usize::MAX;
+        // The battery tab has no action while it's hidden
+        action => {
+            if let Some(tab) = graph::tab_for(&app.graph_data, action) {
+                app.current_stats_tab = tab;
+                app.stats_scroll_offset = 0;  // Reset scroll when switching tabs
             }
         }
-        _ => {}
     }
     Ok(false)
 }
 
+// One-line report on a watched process that exited, from its exit log entry when there is one
+fn watch_summary(pid: u32, entry: Option<&ProcessExitLogEntry>, memory_unit: MemoryUnit) -> String {
+    match entry {
+        Some(entry) => format!(
+            "Process {} ({}) exited at {} after {}; peak CPU {:.1}%, peak memory {}",
+            pid,
+            entry.name,
+            entry.exit_time.format("%H:%M:%S"),
+            crate::util::format_duration(entry.uptime_secs),
+            entry.peak_cpu,
+            format_bytes(entry.peak_mem, memory_unit),
+        ),
+        None => format!("Process {} exited", pid),
+    }
+}
+
+// Copy a process's PID (Action::CopyPid) or command line to the clipboard. Without a clipboard
+// the text goes to the message line so it can still be copied from the terminal.
+fn copy_process_field(app: &mut App, action: Action, pid: u32, cmdline: &str, name: &str) {
+    let (what, text) = match action {
+        Action::CopyPid => ("PID", pid.to_string()),
+        // Kernel threads have no command line; their name is the closest thing
+        _ if cmdline.is_empty() => ("name", name.to_string()),
+        _ => ("command line", cmdline.to_string()),
+    };
+    let message = match crate::util::copy_to_clipboard(&text) {
+        Ok(()) => format!("Copied {} of process {} to the clipboard: {}", what, pid, text),
+        Err(e) => format!("No clipboard ({}); {} of process {}: {}", e, what, pid, text),
+    };
+    app.input_state.message = Some((message, false));
+    app.input_state.message_timeout = Some(std::time::Instant::now() + Duration::from_secs(5));
+}
+
+// Dump the statistics graph history to a timestamped CSV in the working directory
+fn export_graph_history(app: &mut App) {
+    let path = std::path::PathBuf::from(format!("graph_history_{}.csv", chrono::Local::now().format("%Y%m%d_%H%M%S")));
+    app.input_state.message = Some(match app.graph_data.export_csv(&path) {
+        Ok(()) => {
+            let shown = std::fs::canonicalize(&path).unwrap_or(path);
+            (format!("Exported graph history to {}", shown.display()), false)
+        }
+        Err(e) => (format!("Could not write {}: {}", path.display(), e), true),
+    });
+    app.input_state.message_timeout = Some(std::time::Instant::now() + Duration::from_secs(5));
+}
+
+// Views without a message line of their own show the current message on the bottom row
+fn draw_message_overlay(f: &mut Frame, app: &App) {
+    if let Some((msg, is_error)) = &app.input_state.message {
+        let size = f.size();
+        let area = Rect::new(size.x, size.y + size.height.saturating_sub(1), size.width, size.height.min(1));
+        f.render_widget(ratatui::widgets::Clear, area);
+        f.render_widget(Paragraph::new(msg.as_str()).style(app.theme.message(*is_error)), area);
+    }
+}
+
 fn handle_filter_sort_input(key: KeyEvent, app: &mut App) -> Result<bool, Box<dyn Error>> {
     match key.code {
         KeyCode::Char('1') => app.view_mode = ViewMode::Sort,
         KeyCode::Char('2') => app.view_mode = ViewMode::Filter,
         KeyCode::Char('x') => {
             app.input_state.rule_input.clear();
+            update_rule_preview(app);
             app.view_mode = ViewMode::RuleInput;
         }
+        KeyCode::Char('a') | KeyCode::Char('A') => {
+            app.input_state.alert_input.clear();
+            app.view_mode = ViewMode::AlertInput;
+        }
         
         KeyCode::Backspace | KeyCode::Esc => app.view_mode = ViewMode::ProcessList,
         _ => {}
@@ -1293,6 +2595,31 @@ fn handle_sort_input(key: KeyEvent, app: &mut App) -> Result<bool, Box<dyn Error
             app.process_manager.set_sort("cpu", app.sort_ascending);
             app.view_mode = ViewMode::ProcessList;
         }
+        KeyCode::Char('7') => {
+            app.sort_mode = Some("threads".to_string());
+            app.process_manager.set_sort("threads", app.sort_ascending);
+            app.view_mode = ViewMode::ProcessList;
+        }
+        KeyCode::Char('8') => {
+            app.sort_mode = Some("mem_percent".to_string());
+            app.process_manager.set_sort("mem_percent", app.sort_ascending);
+            app.view_mode = ViewMode::ProcessList;
+        }
+        KeyCode::Char('9') => {
+            app.sort_mode = Some("uptime".to_string());
+            app.process_manager.set_sort("uptime", app.sort_ascending);
+            app.view_mode = ViewMode::ProcessList;
+        }
+        KeyCode::Char('0') => {
+            app.sort_mode = Some("swap".to_string());
+            app.process_manager.set_sort("swap", app.sort_ascending);
+            app.view_mode = ViewMode::ProcessList;
+        }
+        KeyCode::Char('t') => {
+            app.sort_mode = Some("cpu_time".to_string());
+            app.process_manager.set_sort("cpu_time", app.sort_ascending);
+            app.view_mode = ViewMode::ProcessList;
+        }
         KeyCode::Char('a') => {
             app.sort_ascending = !app.sort_ascending;
             if let Some(mode) = &app.sort_mode {
@@ -1329,6 +2656,11 @@ fn handle_filter_input(key: KeyEvent, app: &mut App) -> Result<bool, Box<dyn Err
                     app.input_state.filter_input.clear();
                     app.view_mode = ViewMode::FilterInput;
                 }
+                KeyCode::Char('5') => {
+                    app.filter_mode = Some("container".to_string());
+                    app.input_state.filter_input.clear();
+                    app.view_mode = ViewMode::FilterInput;
+                }
                 KeyCode::Esc => {
                     app.filter_mode = None;
                     app.input_state.filter_input.clear();
@@ -1355,15 +2687,16 @@ fn handle_filter_input(key: KeyEvent, app: &mut App) -> Result<bool, Box<dyn Err
                 KeyCode::Backspace => {
                     app.input_state.filter_input.pop();
                 }
-                KeyCode::Enter => {
-                    if !app.input_state.filter_input.is_empty() {
+                KeyCode::Enter
+                    if !app.input_state.filter_input.is_empty() => {
                         app.process_manager.set_filter(
                             app.filter_mode.clone(),
                             Some(app.input_state.filter_input.clone())
                         );
+                        let text = format!("Applied filter {}={}", app.filter_mode.as_deref().unwrap_or("?"), app.input_state.filter_input);
+                        app.log_action(&text, false);
                         app.view_mode = ViewMode::ProcessList;
                     }
-                }
                 KeyCode::Left => {
                     app.view_mode = ViewMode::Filter;
                     app.input_state.filter_input.clear();
@@ -1383,167 +2716,649 @@ fn handle_filter_input(key: KeyEvent, app: &mut App) -> Result<bool, Box<dyn Err
 }
 
 fn handle_kill_stop_input(key: KeyEvent, app: &mut App) -> Result<bool, Box<dyn Error>> {
-    let processes = app.process_manager.get_processes();
+    let processes = listed_processes(&app.process_manager, &app.rule_engine, &app.quick_filter, app.show_kernel_threads, app.running_only);
     match app.kill_stop_input_state {
         KillStopInputState::SelectingPid => {
             match key.code {
-                KeyCode::Up => {
-                    if app.selected_process_index > 0 {
-                        app.selected_process_index -= 1;
-                        if app.selected_process_index < app.scroll_offset {
-                            app.scroll_offset = app.selected_process_index;
-                        }
-                    }
-                }
-                KeyCode::Down => {
-                    if app.selected_process_index + 1 < processes.len() {
-                        app.selected_process_index += 1;
-                        let bottom = app.scroll_offset + app.display_limit;
-                        if app.selected_process_index >= bottom {
-                            app.scroll_offset = app.selected_process_index - app.display_limit + 1;
-                        }
-                    }
-                }
-                KeyCode::Enter => {
-                    if !processes.is_empty() {
+                KeyCode::Enter
+                    if !processes.is_empty() => {
                         app.kill_stop_input_state = KillStopInputState::EnteringAction;
                         app.input_state.pid_input.clear();
                         app.input_state.message = None;
                     }
+                code if app.keymap.is(Action::ByName, code) => {
+                    app.kill_stop_input_state = KillStopInputState::EnteringName;
+                    app.input_state.name_input.clear();
+                    app.input_state.message = None;
+                }
+                code if app.keymap.is(Action::ByPort, code) => {
+                    app.kill_stop_input_state = KillStopInputState::EnteringPort;
+                    app.input_state.port_input.clear();
+                    app.input_state.message = None;
                 }
                 KeyCode::Esc => {
-                    app.view_mode = ViewMode::ProcessList;
-                    app.input_state = InputState::default();
+                    close_menu(app);
                     app.kill_stop_input_state = KillStopInputState::SelectingPid;
                 }
-                _ => {}
+                code => {
+                    if let Some(action) = app.keymap.action(keymap::NAVIGATION_ACTIONS, code) {
+                        let last = processes.len().saturating_sub(1);
+                        scroll_by_action(action, &mut app.selected_process_index, app.display_limit, last);
+                        scroll_into_view(&mut app.scroll_offset, app.display_limit, app.selected_process_index);
+                    }
+                }
+            }
+        }
+        KillStopInputState::EnteringName => {
+            if let Some(name) = handle_name_input(key, app) {
+                app.kill_stop_input_state = if name.is_some() {
+                    KillStopInputState::EnteringAction
+                } else {
+                    KillStopInputState::SelectingPid
+                };
+                app.bulk_name = name;
+            }
+        }
+        KillStopInputState::EnteringPort => match key.code {
+            KeyCode::Char(c) if c.is_ascii_digit() => app.input_state.port_input.push(c),
+            KeyCode::Backspace => {
+                app.input_state.port_input.pop();
+            }
+            KeyCode::Enter => select_port_owner(app),
+            KeyCode::Esc => app.kill_stop_input_state = KillStopInputState::SelectingPid,
+            _ => {}
+        },
+        KillStopInputState::EnteringAction if app.bulk_name.is_some() => {
+            let name = app.bulk_name.clone().unwrap_or_default();
+            match app.keymap.action(keymap::KILL_STOP_ACTIONS, key.code) {
+                Some(action @ (Action::Kill | Action::Stop | Action::Continue | Action::Terminate)) => {
+                    // Always confirm, since this can hit many processes
+                    let count = app.process_manager.pids_named(&name).len();
+                    app.input_state.message = Some((
+                        format!("Press y to {} {} processes named '{}', any other key to cancel", signal_verb(action), count, name),
+                        false
+                    ));
+                    app.input_state.message_timeout = None;
+                    app.pending_kill_action = Some((action, KillTarget::Name(name)));
+                    app.kill_stop_input_state = KillStopInputState::ConfirmingAction;
+                }
+                Some(_) => {
+                    app.input_state.message = Some(("Only kill, stop, continue and terminate can be applied by name".to_string(), true));
+                }
+                None if key.code == KeyCode::Esc => {
+                    app.kill_stop_input_state = KillStopInputState::SelectingPid;
+                    app.bulk_name = None;
+                }
+                None => {}
             }
         }
         KillStopInputState::EnteringAction => {
-            match key.code {
-                KeyCode::Char('k') | KeyCode::Char('s') | KeyCode::Char('c') | KeyCode::Char('t') => {
-                    if let Some(process) = processes.get(app.selected_process_index) {
-                        let action = match key.code {
-                            KeyCode::Char('k') => {
-                                match app.process_manager.kill_process(process.pid) {
-                                    Ok(_) => Some(("Successfully killed process".to_string(), false)),
-                                    Err(e) => Some((format!("Error killing process: {}", e), true)),
-                                }
-                            }
-                            KeyCode::Char('s') => {
-                                match app.process_manager.stop_process(process.pid) {
-                                    Ok(_) => Some(("Successfully stopped process".to_string(), false)),
-                                    Err(e) => Some((format!("Error stopping process: {}", e), true)),
-                                }
-                            }
-                            KeyCode::Char('c') => {
-                                match app.process_manager.continue_process(process.pid) {
-                                    Ok(_) => Some(("Successfully continued process".to_string(), false)),
-                                    Err(e) => Some((format!("Error continuing process: {}", e), true)),
-                                }
-                            }
-                            KeyCode::Char('t') => {
-                                match app.process_manager.terminate_process(process.pid) {
-                                    Ok(_) => Some(("Successfully sent termination request to process".to_string(), false)),
-                                    Err(e) => Some((format!("Error sending termination request: {}", e), true)),
-                                }
-                            }
-                            _ => None,
-                        };
-
-                        if let Some((msg, is_error)) = action {
+            match app.keymap.action(keymap::KILL_STOP_ACTIONS, key.code) {
+                Some(action) => {
+                    let selected = processes
+                        .get(app.selected_process_index)
+                        .map(|p| (p.pid, p.state == ProcessState::Zombie, p.parent_pid));
+                    if let Some((pid, zombie, parent_pid)) = selected {
+                        if zombie && matches!(action, Action::Kill | Action::Terminate | Action::TerminateThenKill | Action::KillTree) {
+                            offer_zombie_parent(app, action, pid, parent_pid);
+                        } else if action == Action::Restart {
+                            // Always confirm: the relaunched copy may not match the original
+                            app.pending_kill_action = Some((action, KillTarget::Pid(pid)));
+                            app.kill_stop_input_state = KillStopInputState::ConfirmingAction;
                             app.input_state.message = Some((
-                                format!("{} {}", msg, process.pid),
-                                is_error
+                                format!(
+                                    "Press y to restart process {}. It is relaunched as you, detached from this terminal, and may fail if its environment or privileges can't be reproduced",
+                                    pid
+                                ),
+                                false
                             ));
-                            app.input_state.message_timeout = Some(std::time::Instant::now() + Duration::from_secs(1));
-                            app.kill_stop_input_state = KillStopInputState::SelectingPid;
+                            app.input_state.message_timeout = None;
+                        } else if app.confirm_before_kill && matches!(action, Action::Kill | Action::Terminate | Action::TerminateThenKill | Action::KillTree) {
+                            let verb = match action {
+                                Action::Kill => "kill",
+                                Action::Terminate => "terminate",
+                                Action::KillTree => "kill the whole tree of",
+                                _ => "terminate (then kill)",
+                            };
+                            app.pending_kill_action = Some((action, KillTarget::Pid(pid)));
+                            app.kill_stop_input_state = KillStopInputState::ConfirmingAction;
+                            app.input_state.message = Some((
+                                format!("Press y to {} process {}, any other key to cancel", verb, pid),
+                                false
+                            ));
+                            app.input_state.message_timeout = None;
+                        } else {
+                            run_kill_stop_action(app, action, pid);
                         }
                     }
                 }
-                KeyCode::Esc => {
+                None if key.code == KeyCode::Esc => {
                     app.kill_stop_input_state = KillStopInputState::SelectingPid;
                     app.input_state.pid_input.clear();
                 }
                 _ => {}
             }
         }
+        KillStopInputState::ConfirmingAction => {
+            let pending = app.pending_kill_action.take();
+            app.kill_stop_input_state = KillStopInputState::SelectingPid;
+            app.bulk_name = None;
+            match (key.code, pending) {
+                (KeyCode::Char('y') | KeyCode::Char('Y'), Some((action, KillTarget::Pid(pid)))) => run_kill_stop_action(app, action, pid),
+                (KeyCode::Char('y') | KeyCode::Char('Y'), Some((action, KillTarget::Name(name)))) => run_signal_by_name(app, action, &name),
+                (KeyCode::Char('y') | KeyCode::Char('Y'), Some((_, KillTarget::ZombieParent(parent)))) => remind_zombie_parent(app, parent),
+                _ => app.input_state.message = Some(("Cancelled".to_string(), false)),
+            }
+        }
     }
     Ok(false)
 }
 
-fn handle_change_nice_input(key: KeyEvent, app: &mut App) -> Result<bool, Box<dyn Error>> {
-    let processes = app.process_manager.get_processes();
-    match app.nice_input_state {
-        NiceInputState::SelectingPid => {
-            match key.code {
-                KeyCode::Up => {
-                    if app.selected_process_index > 0 {
-                        app.selected_process_index -= 1;
-                        if app.selected_process_index < app.change_nice_scroll_offset {
-                            app.change_nice_scroll_offset = app.selected_process_index;
-                        }
-                    }
+// Select the process listening on the port typed in the kill menu and ask for an action, for
+// the "address already in use" case
+fn select_port_owner(app: &mut App) {
+    app.kill_stop_input_state = KillStopInputState::SelectingPid;
+    let Ok(port) = app.input_state.port_input.parse::<u16>() else {
+        app.input_state.message = Some((format!("'{}' is not a port number", app.input_state.port_input), true));
+        return;
+    };
+    let owners = crate::sockets::processes_on_port(app.process_manager.proc_root(), port);
+    let processes = listed_processes(&app.process_manager, &app.rule_engine, &app.quick_filter, app.show_kernel_threads, app.running_only);
+    let (mut text, selected) = match (owners.protocol, owners.pids.first()) {
+        (None, _) => (format!("Nothing is listening on port {}", port), None),
+        (Some(protocol), None) => (format!("Port {}/{} is in use, but not by a process we can see", port, protocol.label()), None),
+        (Some(protocol), Some(&pid)) => {
+            let mut text = match processes.iter().position(|p| p.pid == pid) {
+                Some(index) => (format!("Port {}/{} is held by process {} ({})", port, protocol.label(), pid, processes[index].name), Some(index)),
+                None => (format!("Port {}/{} is held by process {}, which the current filters hide", port, protocol.label(), pid), None),
+            };
+            if owners.pids.len() > 1 {
+                let others: Vec<String> = owners.pids[1..].iter().map(|pid| pid.to_string()).collect();
+                text.0.push_str(&format!(" (also {})", others.join(", ")));
+            }
+            text
+        }
+    };
+    if selected.is_none() && owners.unreadable > 0 && !crate::util::is_root() {
+        text.push_str(&format!("; {} processes could not be inspected without root", owners.unreadable));
+    }
+    match selected {
+        Some(index) => {
+            app.selected_process_index = index;
+            scroll_into_view(&mut app.scroll_offset, app.display_limit, index);
+            app.kill_stop_input_state = KillStopInputState::EnteringAction;
+            app.input_state.message = Some((format!("{} - choose an action", text), false));
+        }
+        None => app.input_state.message = Some((text, true)),
+    }
+    app.input_state.message_timeout = None;
+}
+
+// Send the signal for a kill/stop menu action and report the outcome on the message line
+fn run_kill_stop_action(app: &mut App, action: Action, pid: u32) {
+    let mut denied = false;
+    let result = match action {
+        Action::Kill => {
+            match app.process_manager.kill_process(pid) {
+                Ok(_) => Some(("Successfully killed process".to_string(), false)),
+                Err(e) => {
+                    denied = is_permission_denied(&e);
+                    Some((format!("Error killing process: {}", e), true))
                 }
-                KeyCode::Down => {
-                    if app.selected_process_index + 1 < processes.len() {
-                        app.selected_process_index += 1;
-                        let bottom = app.change_nice_scroll_offset + (PROCESS_TABLE_HEIGHT - 2);
-                        if app.selected_process_index >= bottom {
-                            app.change_nice_scroll_offset += 1;
-                        }
-                    }
+            }
+        }
+        Action::Stop => {
+            match app.process_manager.stop_process(pid) {
+                Ok(_) => Some(("Successfully stopped process".to_string(), false)),
+                Err(e) => {
+                    denied = is_permission_denied(&e);
+                    Some((format!("Error stopping process: {}", e), true))
                 }
-                KeyCode::Enter => {
-                    if !processes.is_empty() {
-                        app.nice_input_state = NiceInputState::EnteringNice;
-                        app.input_state.nice_input.clear();
-                        app.input_state.message = None;
-                    }
+            }
+        }
+        Action::Continue => {
+            match app.process_manager.continue_process(pid) {
+                Ok(_) => Some(("Successfully continued process".to_string(), false)),
+                Err(e) => {
+                    denied = is_permission_denied(&e);
+                    Some((format!("Error continuing process: {}", e), true))
                 }
-                KeyCode::Esc => {
-                    app.view_mode = ViewMode::ProcessList;
-                    app.input_state = InputState::default();
-                    app.nice_input_state = NiceInputState::SelectingPid;
+            }
+        }
+        Action::Terminate => {
+            match app.process_manager.terminate_process(pid) {
+                Ok(_) => Some(("Successfully sent termination request to process".to_string(), false)),
+                Err(e) => {
+                    denied = is_permission_denied(&e);
+                    Some((format!("Error sending termination request: {}", e), true))
                 }
-                _ => {}
             }
         }
-        NiceInputState::EnteringNice => {
-            match key.code {
-                KeyCode::Char(c) => {
-                    if c.is_ascii_digit() || (c == '-' && app.input_state.nice_input.is_empty()) {
-                        app.input_state.nice_input.push(c);
-                    }
+        Action::KillTree => {
+            match app.process_manager.kill_tree(pid, libc::SIGKILL) {
+                Ok(summary) if summary.failed.is_empty() => {
+                    Some((format!("Killed {} processes in the tree of process", summary.succeeded), false))
                 }
-                KeyCode::Backspace => {
-                    app.input_state.nice_input.pop();
+                Ok(summary) => {
+                    denied = summary.permission_denied();
+                    Some((format!("Could not kill the whole tree ({}) of process", summary.describe()), true))
                 }
-                KeyCode::Enter => {
-                    if !app.input_state.nice_input.is_empty() {
-                        if let (Some(proc), Ok(nice)) = (
-                            processes.get(app.selected_process_index),
-                            app.input_state.nice_input.parse::<i32>(),
-                        ) {
-                            if nice >= -20 && nice <= 19 {
-                                match app.process_manager.set_niceness(proc.pid, nice) {
-                                    Ok(_) => {
-                                        app.input_state.message = Some((
-                                            format!("Successfully changed nice value of process {} to {}", proc.pid, nice),
-                                            false
-                                        ));
-                                        app.input_state.message_timeout = Some(std::time::Instant::now() + Duration::from_secs(1));
-                                        app.nice_input_state = NiceInputState::SelectingPid;
-                                        app.input_state.nice_input.clear();
-                                    }
-                                    Err(e) => {
-                                        app.input_state.message = Some((
-                                            format!("Error changing nice value: {}", e),
-                                            true
-                                        ));
-                                        app.nice_input_state = NiceInputState::SelectingPid;
+                Err(e) => {
+                    denied = is_permission_denied(&e);
+                    Some((format!("Cannot kill the tree ({}) of process", e), true))
+                }
+            }
+        }
+        Action::StopTree | Action::ContinueTree => {
+            let (result, verb, done) = if action == Action::StopTree {
+                (app.process_manager.stop_tree(pid), "suspend", "Suspended")
+            } else {
+                (app.process_manager.continue_tree(pid), "resume", "Resumed")
+            };
+            match result {
+                Ok(summary) if summary.failed.is_empty() => {
+                    Some((format!("{} {} processes in the tree of process", done, summary.succeeded), false))
+                }
+                Ok(summary) => {
+                    denied = summary.permission_denied();
+                    Some((format!("Could not {} the whole tree ({}) of process", verb, summary.describe()), true))
+                }
+                Err(e) => {
+                    denied = is_permission_denied(&e);
+                    Some((format!("Cannot {} the tree ({}) of process", verb, e), true))
+                }
+            }
+        }
+        Action::TerminateThenKill => {
+            let start_time = app.process_manager.process_identity(pid).map(|(start_time, _)| start_time);
+            match (start_time, app.process_manager.terminate_then_kill(pid, app.kill_grace_period)) {
+                (Some(start_time), Ok(deadline)) => {
+                    app.pending_terminations.retain(|pending| pending.pid != pid);
+                    app.pending_terminations.push(PendingTermination { pid, start_time, deadline, restart: None });
+                    Some((format!("Sent SIGTERM (force-kill in {:.1}s if still running) to process", app.kill_grace_period.as_secs_f64()), false))
+                }
+                (_, Err(e)) => {
+                    denied = is_permission_denied(&e);
+                    Some((format!("Error sending termination request: {}", e), true))
+                }
+                (None, Ok(_)) => Some(("Sent SIGTERM to exiting process".to_string(), false)),
+            }
+        }
+        Action::Restart => {
+            // The start time tells the process's exit apart from a new one reusing its PID
+            match app.process_manager.process_identity(pid).map(|(start_time, _)| start_time) {
+                None => Some(("Could not restart (not in the process table yet) process".to_string(), true)),
+                Some(start_time) => match app.process_manager.begin_restart(pid) {
+                    Ok(restart) => {
+                        let deadline = std::time::Instant::now() + app.kill_grace_period;
+                        app.pending_terminations.retain(|pending| pending.pid != pid);
+                        app.pending_terminations.push(PendingTermination { pid, start_time, deadline, restart: Some(restart) });
+                        Some((format!("Sent SIGTERM (relaunch once it exits, within {:.1}s) to process", app.kill_grace_period.as_secs_f64()), false))
+                    }
+                    Err(e) => {
+                        denied = is_permission_denied(&e);
+                        Some((format!("Could not restart ({}) process", e), true))
+                    }
+                },
+            }
+        }
+        _ => None,
+    };
+
+    if let Some((msg, is_error)) = result {
+        app.input_state.message = Some((
+            format!("{} {}", msg, pid),
+            is_error
+        ));
+        app.log_message();
+        app.input_state.message_timeout = Some(std::time::Instant::now() + Duration::from_secs(1));
+        app.kill_stop_input_state = KillStopInputState::SelectingPid;
+        if denied {
+            offer_sudo(app);
+        }
+    }
+}
+
+// A zombie has already exited, so signalling it does nothing; only its parent can clear it by
+// reaping it. Say so, select the parent in the list and offer to nudge it with SIGCHLD.
+fn offer_zombie_parent(app: &mut App, action: Action, pid: u32, parent_pid: Option<u32>) {
+    let Some(parent) = parent_pid.filter(|&parent| parent > 0) else {
+        app.input_state.message = Some((format!("Process {} is a zombie with no parent left to reap it", pid), true));
+        app.input_state.message_timeout = Some(std::time::Instant::now() + Duration::from_secs(3));
+        return;
+    };
+    let processes = listed_processes(&app.process_manager, &app.rule_engine, &app.quick_filter, app.show_kernel_threads, app.running_only);
+    let parent_name = app
+        .process_manager
+        .get_processes()
+        .iter()
+        .find(|p| p.pid == parent)
+        .map_or_else(|| "unknown".to_string(), |p| p.name.clone());
+    if let Some(index) = processes.iter().position(|p| p.pid == parent) {
+        app.selected_process_index = index;
+        scroll_into_view(&mut app.scroll_offset, app.display_limit, index);
+    }
+    app.pending_kill_action = Some((action, KillTarget::ZombieParent(parent)));
+    app.kill_stop_input_state = KillStopInputState::ConfirmingAction;
+    app.input_state.message = Some((
+        format!(
+            "Process {} is a zombie: it already exited and stays listed until its parent {} ({}) reaps it. Press y to send SIGCHLD to the parent, any other key to cancel. If that doesn't clear it, killing the parent hands the zombie to init",
+            pid, parent, parent_name
+        ),
+        false,
+    ));
+    app.input_state.message_timeout = None;
+}
+
+fn remind_zombie_parent(app: &mut App, parent: u32) {
+    let result = app.process_manager.remind_parent(parent);
+    let denied = result.as_ref().is_err_and(is_permission_denied);
+    app.input_state.message = Some(match result {
+        Ok(()) => (format!("Sent SIGCHLD to parent process {}; the zombie goes away once it is reaped", parent), false),
+        Err(e) => (format!("Error sending SIGCHLD to parent process {}: {}", parent, e), true),
+    });
+    app.log_message();
+    app.input_state.message_timeout = Some(std::time::Instant::now() + Duration::from_secs(3));
+    if denied {
+        offer_sudo(app);
+    }
+}
+
+fn push_nice_history(history: &mut Vec<(u32, i32)>, pid: u32, previous: i32) {
+    history.push((pid, previous));
+    if history.len() > NICE_HISTORY_LIMIT {
+        history.remove(0);
+    }
+}
+
+// "OOM: 12 (adj 0)" for the Details panels; the kernel recomputes the score continuously
+fn oom_label(proc_root: &std::path::Path, pid: u32) -> String {
+    let show = |value: Option<i32>| value.map_or("-".to_string(), |value| value.to_string());
+    let (score, adj) = process::read_oom_score(proc_root, pid);
+    format!("OOM: {} (adj {})", show(score), show(adj))
+}
+
+fn is_permission_denied(error: &std::io::Error) -> bool {
+    error.kind() == std::io::ErrorKind::PermissionDenied
+}
+
+// After an action was refused for lack of privileges, offer to restart under sudo on the message line
+fn offer_sudo(app: &mut App) {
+    if crate::util::is_root() {
+        return;
+    }
+    app.sudo_offered = true;
+    let key = app.keymap.label(Action::RestartWithSudo);
+    if let Some((text, _)) = &mut app.input_state.message {
+        text.push_str(&format!(" - press {} in the process list to restart with sudo", key));
+    }
+    app.input_state.message_timeout = Some(std::time::Instant::now() + Duration::from_secs(5));
+}
+
+// Back to the process list from the kill or nice menu. A pending sudo offer stays on the message
+// line, since its key only works in the list.
+fn close_menu(app: &mut App) {
+    let menu_input = std::mem::take(&mut app.input_state);
+    app.view_mode = ViewMode::ProcessList;
+    if app.sudo_offered {
+        app.input_state.message = menu_input.message;
+        app.input_state.message_timeout = menu_input.message_timeout;
+    }
+}
+
+// Signal every process with a given name for the kill menu's by-name mode
+fn run_signal_by_name(app: &mut App, action: Action, name: &str) {
+    let signal = match action {
+        Action::Kill => libc::SIGKILL,
+        Action::Stop => libc::SIGSTOP,
+        Action::Continue => libc::SIGCONT,
+        _ => libc::SIGTERM,
+    };
+    let summary = app.process_manager.kill_by_name(name, signal);
+    let message = if summary.succeeded == 0 && summary.failed.is_empty() {
+        (format!("No processes named '{}'", name), true)
+    } else {
+        (
+            format!("{} processes named '{}': {}", signal_verb(action), name, summary.describe()),
+            !summary.failed.is_empty(),
+        )
+    };
+    app.input_state.message = Some(message);
+    app.input_state.message_timeout = Some(std::time::Instant::now() + Duration::from_secs(3));
+    app.log_message();
+    if summary.permission_denied() {
+        offer_sudo(app);
+    }
+}
+
+fn signal_verb(action: Action) -> &'static str {
+    match action {
+        Action::Kill => "kill",
+        Action::Stop => "stop",
+        Action::Continue => "continue",
+        _ => "terminate",
+    }
+}
+
+// Edit the process name for a by-name operation. Returns Some(Some(name)) once a name is
+// entered, Some(None) if cancelled, and None while still typing.
+fn handle_name_input(key: KeyEvent, app: &mut App) -> Option<Option<String>> {
+    match key.code {
+        KeyCode::Char(c) => app.input_state.name_input.push(c),
+        KeyCode::Backspace => {
+            app.input_state.name_input.pop();
+        }
+        KeyCode::Esc => return Some(None),
+        KeyCode::Enter => {
+            let name = app.input_state.name_input.trim().to_string();
+            if name.is_empty() {
+                return Some(None);
+            }
+            if app.process_manager.pids_named(&name).is_empty() {
+                app.input_state.message = Some((format!("No processes named '{}'", name), true));
+                return Some(None);
+            }
+            return Some(Some(name));
+        }
+        _ => {}
+    }
+    None
+}
+
+fn handle_change_nice_input(key: KeyEvent, app: &mut App) -> Result<bool, Box<dyn Error>> {
+    let processes = listed_processes(&app.process_manager, &app.rule_engine, &app.quick_filter, app.show_kernel_threads, app.running_only);
+    match app.nice_input_state {
+        NiceInputState::SelectingPid => {
+            match key.code {
+                KeyCode::Enter
+                    if !processes.is_empty() => {
+                        app.nice_input_state = NiceInputState::EnteringNice;
+                        app.input_state.nice_input.clear();
+                        app.input_state.message = None;
+                    }
+                code if app.keymap.is(Action::ByName, code) => {
+                    app.nice_input_state = NiceInputState::EnteringName;
+                    app.input_state.name_input.clear();
+                    app.input_state.message = None;
+                }
+                code if app.keymap.is(Action::UndoNice, code) => match app.nice_history.pop() {
+                    Some((pid, previous)) => match app.process_manager.set_niceness(pid, previous) {
+                        Ok(()) => {
+                            let remaining = match app.nice_history.len() {
+                                0 => String::new(),
+                                n => format!(" ({} more to undo)", n),
+                            };
+                            app.input_state.message = Some((format!("Restored PID {} to nice {}{}", pid, previous, remaining), false));
+                            app.log_message();
+                            app.input_state.message_timeout = Some(std::time::Instant::now() + Duration::from_secs(3));
+                        }
+                        Err(e) => {
+                            // Keep it, so the undo can be retried with more privileges
+                            app.nice_history.push((pid, previous));
+                            app.input_state.message = Some((format!("Error restoring PID {} to nice {}: {}", pid, previous, e), true));
+                            app.log_message();
+                            if is_permission_denied(&e) {
+                                offer_sudo(app);
+                            }
+                        }
+                    },
+                    None => {
+                        app.input_state.message = Some(("No nice changes to undo".to_string(), true));
+                        app.input_state.message_timeout = Some(std::time::Instant::now() + Duration::from_secs(3));
+                    }
+                },
+                code if app.keymap.is(Action::OomScoreAdj, code) && !processes.is_empty() => {
+                    app.nice_input_state = NiceInputState::EnteringOomAdj;
+                    app.input_state.oom_input.clear();
+                    app.input_state.message = None;
+                }
+                KeyCode::Esc => {
+                    close_menu(app);
+                    app.nice_input_state = NiceInputState::SelectingPid;
+                }
+                code => {
+                    if let Some(action) = app.keymap.action(keymap::NAVIGATION_ACTIONS, code) {
+                        let (last, page) = (processes.len().saturating_sub(1), PROCESS_TABLE_HEIGHT - 2);
+                        scroll_by_action(action, &mut app.selected_process_index, page, last);
+                        scroll_into_view(&mut app.change_nice_scroll_offset, page, app.selected_process_index);
+                    }
+                }
+            }
+        }
+        NiceInputState::EnteringOomAdj => match key.code {
+            KeyCode::Char(c)
+                if (c.is_ascii_digit() || (c == '-' && app.input_state.oom_input.is_empty())) => {
+                    app.input_state.oom_input.push(c);
+                }
+            KeyCode::Backspace => {
+                app.input_state.oom_input.pop();
+            }
+            KeyCode::Enter => {
+                if let Some(proc) = processes.get(app.selected_process_index) {
+                    let pid = proc.pid;
+                    let result = match app.input_state.oom_input.parse::<i32>() {
+                        Ok(adj) if (-1000..=1000).contains(&adj) => app.process_manager.set_oom_score_adj(pid, adj).map(|_| adj),
+                        _ => Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, "oom_score_adj must be between -1000 and 1000")),
+                    };
+                    match result {
+                        Ok(adj) => {
+                            app.input_state.message = Some((format!("Set oom_score_adj of process {} to {}", pid, adj), false));
+                            app.log_message();
+                            app.input_state.message_timeout = Some(std::time::Instant::now() + Duration::from_secs(3));
+                        }
+                        Err(e) => {
+                            app.input_state.message = Some((format!("Error setting oom_score_adj of process {}: {}", pid, e), true));
+                            app.log_message();
+                            if is_permission_denied(&e) {
+                                offer_sudo(app);
+                            }
+                        }
+                    }
+                }
+                app.nice_input_state = NiceInputState::SelectingPid;
+                app.input_state.oom_input.clear();
+            }
+            KeyCode::Esc => {
+                app.nice_input_state = NiceInputState::SelectingPid;
+                app.input_state.oom_input.clear();
+            }
+            _ => {}
+        },
+        NiceInputState::EnteringName => {
+            if let Some(name) = handle_name_input(key, app) {
+                app.nice_input_state = if name.is_some() {
+                    app.input_state.nice_input.clear();
+                    NiceInputState::EnteringNice
+                } else {
+                    NiceInputState::SelectingPid
+                };
+                app.bulk_name = name;
+            }
+        }
+        NiceInputState::EnteringNice => {
+            // Stepping starts from the typed value, else from the selected process's current nice
+            let base = app.input_state.nice_input.parse::<i32>().ok().or(match app.bulk_name {
+                None => processes.get(app.selected_process_index).map(|p| p.nice),
+                Some(_) => None,
+            });
+            match key.code {
+                KeyCode::Char(c)
+                    if (c.is_ascii_digit() || (c == '-' && app.input_state.nice_input.is_empty())) => {
+                        app.input_state.nice_input.push(c);
+                    }
+                // '-' only steps once there is a value; on an empty input it starts a negative number
+                KeyCode::Left | KeyCode::Right | KeyCode::Char('+') | KeyCode::Char('-') => {
+                    let step = if matches!(key.code, KeyCode::Left | KeyCode::Char('-')) { -1 } else { 1 };
+                    let nice = (base.unwrap_or(0) + step).clamp(-20, 19);
+                    app.input_state.nice_input = nice.to_string();
+                }
+                KeyCode::Backspace => {
+                    app.input_state.nice_input.pop();
+                }
+                KeyCode::Enter if app.bulk_name.is_some() => {
+                    let name = app.bulk_name.take().unwrap_or_default();
+                    let mut denied = false;
+                    app.input_state.message = Some(match app.input_state.nice_input.parse::<i32>() {
+                        Ok(nice) if (-20..=19).contains(&nice) => {
+                            let previous: Vec<(u32, i32)> = app
+                                .process_manager
+                                .pids_named(&name)
+                                .into_iter()
+                                .filter_map(|pid| app.process_manager.get_processes().iter().find(|p| p.pid == pid).map(|p| (pid, p.nice)))
+                                .collect();
+                            let summary = app.process_manager.set_niceness_by_name(&name, nice);
+                            for (pid, before) in previous {
+                                if !summary.failed.iter().any(|(failed, _)| *failed == pid) {
+                                    push_nice_history(&mut app.nice_history, pid, before);
+                                }
+                            }
+                            denied = summary.permission_denied();
+                            (
+                                format!("Set nice {} on processes named '{}': {}", nice, name, summary.describe()),
+                                !summary.failed.is_empty(),
+                            )
+                        }
+                        _ => ("Error: Nice value must be between -20 and 19".to_string(), true),
+                    });
+                    app.input_state.message_timeout = Some(std::time::Instant::now() + Duration::from_secs(3));
+                    app.log_message();
+                    if denied {
+                        offer_sudo(app);
+                    }
+                    app.nice_input_state = NiceInputState::SelectingPid;
+                    app.input_state.nice_input.clear();
+                }
+                KeyCode::Enter => {
+                    if !app.input_state.nice_input.is_empty()
+                        && let (Some(proc), Ok(nice)) = (
+                            processes.get(app.selected_process_index),
+                            app.input_state.nice_input.parse::<i32>(),
+                        ) {
+                            if (-20..=19).contains(&nice) {
+                                match app.process_manager.set_niceness(proc.pid, nice) {
+                                    Ok(_) => {
+                                        push_nice_history(&mut app.nice_history, proc.pid, proc.nice);
+                                        app.input_state.message = Some((
+                                            format!("Successfully changed nice value of process {} to {}", proc.pid, nice),
+                                            false
+                                        ));
+                                        app.log_message();
+                                        app.input_state.message_timeout = Some(std::time::Instant::now() + Duration::from_secs(1));
+                                        app.nice_input_state = NiceInputState::SelectingPid;
                                         app.input_state.nice_input.clear();
                                     }
+                                    Err(e) => {
+                                        app.input_state.message = Some((
+                                            format!("Error changing nice value of process {}: {}", proc.pid, e),
+                                            true
+                                        ));
+                                        app.log_message();
+                                        app.nice_input_state = NiceInputState::SelectingPid;
+                                        app.input_state.nice_input.clear();
+                                        if is_permission_denied(&e) {
+                                            offer_sudo(app);
+                                        }
+                                    }
                                 }
                             } else {
                                 app.input_state.message = Some((
@@ -1554,11 +3369,11 @@ fn handle_change_nice_input(key: KeyEvent, app: &mut App) -> Result<bool, Box<dy
                                 app.input_state.nice_input.clear();
                             }
                         }
-                    }
                 }
                 KeyCode::Esc => {
                     app.nice_input_state = NiceInputState::SelectingPid;
                     app.input_state.nice_input.clear();
+                    app.bulk_name = None;
                 }
                 _ => {}
             }
@@ -1568,82 +3383,172 @@ fn handle_change_nice_input(key: KeyEvent, app: &mut App) -> Result<bool, Box<dy
 }
 
 fn handle_per_process_graph_input(key: KeyEvent, app: &mut App) -> Result<bool, Box<dyn Error>> {
-    let processes = app.process_manager.get_processes();
+    if app.graph_filter_active {
+        match key.code {
+            KeyCode::Esc => {
+                app.graph_filter_active = false;
+                app.graph_filter_input.clear();
+            }
+            KeyCode::Enter => app.graph_filter_active = false,
+            KeyCode::Backspace => {
+                app.graph_filter_input.pop();
+            }
+            KeyCode::Char(c) => app.graph_filter_input.push(c),
+            _ => return Ok(false),
+        }
+        app.selected_process_index = 0;
+        app.per_process_graph_scroll_offset = 0;
+        return Ok(false);
+    }
+    let processes = per_process_graph::selection_processes(&app.process_manager, &app.graph_filter_input);
+    // Processes may have exited since the last key press
+    app.selected_process_index = app.selected_process_index.min(processes.len().saturating_sub(1));
     match key.code {
-        KeyCode::Char('q') => {
+        KeyCode::Enter => {
+            if app.selected_process_for_graph.is_none()
+                && let Some(process) = processes.get(app.selected_process_index)
+            {
+                app.selected_process_for_graph = Some(process.pid);
+            }
+            return Ok(false);
+        }
+        KeyCode::Esc => {
+            if app.selected_process_for_graph.is_some() {
+                app.selected_process_for_graph = None;
+            } else {
+                app.view_mode = ViewMode::ProcessList;
+            }
+            return Ok(false);
+        }
+        _ => {}
+    }
+    let Some(action) = app.keymap.action(keymap::PER_PROCESS_GRAPH_ACTIONS, key.code) else {
+        return Ok(false);
+    };
+    match action {
+        Action::Quit => {
             app.view_mode = ViewMode::ProcessList;
             app.selected_process_for_graph = None;
-            Ok(true)
         }
-        KeyCode::Left => {
-            // Switch to previous process
-            if let Some(pid) = app.selected_process_for_graph {
-                if let Some(idx) = processes.iter().position(|p| p.pid == pid) {
-                    if idx > 0 {
-                        app.selected_process_for_graph = Some(processes[idx - 1].pid);
-                    }
-                }
+        Action::PreviousProcess => {
+            if let Some(pid) = app.selected_process_for_graph
+                && let Some(idx) = processes.iter().position(|p| p.pid == pid)
+                && idx > 0
+            {
+                app.selected_process_for_graph = Some(processes[idx - 1].pid);
             }
-            Ok(false)
         }
-        KeyCode::Right => {
-            // Switch to next process
-            if let Some(pid) = app.selected_process_for_graph {
-                if let Some(idx) = processes.iter().position(|p| p.pid == pid) {
-                    if idx + 1 < processes.len() {
-                        app.selected_process_for_graph = Some(processes[idx + 1].pid);
-                    }
-                }
+        Action::NextProcess => {
+            if let Some(pid) = app.selected_process_for_graph
+                && let Some(idx) = processes.iter().position(|p| p.pid == pid)
+                && idx + 1 < processes.len()
+            {
+                app.selected_process_for_graph = Some(processes[idx + 1].pid);
             }
-            Ok(false)
         }
-        KeyCode::Up => {
-            if let Some(_pid) = app.selected_process_for_graph {
-                app.selected_process_for_graph = None;
-            } else {
-                if app.selected_process_index > 0 {
-                    app.selected_process_index -= 1;
-                    if app.selected_process_index < app.per_process_graph_scroll_offset {
-                        app.per_process_graph_scroll_offset = app.selected_process_index;
-                    }
-                }
+        // From a graph, up and down go back to the selection list
+        Action::Up | Action::Down if app.selected_process_for_graph.is_some() => {
+            app.selected_process_for_graph = None;
+        }
+        Action::Up if app.selected_process_index > 0 => {
+            app.selected_process_index -= 1;
+            if app.selected_process_index < app.per_process_graph_scroll_offset {
+                app.per_process_graph_scroll_offset = app.selected_process_index;
             }
-            Ok(false)
         }
-        KeyCode::Down => {
-            if let Some(_pid) = app.selected_process_for_graph {
-                app.selected_process_for_graph = None;
-            } else {
-                let max_index = processes.len().saturating_sub(1);
-                if app.selected_process_index < max_index {
-                    app.selected_process_index += 1;
-                    if app.selected_process_index >= app.per_process_graph_scroll_offset + PROCESS_TABLE_HEIGHT - 2 {
-                        app.per_process_graph_scroll_offset = app.selected_process_index - (PROCESS_TABLE_HEIGHT - 3);
-                    }
+        Action::Down => {
+            let max_index = processes.len().saturating_sub(1);
+            if app.selected_process_index < max_index {
+                app.selected_process_index += 1;
+                if app.selected_process_index >= app.per_process_graph_scroll_offset + PROCESS_TABLE_HEIGHT - 2 {
+                    app.per_process_graph_scroll_offset = app.selected_process_index - (PROCESS_TABLE_HEIGHT - 3);
                 }
             }
-            Ok(false)
         }
-        KeyCode::Enter => {
-            if app.selected_process_for_graph.is_none() {
-                if let Some(process) = processes.get(app.selected_process_index) {
-                    app.selected_process_for_graph = Some(process.pid);
+        Action::ToggleCompare => {
+            // Add or remove the shown (or highlighted) process from the comparison set
+            let pid = app.selected_process_for_graph
+                .or_else(|| processes.get(app.selected_process_index).map(|p| p.pid));
+            if let Some(pid) = pid {
+                if let Some(pos) = app.graph_compare_pids.iter().position(|&p| p == pid) {
+                    app.graph_compare_pids.remove(pos);
+                } else if app.graph_compare_pids.len() + 1 < MAX_GRAPH_SERIES {
+                    app.graph_compare_pids.push(pid);
+                } else {
+                    app.input_state.message = Some((format!("At most {} processes can be compared", MAX_GRAPH_SERIES - 1), true));
+                    app.input_state.message_timeout = Some(std::time::Instant::now() + Duration::from_secs(3));
                 }
             }
-            Ok(false)
         }
-        KeyCode::Esc => {
-            if app.selected_process_for_graph.is_some() {
-                app.selected_process_for_graph = None;
-            } else {
-                app.view_mode = ViewMode::ProcessList;
+        Action::ClearCompare => app.graph_compare_pids.clear(),
+        Action::QuickFilter if app.selected_process_for_graph.is_none() => app.graph_filter_active = true,
+        Action::ZoomIn => app.graph_window_index = app.graph_window_index.saturating_sub(1),
+        Action::ZoomOut => app.graph_window_index = (app.graph_window_index + 1).min(GRAPH_WINDOWS_SECS.len() - 1),
+        Action::ToggleVirtualMemory => app.graph_virtual_memory = !app.graph_virtual_memory,
+        Action::ToggleOverlay => app.graph_overlay = !app.graph_overlay,
+        Action::ToggleSmaps if app.graph_smaps.is_some() => app.graph_smaps = None,
+        Action::ToggleSmaps => load_graph_smaps(app),
+        Action::CopyPid | Action::CopyCommand => {
+            if let Some(pid) = app.selected_process_for_graph
+                && let Some(process) = app.process_manager.find_process(pid)
+            {
+                let (cmdline, name) = (process.cmdline.clone(), process.name.clone());
+                copy_process_field(app, action, pid, &cmdline, &name);
+            }
+        }
+        Action::PageMaps | Action::PageSmaps | Action::PageStack => {
+            if let Some(pid) = app.selected_process_for_graph {
+                let file = match action {
+                    Action::PageMaps => "maps",
+                    Action::PageSmaps => "smaps",
+                    _ => "stack",
+                };
+                page_proc_file(app, pid, file);
             }
-            Ok(false)
         }
-        _ => Ok(false),
+        _ => {}
+    }
+    Ok(false)
+}
+
+// Re-read the memory map summary of the graphed process for the smaps panel
+fn load_graph_smaps(app: &mut App) {
+    let Some(pid) = app.selected_process_for_graph else { return };
+    app.graph_smaps = Some(process::read_smaps(app.process_manager.proc_root(), pid).map_err(|e| match e.kind() {
+        std::io::ErrorKind::PermissionDenied => "unavailable (other users' processes need root)".to_string(),
+        _ => format!("unavailable ({})", e),
+    }));
+}
+
+// Open /proc/<pid>/<file> in the pager. The file is read here rather than by the pager so that
+// permission problems end up on the message line instead of in an empty pager.
+fn page_proc_file(app: &mut App, pid: u32, file: &str) {
+    let path = app.process_manager.proc_root().join(pid.to_string()).join(file);
+    let result = match std::fs::read(&path) {
+        Ok(contents) => suspend_tui(|| crate::util::run_pager(&contents)).and_then(|result| result),
+        Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied && file == "stack" => {
+            Err(std::io::Error::new(e.kind(), "kernel stacks can only be read as root"))
+        }
+        Err(e) => Err(e),
+    };
+    app.redraw = true;
+    if let Err(e) = result {
+        app.input_state.message = Some((format!("Cannot show {}: {}", path.display(), e), true));
+        app.input_state.message_timeout = Some(std::time::Instant::now() + Duration::from_secs(3));
     }
 }
 
+// Give the terminal back to the user while `run` executes (an external pager or editor), then
+// take it over again. The caller should set `redraw` afterwards.
+fn suspend_tui<T>(run: impl FnOnce() -> T) -> std::io::Result<T> {
+    disable_raw_mode()?;
+    execute!(stdout(), LeaveAlternateScreen, DisableMouseCapture, crossterm::cursor::Show)?;
+    let result = run();
+    enable_raw_mode()?;
+    execute!(stdout(), EnterAlternateScreen, EnableMouseCapture)?;
+    Ok(result)
+}
+
 fn handle_script_input(key: KeyEvent, app: &mut App) -> Result<bool, Box<dyn Error>> {
     match key.code {
         KeyCode::Esc => {
@@ -1651,207 +3556,595 @@ fn handle_script_input(key: KeyEvent, app: &mut App) -> Result<bool, Box<dyn Err
         }
         KeyCode::Enter => {
             let rule = app.input_state.rule_input.trim().to_string();
+            let text = if rule.is_empty() { "Cleared the rule filter".to_string() } else { format!("Applied rule: {}", rule) };
             app.rule_engine.set_rule(rule);
-            app.process_manager.apply_rules(&mut app.rule_engine);
+            app.process_manager.apply_rules(&app.rule_engine);
+            app.log_action(&text, false);
             app.view_mode = ViewMode::ProcessList;
         }
         KeyCode::Char(c) => {
             app.input_state.rule_input.push(c);
+            update_rule_preview(app);
         }
         KeyCode::Backspace => {
             app.input_state.rule_input.pop();
+            update_rule_preview(app);
         }
         _ => {}
     }
     Ok(false)
 }
 
+// Count the current processes the rule being typed would keep, so a too broad or never matching
+// rule shows before it's applied
+fn update_rule_preview(app: &mut App) {
+    app.rule_preview = app
+        .rule_engine
+        .select(&app.input_state.rule_input, app.process_manager.get_processes())
+        .map(|matching| matching.len());
+}
 
-fn render_per_process_graph_tab(frame: &mut ratatui::Frame, area: Rect, app: &App) {
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(3),  // Title
-            Constraint::Length(5),  // Process info
-            Constraint::Min(0),     // Content
-            Constraint::Length(2),  // Help line
-        ])
-        .split(area);
+// Keybinding reference shown in the Help view, grouped by the view they apply to. Each entry
+// names the actions whose current keys are shown, plus any keys that can't be remapped.
+type HelpEntry = (&'static [Action], &'static str, &'static str);
 
-    // Title
-    let title = Paragraph::new("Per-Process Graph View")
-        .style(Style::default().fg(Color::White).add_modifier(Modifier::BOLD))
-        .alignment(Alignment::Center)
-        .block(Block::default().borders(Borders::ALL));
-    frame.render_widget(title, chunks[0]);
-
-    if let Some(pid) = app.selected_process_for_graph {
-        let processes = app.process_manager.get_processes();
-        if let Some(process) = processes.iter().find(|p| p.pid == pid) {
-            // Process info box
-            let info_lines = vec![
-                Line::from(vec![Span::styled(format!("Name: {}", process.name), Style::default().fg(Color::Green))]),
-                Line::from(vec![Span::styled(format!("PID: {}", process.pid), Style::default().fg(Color::Yellow)), Span::raw("  "), Span::styled(format!("User: {}", process.user.clone().unwrap_or_default()), Style::default().fg(Color::Magenta))]),
-                Line::from(vec![Span::styled(format!("PPID: {}", process.parent_pid.unwrap_or(0)), Style::default().fg(Color::Cyan)), Span::raw("  "), Span::styled(format!("Status: {}", process.status), Style::default().fg(Color::White))]),
-                Line::from(vec![Span::styled(format!("Start: {}", process.start_time_str), Style::default().fg(Color::White))]),
-            ];
-            let info_box = Paragraph::new(info_lines)
-                .block(Block::default().borders(Borders::ALL).title("Process Info"));
-            frame.render_widget(info_box, chunks[1]);
-
-            // Graphs
-            let graph_chunks = Layout::default()
-                .direction(Direction::Vertical)
-                .constraints([
-                    Constraint::Percentage(50),  // CPU Graph
-                    Constraint::Percentage(50),  // Memory Graph
-                ])
-                .split(chunks[2]);
-
-            if let Some((cpu_history, mem_history)) = app.graph_data.get_process_history(pid) {
-                // Live stats for CPU
-                let current_cpu = cpu_history.back().copied().unwrap_or(0.0);
-                let min_cpu = cpu_history.iter().cloned().fold(f32::INFINITY, f32::min);
-                let max_cpu = cpu_history.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
-                let avg_cpu = if !cpu_history.is_empty() {
-                    cpu_history.iter().sum::<f32>() / cpu_history.len() as f32
-                } else { 0.0 };
-                // CPU Graph
-                let cpu_data: Vec<(f64, f64)> = cpu_history.iter()
-                    .enumerate()
-                    .map(|(i, &usage)| (i as f64, usage as f64))
-                    .collect();
-                let cpu_dataset = Dataset::default()
-                    .name("CPU Usage")
-                    .marker(ratatui::symbols::Marker::Braille)
-                    .graph_type(GraphType::Line)
-                    .style(Style::default().fg(Color::Cyan))
-                    .data(&cpu_data);
-                let cpu_chart = Chart::new(vec![cpu_dataset])
-                    .block(Block::default()
-                        .title(format!("CPU Usage for {} (PID: {}) | Now: {:.1}%  Min: {:.1}%  Max: {:.1}%  Avg: {:.1}%", process.name, pid, current_cpu, min_cpu, max_cpu, avg_cpu))
-                        .borders(Borders::ALL))
-                    .x_axis(ratatui::widgets::Axis::default()
-                        .bounds([0.0, cpu_history.len() as f64])
-                        .labels(vec![]))
-                    .y_axis(ratatui::widgets::Axis::default()
-                        .bounds([0.0, 100.0])
-                        .labels(vec!["0%".into(), "50%".into(), "100%".into()]));
-                frame.render_widget(cpu_chart, graph_chunks[0]);
-
-                // Live stats for MEM
-                let current_mem = mem_history.back().copied().unwrap_or(0) as f64 / (1024.0 * 1024.0);
-                let min_mem = mem_history.iter().cloned().min().unwrap_or(0) as f64 / (1024.0 * 1024.0);
-                let max_mem = mem_history.iter().cloned().max().unwrap_or(0) as f64 / (1024.0 * 1024.0);
-                let avg_mem = if !mem_history.is_empty() {
-                    mem_history.iter().sum::<u64>() as f64 / mem_history.len() as f64 / (1024.0 * 1024.0)
-                } else { 0.0 };
-                let memory_data: Vec<(f64, f64)> = mem_history.iter()
-                    .enumerate()
-                    .map(|(i, &usage)| (i as f64, usage as f64 / (1024.0 * 1024.0)))
-                    .collect();
-                let max_memory = memory_data.iter()
-                    .map(|&(_, y)| y)
-                    .fold(0.0, f64::max)
-                    .max(1.0);
-                let memory_dataset = Dataset::default()
-                    .name("Memory Usage")
-                    .marker(ratatui::symbols::Marker::Braille)
-                    .graph_type(GraphType::Line)
-                    .style(Style::default().fg(Color::Green))
-                    .data(&memory_data);
-                let memory_chart = Chart::new(vec![memory_dataset])
-                    .block(Block::default()
-                        .title(format!("Memory Usage for {} (PID: {}) | Now: {:.2} MB  Min: {:.2} MB  Max: {:.2} MB  Avg: {:.2} MB", process.name, pid, current_mem, min_mem, max_mem, avg_mem))
-                        .borders(Borders::ALL))
-                    .x_axis(ratatui::widgets::Axis::default()
-                        .bounds([0.0, mem_history.len() as f64])
-                        .labels(vec![]))
-                    .y_axis(ratatui::widgets::Axis::default()
-                        .bounds([0.0, max_memory * 1.2])
-                        .labels(vec![
-                            "0 MB".into(),
-                            format!("{:.1} MB", max_memory / 2.0).into(),
-                            format!("{:.1} MB", max_memory).into(),
-                        ]));
-                frame.render_widget(memory_chart, graph_chunks[1]);
-            }
-        }
-        // Help line
-        let help = Paragraph::new("←/→: Next/Prev process  ↑/↓: Back to list  Enter: Select  Esc: Back  Q: Quit")
-            .style(Style::default().fg(Color::Gray))
-            .alignment(Alignment::Center)
-            .block(Block::default().borders(Borders::ALL));
-        frame.render_widget(help, chunks[3]);
-    } else {
-        // Show process selection list
-        let processes = app.process_manager.get_processes();
-        let headers = ["PID", "NAME", "CPU%", "MEM(MB)", "USER"];
-        let header_cells = headers
-            .iter()
-            .map(|h| Cell::from(*h).style(Style::default().fg(Color::White).add_modifier(Modifier::BOLD)));
-        let header = Row::new(header_cells)
-            .style(Style::default().bg(Color::Blue))
-            .height(1);
-        let rows: Vec<Row> = processes
+const HELP_SECTIONS: &[(&str, &[HelpEntry])] = &[
+    ("Process List", &[
+        (&[Action::Up, Action::Down], "", "Scroll one row"),
+        (&[Action::PageUp, Action::PageDown], "", "Scroll one page"),
+        (&[Action::Top, Action::Bottom], "", "Jump to top / bottom"),
+        (&[], "Mouse click / wheel", "Select a row / scroll"),
+        (&[Action::Pin], "", "Pin/unpin the selected process so the selection follows it"),
+        (&[Action::ColumnsMenu], "", "Choose and reorder columns"),
+        (&[Action::Environment], "", "Show the environment variables of the selected process"),
+        (&[Action::ToggleKernelThreads], "", "Show/hide kernel threads (hidden by default)"),
+        (&[Action::ToggleRunningOnly], "", "Show only running processes, and back"),
+        (&[Action::QuickFilter], "", "Quick filter by name/cmdline (Enter keeps, Esc clears)"),
+        (&[Action::CpuScale], "", "Show CPU% as a share of one core (can exceed 100%) or of the whole machine"),
+        (&[Action::Highlight], "", "Highlight rows matching a name/cmdline search, dimming the rest (Esc clears)"),
+        (&[Action::NextMatch, Action::PreviousMatch], "", "Select the next / previous highlighted match"),
+        (&[Action::ResetView], "", "Reset the view: configured sort, no filters, rule, quick filter or highlight"),
+        (&[Action::Compact], "", "Compact mode: drop the borders and key menu to fit more processes"),
+        (&[Action::JumpToPid], "", "Jump to a PID (offers to clear filters that hide it)"),
+        (&[Action::GroupByName], "", "Group processes by name or user with summed CPU and memory"),
+        (&[Action::Snapshot], "", "Take a snapshot; from the second one on, show what changed since the previous"),
+        (&[Action::Sockets], "", "Show the TCP/UDP sockets of the selected process: listening ports and connections"),
+        (&[Action::Watch], "", "Watch the selected process: a banner with its uptime and peaks shows when it exits"),
+        (&[Action::CopyPid, Action::CopyCommand], "", "Copy the selected PID / command line to the clipboard (shown in the message line without one)"),
+        (&[Action::ToggleSortOrder], "", "Toggle ascending/descending sort"),
+        (&[Action::SortByCpu, Action::SortByMemory], "", "Sort by CPU / memory, highest first, and jump to the top"),
+        (&[Action::FilterSortMenu], "", "Filter/Sort menu, script rules (x) and alert rules (a)"),
+        (&[Action::ChangeNiceMenu], "", "Change nice value"),
+        (&[Action::KillStopMenu], "", "Kill/Stop menu"),
+        (&[Action::PerProcessGraph], "", "Per-process graph"),
+        (&[Action::ProcessLog], "", "Process exit log"),
+        (&[Action::Help], "", "This help screen"),
+        (&[Action::Statistics], "", "Statistics dashboard"),
+        (&[Action::RestartWithSudo], "", "After a permission error: restart under sudo with the same arguments"),
+        (&[Action::Quit], "", "Quit"),
+    ]),
+    ("Kill/Stop and Change Nice", &[
+        (&[Action::Up, Action::Down], "", "Move selection"),
+        (&[Action::Top, Action::Bottom], "", "Select first / last process"),
+        (&[], "Enter", "Act on the selected process"),
+        (&[Action::Kill, Action::Stop, Action::Continue, Action::Terminate], "", "Kill, stop, continue or terminate it"),
+        (&[Action::TerminateThenKill], "", "Terminate, then kill if still running after the grace period"),
+        (&[Action::KillTree], "", "Kill the process and all of its descendants"),
+        (&[Action::StopTree, Action::ContinueTree], "", "Suspend / resume the process and all of its descendants"),
+        (&[Action::Restart], "", "Restart: terminate, wait for exit, relaunch with the same command line; shows the new PID"),
+        (&[Action::ByName], "", "Kill/stop or renice every process with a given name"),
+        (&[Action::ByPort], "", "Kill menu: select the process listening on a TCP/UDP port (\"address already in use\")"),
+        (&[], "←/→, -/+", "While entering a nice value: step it down / up by one"),
+        (&[Action::UndoNice], "", "Nice menu: undo the last nice change, restoring the previous value"),
+        (&[Action::OomScoreAdj], "", "Nice menu: set the process's oom_score_adj (-1000 to 1000) to protect or sacrifice it under memory pressure"),
+        (&[], "Esc", "Cancel and return"),
+    ]),
+    ("Columns", &[
+        (&[Action::Up, Action::Down], "", "Move the cursor"),
+        (&[Action::ToggleColumn], "", "Show or hide the column"),
+        (&[Action::MoveColumnUp, Action::MoveColumnDown], "", "Move the column up / down"),
+        (&[Action::Quit], "Esc", "Save to the config file and return"),
+    ]),
+    ("Per-Process Graph", &[
+        (&[Action::Up, Action::Down], "Enter", "Choose a process to graph; from a graph, go back to the list"),
+        (&[Action::QuickFilter], "", "Search the list by name or PID (Enter keeps, Esc clears)"),
+        (&[Action::PreviousProcess, Action::NextProcess], "", "Previous / next process"),
+        (&[Action::ToggleCompare], "", "Add or remove the process from the comparison overlay (up to 4 others)"),
+        (&[Action::ClearCompare], "", "Clear the comparison overlay"),
+        (&[Action::ZoomIn, Action::ZoomOut], "", "Zoom the time window in / out (15s, 30s, 1m, 5m)"),
+        (&[Action::ToggleVirtualMemory], "", "Chart virtual size instead of resident memory, and back"),
+        (&[Action::ToggleOverlay], "", "Overlay CPU and memory on one chart with two scales, and back"),
+        (&[Action::ToggleSmaps], "", "Show or hide the memory map panel: RSS, PSS, private dirty, shared and swap"),
+        (&[Action::CopyPid, Action::CopyCommand], "", "Copy the PID / command line to the clipboard"),
+        (&[Action::PageMaps, Action::PageSmaps, Action::PageStack], "", "Open the process's maps / smaps / kernel stack in $PAGER (default less)"),
+        (&[], "Esc", "Back to the list from a graph, else to the process list"),
+        (&[Action::Quit], "", "Back to the process list"),
+    ]),
+    ("Process Log", &[
+        (&[Action::Up, Action::Down], "", "Scroll one row"),
+        (&[Action::PageUp, Action::PageDown], "", "Scroll one page"),
+        (&[Action::Bottom], "Home", "Jump to top / bottom"),
+        (&[Action::QuickFilter], "", "Search/filter the log"),
+        (&[Action::GroupLog, Action::UngroupLog], "", "Cycle grouping / ungroup"),
+        (&[Action::CycleLogSort, Action::ToggleSortOrder], "", "Cycle sort column / toggle order"),
+        (&[Action::ClearLog], "", "Clear the log"),
+        (&[Action::Quit], "Esc", "Back"),
+    ]),
+    ("Environment View", &[
+        (&[Action::RevealSecrets], "", "Show or hide the values of variables that look like secrets"),
+        (&[Action::Up, Action::Down, Action::PageUp, Action::PageDown], "", "Scroll"),
+        (&[Action::Quit], "Esc", "Back"),
+    ]),
+    ("Sockets View", &[
+        (&[Action::Reload], "", "Reload the socket list"),
+        (&[Action::Up, Action::Down, Action::PageUp, Action::PageDown], "", "Scroll"),
+        (&[Action::Quit], "Esc", "Back"),
+    ]),
+    ("Grouped View", &[
+        (&[Action::ToggleGroupBy], "", "Group by process name or by user"),
+        (&[Action::GroupSortCpu, Action::GroupSortMemory, Action::GroupSortCount], "", "Sort by total CPU, total memory or process count"),
+        (&[Action::Quit], "Esc", "Back"),
+    ]),
+    ("Statistics", &[
+        (
+            &[
+                Action::GraphsTab,
+                Action::OverviewTab,
+                Action::CpuTab,
+                Action::MemoryTab,
+                Action::DiskTab,
+                Action::ProcessesTab,
+                Action::AdvancedTab,
+                Action::GpuTab,
+                Action::BatteryTab,
+            ],
+            "click label",
+            "Switch tabs (the last is the battery, laptops only)",
+        ),
+        (&[Action::Up, Action::Down, Action::PageUp, Action::PageDown, Action::Top, Action::Bottom], "", "Scroll the Overview, CPU and Advanced tabs"),
+        (&[Action::ExportGraphs], "", "Export the CPU and memory history to a CSV file in the working directory"),
+        (&[Action::MaximizeCpuGraph, Action::MaximizeMemoryGraph], "", "Graphs tab: maximize the CPU / memory chart; the same key restores the layout"),
+        (&[Action::Statistics, Action::Quit], "Esc", "Back"),
+    ]),
+];
+
+// The keys of a help entry as currently bound: fixed keys first, then each action's keys
+fn help_keys(keymap: &KeyMap, actions: &[Action], fixed: &str) -> String {
+    (!fixed.is_empty())
+        .then(|| fixed.to_string())
+        .into_iter()
+        .chain(actions.iter().map(|action| keymap.keys(*action)))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+// What the process list colors mean, drawn in the theme's own styles and with the configured
+// thresholds so it can't drift from the coloring itself
+fn color_legend(theme: &Theme) -> Vec<Line<'static>> {
+    let (warn, crit) = (theme.usage_warn, theme.usage_crit);
+    let label = |text: &'static str| Span::styled(format!("  {:<26}", text), Style::default().fg(Color::Yellow));
+    let states = [
+        ("Running", ProcessState::Running),
+        ("Sleeping/Idle", ProcessState::Sleeping),
+        ("Disk Sleep", ProcessState::DiskSleep),
+        ("Stopped", ProcessState::Stopped),
+        ("Zombie", ProcessState::Zombie),
+    ];
+    let mut status = vec![label("STATUS")];
+    for (name, state) in states {
+        status.push(Span::styled(name, theme.status(state)));
+        status.push(Span::raw("  "));
+    }
+    vec![
+        Line::from(vec![Span::styled("Colors", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))]),
+        Line::from(vec![
+            label("CPU% (share of one core)"),
+            Span::styled(format!("up to {}%", warn), theme.cpu(warn)),
+            Span::raw("  "),
+            Span::styled(format!("above {}%", warn), theme.cpu_mid),
+            Span::raw("  "),
+            Span::styled(format!("above {}%", crit), theme.cpu_high),
+            Span::styled("  (set in [thresholds])", Style::default().fg(Color::Gray)),
+        ]),
+        Line::from(status),
+        Line::from(vec![
+            label("Rows"),
+            Span::styled("started in the last few refreshes", theme.new_process),
+            Span::raw("  "),
+            Span::styled("alerting", theme.message_error.add_modifier(Modifier::BOLD)),
+            Span::raw("  "),
+            Span::styled("highlight match", theme.search_match),
+            Span::raw("  "),
+            Span::styled("*PID pinned", theme.row(0)),
+        ]),
+    ]
+}
+
+fn draw_help(f: &mut Frame, app: &App) {
+    let mut lines = vec![
+        Line::from(vec![Span::styled("Help & Documentation", Style::default().fg(Color::White).add_modifier(Modifier::BOLD))]),
+        Line::from(vec![Span::styled("The current keys are listed; remap them in the [keys] table of the config file.", Style::default().fg(Color::Gray))]),
+        Line::from(""),
+        Line::from(vec![Span::styled("Recent Actions", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))]),
+    ];
+    if app.action_log.is_empty() {
+        lines.push(Line::from(vec![Span::styled("  None yet this session", Style::default().fg(Color::DarkGray))]));
+    }
+    for (time, text, is_error) in app.action_log.iter().rev() {
+        lines.push(Line::from(vec![
+            Span::styled(format!("  {}  ", time.format("%H:%M:%S")), Style::default().fg(Color::Gray)),
+            Span::styled(text.clone(), app.theme.message(*is_error)),
+        ]));
+    }
+    lines.push(Line::from(""));
+    lines.extend(color_legend(&app.theme));
+    for (section, bindings) in HELP_SECTIONS {
+        lines.push(Line::from(""));
+        lines.push(Line::from(vec![Span::styled(*section, Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))]));
+        for (actions, fixed, description) in bindings.iter() {
+            lines.push(Line::from(vec![
+                Span::styled(format!("  {:<26} ", help_keys(&app.keymap, actions, fixed)), Style::default().fg(Color::Yellow)),
+                Span::styled(*description, Style::default().fg(Color::Gray)),
+            ]));
+        }
+    }
+    let widget = Paragraph::new(lines)
+        .scroll((app.help_scroll_offset as u16, 0))
+        .block(Block::default().borders(Borders::ALL).title(format!(
+            "Help ({}/{} to scroll, Esc/{} to return)",
+            app.keymap.keys(Action::Up),
+            app.keymap.keys(Action::Down),
+            app.keymap.label(Action::Quit)
+        )));
+    f.render_widget(widget, f.size());
+}
+
+fn draw_columns_menu(f: &mut Frame, app: &App) {
+    let items: Vec<ListItem> = app
+        .columns
+        .columns
+        .iter()
+        .enumerate()
+        .map(|(i, (column, shown))| {
+            let text = format!("[{}] {:<8} ({})", if *shown { "x" } else { " " }, column.header(app.cpu_of_total), column.key());
+            let style = if i == app.column_menu_index {
+                Style::default().fg(Color::Black).bg(Color::Cyan)
+            } else if *shown {
+                Style::default().fg(Color::White)
+            } else {
+                Style::default().fg(Color::DarkGray)
+            };
+            ListItem::new(text).style(style)
+        })
+        .collect();
+    let list = List::new(items).block(Block::default().borders(Borders::ALL).title(format!(
+        "Columns ([{}] show/hide, [{}/{}] move up/down, [Esc] return, saving any change)",
+        app.keymap.label(Action::ToggleColumn),
+        app.keymap.label(Action::MoveColumnUp),
+        app.keymap.label(Action::MoveColumnDown),
+    )));
+    f.render_widget(list, f.size());
+}
+
+fn draw_environment(f: &mut Frame, app: &App) {
+    let Some(view) = &app.environ_view else { return };
+    let lines: Vec<Line> = match &view.vars {
+        Ok(vars) if vars.is_empty() => vec![Line::from("The environment is empty.")],
+        Ok(vars) => vars
             .iter()
-            .skip(app.per_process_graph_scroll_offset)
-            .take(PROCESS_TABLE_HEIGHT - 2)
-            .enumerate()
-            .map(|(i, process)| {
-                let idx = app.per_process_graph_scroll_offset + i;
-                let highlight = idx == app.selected_process_index;
-                let style = if highlight {
-                    Style::default().fg(Color::Black).bg(Color::Yellow).add_modifier(Modifier::BOLD)
-                } else if i % 2 == 0 {
-                    Style::default().fg(Color::Cyan)
-                } else {
-                    Style::default().fg(Color::Blue)
-                };
-                let memory_mb = process.memory_usage / (1024 * 1024);
-                Row::new(vec![
-                    Cell::from(process.pid.to_string()).style(style),
-                    Cell::from(process.name.clone()).style(Style::default().fg(Color::Green)),
-                    Cell::from(format!("{:.1}%", process.cpu_usage)).style(style),
-                    Cell::from(format!("{}", memory_mb)).style(style),
-                    Cell::from(process.user.clone().unwrap_or_default()).style(Style::default().fg(Color::Magenta)),
+            .map(|(name, value)| {
+                let value = if !view.reveal_secrets && process::is_secret_name(name) { "********" } else { value.as_str() };
+                Line::from(vec![
+                    Span::styled(name.clone(), Style::default().fg(Color::Yellow)),
+                    Span::raw("="),
+                    Span::raw(value.to_string()),
                 ])
             })
-            .collect();
-        let table = Table::new(rows)
-            .header(header)
-            .block(Block::default().borders(Borders::ALL).title("Select a Process (↑↓ to move, Enter to select, Esc to return)"))
-            .widths(&[
-                Constraint::Length(8),   // PID
-                Constraint::Length(20),  // NAME
-                Constraint::Length(8),   // CPU%
-                Constraint::Length(10),  // MEM(MB)
-                Constraint::Length(12),  // USER
-            ]);
-        frame.render_widget(table, chunks[2]);
-        // Help line
-        let help = Paragraph::new("↑/↓: Move  Enter: Select  Esc: Back  Q: Quit")
-            .style(Style::default().fg(Color::Gray))
-            .alignment(Alignment::Center)
-            .block(Block::default().borders(Borders::ALL));
-        frame.render_widget(help, chunks[3]);
-    }
-}
-
-// fn render_help_tab(frame: &mut ratatui::Frame, area: Rect) {
-//     let text = vec![
-//         Line::from(vec![Span::styled("Help & Documentation", Style::default().fg(Color::White).add_modifier(Modifier::BOLD))]),
-//         Line::from(vec![Span::styled("Navigation:", Style::default().fg(Color::Cyan))]),
-//         Line::from(vec![Span::styled("↑/↓ - Scroll through processes", Style::default().fg(Color::Gray))]),
-//         Line::from(vec![Span::styled("1-6 - Switch between views", Style::default().fg(Color::Gray))]),
-//         Line::from(vec![Span::styled("S - Show statistics", Style::default().fg(Color::Gray))]),
-//         Line::from(vec![Span::styled("q - Quit", Style::default().fg(Color::Gray))]),
-//     ];
-//     let widget = Paragraph::new(text).block(Block::default().borders(Borders::ALL).title("Help"));
-//     frame.render_widget(widget, area);
-// }
-
-//draw_help
+            .collect(),
+        Err(e) => vec![Line::from(Span::styled(format!("Cannot read environment: {}", e), app.theme.message(true)))],
+    };
+    let count = view.vars.as_ref().map(|vars| vars.len()).unwrap_or(0);
+    let title = format!(
+        "Environment of {} ({}) - {} variables ([{}] {} secrets, {}/{} scroll, Esc return)",
+        view.pid,
+        view.name,
+        count,
+        app.keymap.label(Action::RevealSecrets),
+        if view.reveal_secrets { "mask" } else { "reveal" },
+        app.keymap.label(Action::Up),
+        app.keymap.label(Action::Down),
+    );
+    let widget = Paragraph::new(lines)
+        .scroll((view.scroll as u16, 0))
+        .block(Block::default().borders(Borders::ALL).title(title));
+    f.render_widget(widget, f.size());
+}
+
+fn handle_environment_input(key: KeyEvent, app: &mut App) -> Result<bool, Box<dyn Error>> {
+    let page = app.display_limit;
+    let Some(view) = &mut app.environ_view else {
+        app.view_mode = ViewMode::ProcessList;
+        return Ok(false);
+    };
+    let last = view.vars.as_ref().map(|vars| vars.len().saturating_sub(1)).unwrap_or(0);
+    let action = app.keymap.action(keymap::ENVIRONMENT_ACTIONS, key.code)
+        .or((key.code == KeyCode::Esc).then_some(Action::Quit));
+    match action {
+        Some(Action::Quit) => {
+            app.view_mode = ViewMode::ProcessList;
+            app.environ_view = None;
+        }
+        Some(Action::RevealSecrets) => view.reveal_secrets = !view.reveal_secrets,
+        Some(action) => scroll_by_action(action, &mut view.scroll, page, last),
+        None => {}
+    }
+    Ok(false)
+}
+
+fn draw_sockets(f: &mut Frame, app: &App) {
+    let Some(view) = &app.sockets_view else { return };
+    let title = format!(
+        "TCP/UDP sockets of {} ({}) - {} ([{}] reload, {}/{} scroll, Esc return)",
+        view.pid,
+        view.name,
+        view.sockets.as_ref().map_or("-".to_string(), |sockets| sockets.len().to_string()),
+        app.keymap.label(Action::Reload),
+        app.keymap.label(Action::Up),
+        app.keymap.label(Action::Down),
+    );
+    let block = Block::default().borders(Borders::ALL).title(title);
+    let sockets = match &view.sockets {
+        Ok(sockets) if !sockets.is_empty() => sockets,
+        Ok(_) => {
+            f.render_widget(Paragraph::new("The process has no TCP or UDP sockets open.").block(block), f.size());
+            return;
+        }
+        Err(e) => {
+            let text = Line::from(Span::styled(format!("Cannot list sockets: {}", e), app.theme.message(true)));
+            f.render_widget(Paragraph::new(text).block(block), f.size());
+            return;
+        }
+    };
+    let header = Row::new(["PROTO", "LOCAL", "REMOTE", "STATE"].map(|h| Cell::from(h).style(app.theme.header)));
+    let rows: Vec<Row> = sockets
+        .iter()
+        .skip(view.scroll)
+        .enumerate()
+        .map(|(i, socket)| {
+            // Unconnected sockets have no peer; show "*" like ss does
+            let remote = if socket.info.remote.port() == 0 { "*".to_string() } else { socket.info.remote.to_string() };
+            let style = if socket.is_listening() { app.theme.message_ok } else { app.theme.row(i) };
+            Row::new(vec![
+                Cell::from(socket.protocol.label()),
+                Cell::from(socket.info.local.to_string()),
+                Cell::from(remote),
+                Cell::from(socket.state_label()),
+            ])
+            .style(style)
+        })
+        .collect();
+    let widths = [Constraint::Length(6), Constraint::Length(47), Constraint::Length(47), Constraint::Length(11)];
+    let table = Table::new(rows).header(header).block(block).widths(&widths);
+    f.render_widget(table, f.size());
+}
+
+fn handle_sockets_input(key: KeyEvent, app: &mut App) -> Result<bool, Box<dyn Error>> {
+    let page = app.display_limit;
+    let Some(view) = &mut app.sockets_view else {
+        app.view_mode = ViewMode::ProcessList;
+        return Ok(false);
+    };
+    let last = view.sockets.as_ref().map(|sockets| sockets.len().saturating_sub(1)).unwrap_or(0);
+    let action = app.keymap.action(keymap::SOCKETS_ACTIONS, key.code)
+        .or((key.code == KeyCode::Esc).then_some(Action::Quit));
+    match action {
+        Some(Action::Quit) => {
+            app.view_mode = ViewMode::ProcessList;
+            app.sockets_view = None;
+        }
+        Some(Action::Reload) => *view = SocketsView::load(app.process_manager.proc_root(), view.pid, view.name.clone()),
+        Some(action) => scroll_by_action(action, &mut view.scroll, page, last),
+        None => {}
+    }
+    Ok(false)
+}
+
+fn draw_aggregated(f: &mut Frame, app: &App) {
+    let groups = app.process_manager.groups(app.group_by, app.group_sort, app.show_kernel_threads);
+    let (key_header, noun) = match app.group_by {
+        GroupBy::Name => ("NAME", "names"),
+        GroupBy::User => ("USER", "users"),
+    };
+    // Mark the sort column
+    let sorted = |title: &str, column: GroupSort| if app.group_sort == column { format!("{}▼", title) } else { title.to_string() };
+    let header = Row::new([
+        Cell::from(key_header),
+        Cell::from(sorted("COUNT", GroupSort::Count)),
+        Cell::from(sorted("CPU%", GroupSort::Cpu)),
+        Cell::from(sorted("MEM", GroupSort::Memory)),
+    ])
+    .style(app.theme.header);
+    let rows: Vec<Row> = groups
+        .iter()
+        .skip(app.aggregated_scroll)
+        .enumerate()
+        .map(|(i, group)| {
+            Row::new(vec![
+                Cell::from(ellipsize(&group.name, GROUP_KEY_WIDTH as usize)).style(match app.group_by {
+                    GroupBy::Name => app.theme.name,
+                    GroupBy::User => app.theme.user,
+                }),
+                Cell::from(group.count.to_string()),
+                Cell::from(format!("{:.1}", group.cpu_usage)).style(app.theme.cpu(group.cpu_usage)),
+                Cell::from(format_bytes(group.memory_usage, app.memory_unit)),
+            ])
+            .style(app.theme.row(i))
+        })
+        .collect();
+    let title = format!(
+        "Processes by {} - {} {} ({}: by name/user  {}/{}/{}: sort by CPU/memory/count  {}/{} scroll  Esc return)",
+        key_header.to_lowercase(),
+        groups.len(),
+        noun,
+        app.keymap.label(Action::ToggleGroupBy),
+        app.keymap.label(Action::GroupSortCpu),
+        app.keymap.label(Action::GroupSortMemory),
+        app.keymap.label(Action::GroupSortCount),
+        app.keymap.label(Action::Up),
+        app.keymap.label(Action::Down),
+    );
+    let table = Table::new(rows)
+        .header(header)
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .widths(&[Constraint::Length(GROUP_KEY_WIDTH), Constraint::Length(7), Constraint::Length(8), Constraint::Length(10)]);
+    f.render_widget(table, f.size());
+}
+
+fn handle_aggregated_input(key: KeyEvent, app: &mut App) -> Result<bool, Box<dyn Error>> {
+    let page = app.display_limit;
+    let last = app.process_manager.groups(app.group_by, app.group_sort, app.show_kernel_threads).len().saturating_sub(1);
+    let scroll = &mut app.aggregated_scroll;
+    let action = app.keymap.action(keymap::GROUPED_ACTIONS, key.code)
+        .or((key.code == KeyCode::Esc).then_some(Action::Quit));
+    match action {
+        Some(Action::Quit) => app.view_mode = ViewMode::ProcessList,
+        Some(Action::ToggleGroupBy) => {
+            app.group_by = match app.group_by {
+                GroupBy::Name => GroupBy::User,
+                GroupBy::User => GroupBy::Name,
+            };
+            *scroll = 0;
+        }
+        Some(Action::GroupSortCpu) => app.group_sort = GroupSort::Cpu,
+        Some(Action::GroupSortMemory) => app.group_sort = GroupSort::Memory,
+        Some(Action::GroupSortCount) => app.group_sort = GroupSort::Count,
+        Some(action) => scroll_by_action(action, scroll, page, last),
+        None => {}
+    }
+    Ok(false)
+}
+
+// Lines of the snapshot diff view, built from the two latest snapshots
+fn snapshot_diff_lines(app: &App) -> Vec<Line<'static>> {
+    let (Some(older), Some(newer)) = (app.snapshots.front(), app.snapshots.back()) else {
+        return vec![Line::from(format!("Take two snapshots with {} to compare them.", app.keymap.label(Action::Snapshot)))];
+    };
+    let diff = snapshot::diff(older, newer);
+    let heading = |text: String| Line::from(Span::styled(text, Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)));
+    let describe = |p: &process::ProcessInfo| {
+        format!("  {:>8} {:<20} CPU {:>6.1}%  MEM {}", p.pid, p.name, p.cpu_usage, format_bytes(p.memory_usage, app.memory_unit))
+    };
+
+    let mut lines = vec![heading(format!("Appeared ({})", diff.appeared.len()))];
+    lines.extend(diff.appeared.iter().map(|p| Line::from(Span::styled(describe(p), app.theme.message_ok))));
+    lines.push(Line::from(""));
+    lines.push(heading(format!("Disappeared ({})", diff.disappeared.len())));
+    lines.extend(diff.disappeared.iter().map(|p| Line::from(Span::styled(describe(p), app.theme.message_error))));
+    lines.push(Line::from(""));
+    lines.push(heading(format!("Changed ({})", diff.changed.len())));
+    lines.extend(diff.changed.iter().map(|c| {
+        Line::from(format!(
+            "  {:>8} {:<20} CPU {:>6.1}% -> {:>6.1}%  MEM {} -> {}",
+            c.after.pid,
+            c.after.name,
+            c.before.cpu_usage,
+            c.after.cpu_usage,
+            format_bytes(c.before.memory_usage, app.memory_unit),
+            format_bytes(c.after.memory_usage, app.memory_unit),
+        ))
+    }));
+    lines
+}
+
+fn draw_snapshot_diff(f: &mut Frame, app: &App) {
+    let title = match (app.snapshots.front(), app.snapshots.back()) {
+        (Some(older), Some(newer)) => format!(
+            "Snapshot {} ({}) -> {} ({}) ({}/{} scroll, {} new snapshot, Esc return)",
+            older.name,
+            older.taken_at.format("%H:%M:%S"),
+            newer.name,
+            newer.taken_at.format("%H:%M:%S"),
+            app.keymap.label(Action::Up),
+            app.keymap.label(Action::Down),
+            app.keymap.label(Action::Snapshot),
+        ),
+        _ => "Snapshot diff".to_string(),
+    };
+    let widget = Paragraph::new(snapshot_diff_lines(app))
+        .scroll((app.snapshot_diff_scroll as u16, 0))
+        .block(Block::default().borders(Borders::ALL).title(title));
+    f.render_widget(widget, f.size());
+}
+
+fn handle_snapshot_diff_input(key: KeyEvent, app: &mut App) -> Result<bool, Box<dyn Error>> {
+    let page = app.display_limit;
+    let last = snapshot_diff_lines(app).len().saturating_sub(1);
+    let scroll = &mut app.snapshot_diff_scroll;
+    let action = app.keymap.action(keymap::SNAPSHOT_DIFF_ACTIONS, key.code)
+        .or((key.code == KeyCode::Esc).then_some(Action::Quit));
+    match action {
+        Some(Action::Quit) => app.view_mode = ViewMode::ProcessList,
+        Some(Action::Snapshot) => {
+            app.view_mode = ViewMode::ProcessList;
+            return handle_process_list_input(key, app);
+        }
+        Some(action) => scroll_by_action(action, scroll, page, last),
+        None => {}
+    }
+    Ok(false)
+}
+
+fn handle_columns_input(key: KeyEvent, app: &mut App) -> Result<bool, Box<dyn Error>> {
+    let last = app.columns.columns.len().saturating_sub(1);
+    let action = app.keymap.action(keymap::COLUMNS_ACTIONS, key.code)
+        .or((key.code == KeyCode::Esc).then_some(Action::Quit));
+    match action {
+        Some(Action::Quit) => {
+            app.view_mode = ViewMode::ProcessList;
+            let keys = app.columns.visible_keys();
+            // Saving rewrites the whole config file, so leave it alone when nothing changed
+            if keys == app.columns_on_open {
+                return Ok(false);
+            }
+            let keys = keys.into_iter().map(toml::Value::String).collect();
+            app.input_state.message = Some(match config::save_value("columns", toml::Value::Array(keys)) {
+                Ok(path) => (format!("Columns saved: {} rewritten (comments in it are not kept)", path.display()), false),
+                Err(e) => (format!("Could not save columns: {}", e), true),
+            });
+            app.input_state.message_timeout = Some(std::time::Instant::now() + Duration::from_secs(3));
+        }
+        Some(Action::ToggleColumn) => app.columns.toggle(app.column_menu_index),
+        Some(Action::MoveColumnUp) if app.column_menu_index > 0 => {
+            app.columns.swap_with_next(app.column_menu_index - 1);
+            app.column_menu_index -= 1;
+        }
+        Some(Action::MoveColumnDown) if app.column_menu_index < last => {
+            app.columns.swap_with_next(app.column_menu_index);
+            app.column_menu_index += 1;
+        }
+        Some(action @ (Action::Up | Action::Down)) => scroll_by_action(action, &mut app.column_menu_index, 1, last),
+        _ => {}
+    }
+    Ok(false)
+}
+
+fn handle_help_input(key: KeyEvent, app: &mut App) -> Result<bool, Box<dyn Error>> {
+    let action = app.keymap.action(keymap::HELP_ACTIONS, key.code)
+        .or((key.code == KeyCode::Esc).then_some(Action::Quit));
+    match action {
+        Some(Action::Quit | Action::Help) => {
+            app.view_mode = ViewMode::ProcessList;
+            app.help_scroll_offset = 0;
+        }
+        Some(action) => {
+            let total_lines = 2 + HELP_SECTIONS.iter().map(|(_, bindings)| bindings.len() + 2).sum::<usize>();
+            scroll_by_action(action, &mut app.help_scroll_offset, app.display_limit, total_lines.saturating_sub(1));
+        }
+        None => {}
+    }
+    Ok(false)
+}
+
 
 fn handle_process_log_input(key: KeyEvent, app: &mut App) -> Result<bool, Box<dyn Error>> {
     // For robust scrolling, recalculate max_scroll based on current filtered log and a default height (e.g., 10)
@@ -1894,45 +4187,66 @@ fn handle_process_log_input(key: KeyEvent, app: &mut App) -> Result<bool, Box<dy
             _ => {}
         }
     } else {
-        match key.code {
-            KeyCode::Char('g') => {
+        let action = app.keymap.action(keymap::PROCESS_LOG_ACTIONS, key.code).or(match key.code {
+            KeyCode::Esc => Some(Action::Quit),
+            KeyCode::Home => Some(Action::Top),
+            _ => None,
+        });
+        match action {
+            Some(Action::GroupLog) => {
                 app.log_group_mode = match app.log_group_mode {
                     LogGroupMode::None => LogGroupMode::Name,
-                    LogGroupMode::Name => LogGroupMode::PPID,
-                    LogGroupMode::PPID => LogGroupMode::User,
+                    LogGroupMode::Name => LogGroupMode::Ppid,
+                    LogGroupMode::Ppid => LogGroupMode::User,
                     LogGroupMode::User => LogGroupMode::None,
                 };
                 app.log_scroll_offset = 0;
             }
-            KeyCode::Char('u') => {
+            Some(Action::UngroupLog) => {
                 app.log_group_mode = LogGroupMode::None;
                 app.log_scroll_offset = 0;
             }
-            KeyCode::Char('/') => {
+            Some(Action::ClearLog) => {
+                app.process_exit_log.clear();
+                app.log_scroll_offset = 0;
+            }
+            Some(Action::CycleLogSort) => {
+                app.log_sort_mode = app.log_sort_mode.next();
+                app.log_scroll_offset = 0;
+            }
+            Some(Action::ToggleSortOrder) => {
+                app.log_sort_ascending = !app.log_sort_ascending;
+                app.log_scroll_offset = 0;
+            }
+            Some(Action::QuickFilter) => {
                 app.log_filter_active = true;
                 app.log_filter_input.clear();
                 app.log_scroll_offset = 0;
             }
-            KeyCode::Esc | KeyCode::Char('q') => {
+            Some(Action::Quit) => {
                 app.view_mode = ViewMode::ProcessList;
                 app.log_filter_input.clear();
                 app.log_filter_active = false;
                 app.log_scroll_offset = 0;
             }
-            KeyCode::Up => {
-                app.log_scroll_offset = app.log_scroll_offset.saturating_sub(1).min(max_scroll);
-            }
-            KeyCode::Down => {
-                app.log_scroll_offset = (app.log_scroll_offset + 1).min(max_scroll);
-            }
-            KeyCode::PageUp => {
-                app.log_scroll_offset = app.log_scroll_offset.saturating_sub(log_height).min(max_scroll);
-            }
-            KeyCode::PageDown => {
-                app.log_scroll_offset = (app.log_scroll_offset + log_height).min(max_scroll);
+            Some(action @ (Action::Up | Action::Down | Action::PageUp | Action::PageDown | Action::Top | Action::Bottom)) => {
+                scroll_by_action(action, &mut app.log_scroll_offset, log_height, max_scroll);
             }
             _ => {}
         }
     }
     Ok(false)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn match_search_on_an_empty_list_finds_nothing() {
+        // Everything filtered out while the highlight search is still active
+        assert_eq!(find_match(&[], "bash", 0, true, true), None);
+        assert_eq!(find_match(&[], "bash", 3, true, false), None);
+        assert_eq!(find_match(&[], "bash", 0, false, false), None);
+    }
+}
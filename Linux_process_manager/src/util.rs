@@ -0,0 +1,205 @@
+//! Shared helpers
+// Small formatting and time utilities used by the process list, the exit log and the CLI, and
+// the privilege checks behind the sudo restart.
+
+use std::collections::VecDeque;
+
+/// Unit for memory sizes on screen, from the `memory_unit` config option.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum MemoryUnit {
+    Auto, // The largest unit that keeps the value at 1 or more
+    Kb,
+    Mb,
+    Gb,
+    Tb,
+}
+
+/// Names accepted by the `memory_unit` config option.
+pub const MEMORY_UNITS: &[&str] = &["auto", "KB", "MB", "GB", "TB"];
+
+impl MemoryUnit {
+    pub fn named(name: &str) -> Option<MemoryUnit> {
+        match name.to_ascii_uppercase().as_str() {
+            "AUTO" => Some(MemoryUnit::Auto),
+            "KB" => Some(MemoryUnit::Kb),
+            "MB" => Some(MemoryUnit::Mb),
+            "GB" => Some(MemoryUnit::Gb),
+            "TB" => Some(MemoryUnit::Tb),
+            _ => None,
+        }
+    }
+}
+
+/// How high a usage percentage is against the configured warning and critical thresholds.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum UsageLevel {
+    Normal,
+    Warning,
+    Critical,
+}
+
+/// Classify a usage percentage (CPU, memory, swap, ...) against the `[thresholds]` warning and
+/// critical values; a value above a threshold reaches it.
+pub fn usage_level(percent: f64, warn: f32, crit: f32) -> UsageLevel {
+    if percent > crit as f64 {
+        UsageLevel::Critical
+    } else if percent > warn as f64 {
+        UsageLevel::Warning
+    } else {
+        UsageLevel::Normal
+    }
+}
+
+/// Format a size in bytes with one decimal, e.g. "2.0MB" or "40.0GB", in `unit` (the
+/// `memory_unit` option) or auto-scaled from KB up to TB.
+pub fn format_bytes(bytes: u64, unit: MemoryUnit) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    let exponent = match unit {
+        MemoryUnit::Kb => 0,
+        MemoryUnit::Mb => 1,
+        MemoryUnit::Gb => 2,
+        MemoryUnit::Tb => 3,
+        MemoryUnit::Auto => {
+            let mut exponent = 0;
+            while exponent < UNITS.len() - 1 && bytes >= 1024u64.pow(exponent as u32 + 2) {
+                exponent += 1;
+            }
+            exponent
+        }
+    };
+    let value = bytes as f64 / 1024f64.powi(exponent as i32 + 1);
+    format!("{:.1}{}", value, UNITS[exponent])
+}
+
+/// `format_bytes` for values already in MB, such as chart data and axis bounds.
+pub fn format_megabytes(mb: f64, unit: MemoryUnit) -> String {
+    format_bytes((mb.max(0.0) * 1024.0 * 1024.0) as u64, unit)
+}
+
+/// Format a duration in seconds compactly, keeping the two most significant units
+/// (e.g. "2d 3h", "5h 12m", "14m", "42s").
+pub fn format_duration(secs: u64) -> String {
+    let days = secs / 86_400;
+    let hours = (secs % 86_400) / 3_600;
+    let minutes = (secs % 3_600) / 60;
+    if days > 0 {
+        format!("{}d {}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else if minutes > 0 {
+        format!("{}m", minutes)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+/// Cumulative CPU time like top's TIME+: "m:ss" under an hour, "h:mm:ss" after.
+pub fn format_cpu_time(secs: f64) -> String {
+    let secs = secs as u64;
+    let (hours, minutes, seconds) = (secs / 3_600, (secs % 3_600) / 60, secs % 60);
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{}:{:02}", minutes, seconds)
+    }
+}
+
+/// The last `width` samples of a 0-100% series as block characters, one per sample, right-aligned
+/// so the newest is at the end. Values above 100% (several cores) are drawn full.
+pub fn sparkline(values: &VecDeque<f32>, width: usize) -> String {
+    const LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let shown = values.len().min(width);
+    let mut line = " ".repeat(width - shown);
+    line.extend(values.iter().skip(values.len() - shown).map(|&value| {
+        let level = (value.clamp(0.0, 100.0) / 100.0 * (LEVELS.len() - 1) as f32).round() as usize;
+        LEVELS[level]
+    }));
+    line
+}
+
+/// Fit `text` into `width` characters, replacing the end with "…" when it's cut, so a clipped
+/// name can't pass for a complete one.
+pub fn ellipsize(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        return text.to_string();
+    }
+    let mut shortened: String = text.chars().take(width.saturating_sub(1)).collect();
+    if width > 0 {
+        shortened.push('…');
+    }
+    shortened
+}
+
+/// Display a path that may be unreadable as "-". Non-UTF-8 bytes are replaced rather than
+/// rejected, since paths come straight from /proc.
+pub fn path_or_dash(path: Option<&std::path::Path>) -> String {
+    path.map(|p| p.to_string_lossy().into_owned()).unwrap_or_else(|| "-".to_string())
+}
+
+/// Whether we run with root privileges, so signals and negative nice values to any process work.
+pub fn is_root() -> bool {
+    // SAFETY: geteuid has no preconditions and cannot fail
+    unsafe { libc::geteuid() == 0 }
+}
+
+/// Replace this process with `sudo <this binary> <same arguments>`. Only returns if that fails.
+/// The terminal must already be restored, since sudo may prompt for a password.
+pub fn reexec_with_sudo() -> std::io::Error {
+    use std::os::unix::process::CommandExt;
+    let exe = match std::env::current_exe() {
+        Ok(exe) => exe,
+        Err(e) => return e,
+    };
+    std::process::Command::new("sudo").arg("--").arg(exe).args(std::env::args_os().skip(1)).exec()
+}
+
+/// Show `text` in the user's $PAGER (default `less`) and wait for it to exit. $PAGER may carry
+/// arguments, e.g. "less -S". The caller must hand the terminal over first.
+pub fn run_pager(text: &[u8]) -> std::io::Result<()> {
+    use std::io::Write;
+    let pager = std::env::var("PAGER").ok().filter(|pager| !pager.trim().is_empty()).unwrap_or_else(|| "less".to_string());
+    let mut words = pager.split_whitespace();
+    let program = words.next().unwrap_or("less");
+    let mut child = std::process::Command::new(program).args(words).stdin(std::process::Stdio::piped()).spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // The pager may quit before reading everything; that's not an error
+        match stdin.write_all(text) {
+            Err(e) if e.kind() != std::io::ErrorKind::BrokenPipe => return Err(e),
+            _ => {}
+        }
+    }
+    child.wait()?;
+    Ok(())
+}
+
+/// Put `text` on the system clipboard. Fails without a clipboard (headless, over SSH) or when
+/// built without the `clipboard` feature.
+#[cfg(feature = "clipboard")]
+pub fn copy_to_clipboard(text: &str) -> Result<(), String> {
+    use std::sync::Mutex;
+    // On X11 the copied text is served by whoever owns the clipboard, so keep the handle alive
+    // for the whole run instead of dropping it after each copy
+    static CLIPBOARD: Mutex<Option<arboard::Clipboard>> = Mutex::new(None);
+    let mut clipboard = CLIPBOARD.lock().map_err(|_| "clipboard unavailable".to_string())?;
+    if clipboard.is_none() {
+        *clipboard = Some(arboard::Clipboard::new().map_err(|e| e.to_string())?);
+    }
+    match clipboard.as_mut() {
+        Some(clipboard) => clipboard.set_text(text).map_err(|e| e.to_string()),
+        None => Err("clipboard unavailable".to_string()),
+    }
+}
+
+#[cfg(not(feature = "clipboard"))]
+pub fn copy_to_clipboard(_text: &str) -> Result<(), String> {
+    Err("built without the clipboard feature".to_string())
+}
+
+/// Seconds elapsed since a Unix timestamp, clamped to zero for timestamps in the future.
+pub fn seconds_since(epoch_secs: u64) -> u64 {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    now.saturating_sub(epoch_secs)
+}